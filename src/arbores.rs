@@ -59,36 +59,36 @@ impl Arbores {
         let mut metadata = Vec::new();
 
         // ("id" . id)
-        metadata.push(Value::Cons(
-            Rc::new(Value::String("id".to_string())),
-            Rc::new(Value::Integer(id as i64)),
+        metadata.push(Value::cons(
+            Value::string("id"),
+            Value::Integer(id as i64),
         ));
 
         // ("description" . description)
         if let Some(desc) = &expr.description {
-            metadata.push(Value::Cons(
-                Rc::new(Value::String("description".to_string())),
-                Rc::new(Value::String(desc.clone())),
+            metadata.push(Value::cons(
+                Value::string("description"),
+                Value::string(desc.clone()),
             ));
         }
 
         // ("type" . type)
         if let Some(type_desc) = &expr.type_description {
-            metadata.push(Value::Cons(
-                Rc::new(Value::String("type".to_string())),
-                Rc::new(Value::String(type_desc.clone())),
+            metadata.push(Value::cons(
+                Value::string("type"),
+                Value::string(type_desc.clone()),
             ));
         }
 
         // ("symbol-names" . (list of symbols))
         if !expr.symbol_names.is_empty() {
             let symbols: Vec<Value> = expr.symbol_names.iter()
-                .map(|s| Value::String(s.clone()))
+                .map(|s| Value::string(s.clone()))
                 .collect();
             let symbols_list = Self::vec_to_list(symbols);
-            metadata.push(Value::Cons(
-                Rc::new(Value::String("symbol-names".to_string())),
-                Rc::new(symbols_list),
+            metadata.push(Value::cons(
+                Value::string("symbol-names"),
+                symbols_list,
             ));
         }
 
@@ -98,16 +98,16 @@ impl Arbores {
                 .map(|&id| Value::Integer(id as i64))
                 .collect();
             let deps_list = Self::vec_to_list(deps);
-            metadata.push(Value::Cons(
-                Rc::new(Value::String("dependencies".to_string())),
-                Rc::new(deps_list),
+            metadata.push(Value::cons(
+                Value::string("dependencies"),
+                deps_list,
             ));
         }
 
         // ("code" . code)
-        metadata.push(Value::Cons(
-            Rc::new(Value::String("code".to_string())),
-            expr.code,
+        metadata.push(Value::cons(
+            Value::string("code"),
+            (*expr.code).clone(),
         ));
 
         Ok(Self::vec_to_list(metadata))
@@ -150,28 +150,28 @@ impl Arbores {
                 let mut result_entry = Vec::new();
 
                 // ("id" . id)
-                result_entry.push(Value::Cons(
-                    Rc::new(Value::String("id".to_string())),
-                    Rc::new(Value::Integer(id as i64)),
+                result_entry.push(Value::cons(
+                    Value::string("id"),
+                    Value::Integer(id as i64),
                 ));
 
                 // ("symbol-names" . (list of symbols))
                 if !expr.symbol_names.is_empty() {
                     let symbols: Vec<Value> = expr.symbol_names.iter()
-                        .map(|s| Value::String(s.clone()))
+                        .map(|s| Value::string(s.clone()))
                         .collect();
                     let symbols_list = Self::vec_to_list(symbols);
-                    result_entry.push(Value::Cons(
-                        Rc::new(Value::String("symbol-names".to_string())),
-                        Rc::new(symbols_list),
+                    result_entry.push(Value::cons(
+                        Value::string("symbol-names"),
+                        symbols_list,
                     ));
                 }
 
                 // ("description" . description)
                 if let Some(desc) = &expr.description {
-                    result_entry.push(Value::Cons(
-                        Rc::new(Value::String("description".to_string())),
-                        Rc::new(Value::String(desc.clone())),
+                    result_entry.push(Value::cons(
+                        Value::string("description"),
+                        Value::string(desc.clone()),
                     ));
                 }
 
@@ -196,19 +196,19 @@ impl Arbores {
                     if desc.to_lowercase().contains(&query.to_lowercase()) {
                         let result_entry = vec![
                             // ("id" . id)
-                            Value::Cons(
-                                Rc::new(Value::String("id".to_string())),
-                                Rc::new(Value::Integer(id as i64)),
+                            Value::cons(
+                                Value::string("id"),
+                                Value::Integer(id as i64),
                             ),
                             // ("score" . score)
-                            Value::Cons(
-                                Rc::new(Value::String("score".to_string())),
-                                Rc::new(Value::Float(0.8)),
+                            Value::cons(
+                                Value::string("score"),
+                                Value::Float(0.8),
                             ),
                             // ("description" . description)
-                            Value::Cons(
-                                Rc::new(Value::String("description".to_string())),
-                                Rc::new(Value::String(desc.clone())),
+                            Value::cons(
+                                Value::string("description"),
+                                Value::string(desc.clone()),
                             ),
                         ];
 
@@ -250,7 +250,7 @@ impl Arbores {
     /// 将 Vec<Value> 转换为 Scheme 列表
     fn vec_to_list(items: Vec<Value>) -> Value {
         items.into_iter().rev().fold(Value::Nil, |acc, item| {
-            Value::Cons(Rc::new(item), Rc::new(acc))
+            Value::cons(item, acc)
         })
     }
 }
@@ -282,7 +282,7 @@ mod tests {
 
         // 测试获取元数据
         let metadata = arbores.get_metadata(id).unwrap();
-        assert!(matches!(metadata, Value::Cons(_, _)));
+        assert!(matches!(metadata, Value::Cons(_, _, _)));
 
         // 测试依赖查询
         let deps = arbores.get_dependencies(id).unwrap();
@@ -290,11 +290,11 @@ mod tests {
 
         // 测试符号搜索
         let search_results = arbores.search_by_symbol("fact", Some("prefix")).unwrap();
-        assert!(matches!(search_results, Value::Cons(_, _)));
+        assert!(matches!(search_results, Value::Cons(_, _, _)));
 
         // 测试语义搜索
         let semantic_results = arbores.semantic_search("阶乘").unwrap();
-        assert!(matches!(semantic_results, Value::Cons(_, _)));
+        assert!(matches!(semantic_results, Value::Cons(_, _, _)));
     }
 
     #[test]
@@ -320,11 +320,11 @@ mod tests {
 
         // 测试前缀匹配
         let prefix_results = arbores.search_by_symbol("test", Some("prefix")).unwrap();
-        assert!(matches!(prefix_results, Value::Cons(_, _)));
+        assert!(matches!(prefix_results, Value::Cons(_, _, _)));
 
         // 测试精确匹配
         let exact_results = arbores.search_by_symbol("test-func", Some("exact")).unwrap();
-        assert!(matches!(exact_results, Value::Cons(_, _)));
+        assert!(matches!(exact_results, Value::Cons(_, _, _)));
     }
 
     #[test]
@@ -352,6 +352,6 @@ mod tests {
         // 测试依赖查询
         let deps = arbores.get_dependencies(main_id).unwrap();
         // 应该返回包含 helper_id 的列表
-        assert!(matches!(deps, Value::Cons(_, _)));
+        assert!(matches!(deps, Value::Cons(_, _, _)));
     }
 }
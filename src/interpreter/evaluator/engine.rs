@@ -9,6 +9,7 @@ use std::rc::Rc;
 use gc::{Gc, Trace, Finalize};
 
 use crate::interpreter::{SExpr, SExprContent, Value};
+use crate::interpreter::parser::{parse_from_string, get_error_summary};
 use super::types::*;
 
 // ============================================================================
@@ -61,11 +62,13 @@ fn convert_sexpr_to_runtime_object(expr: Rc<SExpr>) -> Result<Gc<RuntimeObject>,
             }
         },
         SExprContent::Cons { car, cdr } => {
-            // 暂时不支持列表转换，返回错误
-            return Err(EvaluateError::NotImplemented {
-                expr: expr.clone(),
-                feature: "list conversion".to_string(),
-            });
+            RuntimeObject {
+                core: RuntimeObjectCore::Cons(MutableCons {
+                    car: convert_sexpr_to_runtime_object(car.clone())?,
+                    cdr: convert_sexpr_to_runtime_object(cdr.clone())?,
+                }),
+                source: Some(expr.clone()),
+            }
         },
         SExprContent::Vector(elements) => {
             // 暂时不支持向量转换，返回错误
@@ -93,11 +96,70 @@ fn convert_sexpr_to_runtime_object(expr: Rc<SExpr>) -> Result<Gc<RuntimeObject>,
 /// - 成功时返回求值结果的运行时对象
 /// - 失败时返回求值错误
 pub fn evaluate(expr: Rc<SExpr>, env: Gc<Environment>) -> Result<Rc<RuntimeObject>, EvaluateError> {
-    // 将 SExpr 转换为 RuntimeObject
+    run_to_completion(initial_state(expr, env)?, None)
+}
+
+/// 解析并依次求值源码中的所有表达式 - 对外接口
+/// 
+/// # 参数
+/// - `source`: 源代码字符串
+/// - `env`: 全局环境
+/// 
+/// # 返回值
+/// - 成功时返回每个顶层表达式的求值结果
+/// - 失败时返回渲染好的错误报告：解析错误见 `get_error_summary`，
+///   求值错误见 `render_evaluate_error`，均附带出错位置的源码片段
+pub fn evaluate_source(source: &str, env: Gc<Environment>) -> Result<Vec<Rc<RuntimeObject>>, String> {
+    let output = parse_from_string(source);
+    if let Some(summary) = get_error_summary(&output, source) {
+        return Err(summary);
+    }
+    let exprs = output.result.unwrap_or_default();
+    exprs.into_iter()
+        .map(|expr| evaluate(Rc::new(expr), env.clone()).map_err(|error| render_evaluate_error(source, &error)))
+        .collect()
+}
+
+/// 创建求值的初始状态 - 对外接口
+/// 
+/// 与 `evaluate_step` 配合，可由调用方逐步驱动求值（调试器、协作式调度等）
+/// 
+/// # 参数
+/// - `expr`: 要求值的 S 表达式
+/// - `env`: 全局环境
+/// 
+/// # 返回值
+/// - 以根续延为栈底、表达式处于尾位置的初始状态
+/// - 表达式无法转换为运行时对象时返回求值错误
+pub fn initial_state(expr: Rc<SExpr>, env: Gc<Environment>) -> Result<Rc<EvalState>, EvaluateError> {
     let runtime_expr = convert_sexpr_to_runtime_object(expr)?;
-    let mut current_state = Rc::new(init_eval_state(runtime_expr, env));
+    Ok(Rc::new(init_eval_state(runtime_expr, env)))
+}
+
+/// 从给定状态开始反复单步求值，直到完成或出错 - 对外接口
+/// 
+/// # 参数
+/// - `state`: 起始求值状态（可以是 `initial_state` 的结果，也可以是单步求值的中间状态）
+/// - `fuel`: 最多执行的单步求值次数，`None` 表示不限制；用于限制不可信脚本的运行时间
+/// 
+/// # 返回值
+/// - 成功时返回求值结果的运行时对象
+/// - 失败时返回求值错误；预算耗尽时返回 `EvaluateError::FuelExhausted`
+pub fn run_to_completion(state: Rc<EvalState>, fuel: Option<u64>) -> Result<Rc<RuntimeObject>, EvaluateError> {
+    let mut current_state = state;
+    let mut remaining = fuel;
     
     loop {
+        if let (Some(0), Some(budget)) = (remaining, fuel) {
+            return Err(EvaluateError::FuelExhausted {
+                expr: current_state.expr.source.clone().unwrap_or_else(|| {
+                    Rc::new(SExpr::without_span(SExprContent::Atom(Value::Symbol("unknown".to_string()))))
+                }),
+                budget,
+            });
+        }
+        remaining = remaining.map(|fuel| fuel - 1);
+
         match evaluate_step(current_state) {
             EvaluateResult::Completed(result) => return Ok(result),
             EvaluateResult::Continue(next_state) => {
@@ -110,6 +172,13 @@ pub fn evaluate(expr: Rc<SExpr>, env: Gc<Environment>) -> Result<Rc<RuntimeObjec
 
 /// 单步状态转移函数 - 对外接口
 /// 
+/// 每一步处理状态中的一个表达式：
+/// - 自求值表达式和变量引用：把值交给当前栈帧的续延
+/// - 列表表达式：压入 `FunctionEval` 续延的新栈帧，下一步求值运算符
+/// - 续延收到运算符的值后逐个求值参数（`ArgumentEval`），全部求值后应用函数，
+///   再把结果交给调用处栈帧的续延
+/// - 根续延收到值时求值完成
+/// 
 /// # 参数
 /// - `state`: 当前求值状态
 /// 
@@ -179,15 +248,23 @@ fn init_eval_state(expr: Gc<RuntimeObject>, env: Gc<Environment>) -> EvalState {
     )
 }
 
-/// 求值列表表达式（函数调用或特殊形式）
+/// 求值列表表达式（函数调用）：先在新栈帧中求值运算符，其续延负责求值参数
 fn evaluate_list_expression(state: Rc<EvalState>, cons: &MutableCons) -> EvaluateResult {
-    // 暂时不支持列表求值，返回错误
-    EvaluateResult::Error(EvaluateError::NotImplemented {
-        expr: state.expr.source.clone().unwrap_or_else(|| {
-            Rc::new(SExpr::without_span(SExprContent::Atom(Value::Symbol("list".to_string()))))
+    let operator_frame = Frame {
+        env: state.frame.env.clone(),
+        continuation: Gc::new(Continuation::FunctionEval {
+            frame: state.frame.clone(),
+            operands: cons.cdr.clone(),
         }),
-        feature: "list evaluation".to_string(),
-    })
+        parent: Some(state.frame.clone()),
+    };
+    
+    EvaluateResult::Continue(Rc::new(EvalState::new(
+        Gc::new(operator_frame),
+        cons.car.clone(),
+        TailContext::NonTailPosition,
+        None,
+    )))
 }
 
 /// 创建内置函数对象
@@ -213,3 +290,86 @@ fn create_builtin_function(name: &str) -> RuntimeObject {
         source: None,
     }
 }
+
+// ============================================================================
+// 测试
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_one(source: &str) -> Rc<SExpr> {
+        let exprs = parse_from_string(source).result.expect("parse error");
+        Rc::new(exprs.into_iter().next().expect("empty input"))
+    }
+
+    #[test]
+    fn test_step_addition_to_completion() {
+        let env = Gc::new(Environment::new());
+        let mut state = initial_state(parse_one("(+ 1 2)"), env.clone()).unwrap();
+        
+        // 求值运算符、两个参数，最后一个参数的值触发函数应用并完成
+        let mut steps = 0;
+        let result = loop {
+            steps += 1;
+            match evaluate_step(state) {
+                EvaluateResult::Continue(next) => state = next,
+                EvaluateResult::Completed(result) => break result,
+                EvaluateResult::Error(error) => panic!("unexpected error: {error:?}"),
+            }
+        };
+        assert_eq!(result.to_string(), "3");
+        assert_eq!(steps, 4);
+        
+        // run_to_completion 可以从任意中间状态继续
+        let state = initial_state(parse_one("(* (+ 1 2) 4)"), env.clone()).unwrap();
+        let next = match evaluate_step(state) {
+            EvaluateResult::Continue(next) => next,
+            other => panic!("unexpected result: {other:?}"),
+        };
+        assert_eq!(run_to_completion(next, None).unwrap().to_string(), "12");
+        
+        assert!(matches!(evaluate(parse_one("(1 2)"), env), Err(EvaluateError::NotCallable { .. })));
+    }
+
+    #[test]
+    fn test_render_type_mismatch_snippet() {
+        let env = Gc::new(Environment::new());
+        let source = "(+ 1 2)\n(+ 1 \"abc\")";
+        let error = match evaluate(parse_from_string(source).result.unwrap().remove(1).into(), env.clone()) {
+            Err(error @ EvaluateError::TypeMismatch { .. }) => error,
+            other => panic!("unexpected result: {other:?}"),
+        };
+        
+        // 错误消息之后是第二行源码，^ 覆盖出错的字符串字面量
+        let rendered = render_evaluate_error(source, &error);
+        assert!(rendered.starts_with("Type mismatch: expected number"), "unexpected rendering: {rendered}");
+        assert!(rendered.ends_with("  |\n2 | (+ 1 \"abc\")\n  |      ^^^^^"), "unexpected rendering: {rendered}");
+        
+        // evaluate_source 返回同样的报告，解析错误也附带片段
+        assert_eq!(evaluate_source(source, env.clone()).unwrap_err(), rendered);
+        assert!(evaluate_source("(+ 1))", env.clone()).unwrap_err().ends_with("1 | (+ 1))\n  |      ^"));
+        assert_eq!(evaluate_source("(+ 1 2) (* 2 3)", env).unwrap().iter().map(|v| v.to_string()).collect::<Vec<_>>(), ["3", "6"]);
+    }
+
+    #[test]
+    fn test_fuel_budget() {
+        let env = Gc::new(Environment::new());
+        let expr = parse_one("(+ 1 (* 2 3))");
+        
+        // 该表达式恰好需要 7 步
+        let result = run_to_completion(initial_state(expr.clone(), env.clone()).unwrap(), Some(7));
+        assert_eq!(result.unwrap().to_string(), "7");
+        
+        // 预算不足时停止，且报告所用预算
+        match run_to_completion(initial_state(expr.clone(), env.clone()).unwrap(), Some(6)) {
+            Err(EvaluateError::FuelExhausted { budget, .. }) => assert_eq!(budget, 6),
+            other => panic!("unexpected result: {other:?}"),
+        }
+        assert!(matches!(
+            run_to_completion(initial_state(expr, env).unwrap(), Some(0)),
+            Err(EvaluateError::FuelExhausted { budget: 0, .. })
+        ));
+    }
+}
@@ -3,7 +3,7 @@
 //! 支持 call/cc 的续延结构
 
 use gc::{Finalize, Gc, Trace};
-use super::{RuntimeObject, EvaluateResult, EvaluateError};
+use super::{RuntimeObject, RuntimeObjectCore, EvaluateResult, EvaluateError, EvalState, TailContext};
 
 /// 续延类型枚举 - 支持不同的续延实现
 #[derive(Clone, Trace, Finalize)]
@@ -72,21 +72,78 @@ impl Continuation {
     }
 }
 
-/// 求值函数参数
+/// 求值函数参数：逐个在新栈帧中求值剩余参数，全部求值后应用函数
 fn evaluate_arguments(
     frame: Gc<super::Frame>,
     function_value: Gc<RuntimeObject>,
     remaining_args: &Gc<RuntimeObject>,
     evaluated_args: Vec<Gc<RuntimeObject>>,
 ) -> EvaluateResult {
-    // 暂时不支持参数求值，返回错误
-    EvaluateResult::Error(EvaluateError::NotImplemented {
-        expr: function_value.source.clone().unwrap_or_else(|| {
-            std::rc::Rc::new(crate::interpreter::SExpr::without_span(
-                crate::interpreter::SExprContent::Atom(crate::interpreter::Value::Symbol("function".to_string()))
-            ))
+    match &remaining_args.core {
+        RuntimeObjectCore::Nil => apply_function(frame, function_value, evaluated_args),
+        RuntimeObjectCore::Cons(cons) => {
+            let argument_frame = super::Frame {
+                env: frame.env.clone(),
+                continuation: Gc::new(Continuation::ArgumentEval {
+                    frame: frame.clone(),
+                    function_value,
+                    remaining_args: cons.cdr.clone(),
+                    evaluated_args,
+                }),
+                parent: Some(frame.clone()),
+            };
+            EvaluateResult::Continue(std::rc::Rc::new(EvalState::new(
+                Gc::new(argument_frame),
+                cons.car.clone(),
+                TailContext::NonTailPosition,
+                None,
+            )))
+        },
+        _ => EvaluateResult::Error(EvaluateError::InvalidArgumentList {
+            expr: source_or_placeholder(remaining_args, "arguments"),
+            message: "argument list must be a proper list".to_string(),
+        }),
+    }
+}
+
+/// 应用函数，并将结果交给调用处栈帧的续延
+fn apply_function(
+    frame: Gc<super::Frame>,
+    function_value: Gc<RuntimeObject>,
+    args: Vec<Gc<RuntimeObject>>,
+) -> EvaluateResult {
+    match &function_value.core {
+        RuntimeObjectCore::BuiltinFunction(builtin) => {
+            if !builtin.matches_arity(args.len()) {
+                return EvaluateResult::Error(EvaluateError::ArgumentCountMismatch {
+                    expr: source_or_placeholder(&function_value, builtin.name()),
+                    expected: builtin.arity().description(),
+                    actual: args.len(),
+                });
+            }
+            let args: Vec<RuntimeObject> = args.iter().map(|arg| arg.as_ref().clone()).collect();
+            match builtin.call(&args) {
+                Ok(result) => frame.continuation.call(Gc::new(result)),
+                Err(error) => EvaluateResult::Error(error),
+            }
+        },
+        RuntimeObjectCore::Lambda(_) => EvaluateResult::Error(EvaluateError::NotImplemented {
+            expr: source_or_placeholder(&function_value, "lambda"),
+            feature: "lambda application".to_string(),
+        }),
+        _ => EvaluateResult::Error(EvaluateError::NotCallable {
+            expr: source_or_placeholder(&function_value, "function"),
+            value: function_value.to_string(),
         }),
-        feature: "argument evaluation".to_string(),
+    }
+}
+
+/// 取运行时对象的源表达式，没有时以符号占位
+fn source_or_placeholder(object: &RuntimeObject, placeholder: &str) -> std::rc::Rc<crate::interpreter::SExpr> {
+    object.source.clone().unwrap_or_else(|| {
+        std::rc::Rc::new(crate::interpreter::SExpr::without_span(
+            crate::interpreter::SExprContent::Atom(crate::interpreter::Value::Symbol(placeholder.to_string()))
+        ))
     })
 }
 
@@ -4,6 +4,7 @@
 
 use std::rc::Rc;
 use crate::interpreter::SExpr;
+use crate::interpreter::parser::render_snippet;
 
 /// 求值错误类型 - 表示求值过程中可能出现的各种错误
 #[derive(Debug, Clone, PartialEq)]
@@ -97,6 +98,57 @@ pub enum EvaluateError {
         expr: Rc<SExpr>,
         feature: String,
     },
+    /// 单步求值次数超出预算（expr 为预算耗尽时待求值的表达式）
+    FuelExhausted {
+        expr: Rc<SExpr>,
+        budget: u64,
+    },
+}
+
+impl EvaluateError {
+    /// 出错的表达式（其 span 即错误在源码中的位置）
+    pub fn expr(&self) -> &Rc<SExpr> {
+        match self {
+            EvaluateError::InvalidQuoteSyntax { expr, .. }
+            | EvaluateError::InvalidIfSyntax { expr, .. }
+            | EvaluateError::InvalidLambdaSyntax { expr, .. }
+            | EvaluateError::InvalidDefineSyntax { expr, .. }
+            | EvaluateError::InvalidLetSyntax { expr, .. }
+            | EvaluateError::InvalidLetBinding { expr, .. }
+            | EvaluateError::InvalidParameterName { expr, .. }
+            | EvaluateError::InvalidParameterList { expr, .. }
+            | EvaluateError::InvalidArgumentList { expr, .. }
+            | EvaluateError::InvalidExpression { expr, .. }
+            | EvaluateError::UndefinedVariable { expr, .. }
+            | EvaluateError::UndefinedFunction { expr, .. }
+            | EvaluateError::NotCallable { expr, .. }
+            | EvaluateError::ArgumentCountMismatch { expr, .. }
+            | EvaluateError::DivisionByZero { expr, .. }
+            | EvaluateError::TypeMismatch { expr, .. }
+            | EvaluateError::TypeError { expr, .. }
+            | EvaluateError::StackOverflow { expr, .. }
+            | EvaluateError::OutOfMemory { expr, .. }
+            | EvaluateError::InternalError { expr, .. }
+            | EvaluateError::NotImplemented { expr, .. }
+            | EvaluateError::FuelExhausted { expr, .. } => expr,
+        }
+    }
+}
+
+/// 渲染求值错误：错误消息，以及出错表达式在原始源码 `source` 中所在的行，
+/// 下一行用 `^` 标出表达式的范围（`Span::start` 到 `Span::end`）。
+/// 表达式没有位置信息（空区间）或位置超出源码时只输出错误消息
+pub fn render_evaluate_error(source: &str, error: &EvaluateError) -> String {
+    let span = &error.expr().span;
+    let snippet = if span.start < span.end {
+        render_snippet(source, span.start, span.end)
+    } else {
+        None
+    };
+    match snippet {
+        Some(snippet) => format!("{error}\n{snippet}"),
+        None => error.to_string(),
+    }
 }
 
 // ============================================================================
@@ -169,6 +221,9 @@ impl std::fmt::Display for EvaluateError {
             EvaluateError::NotImplemented { feature, .. } => {
                 write!(f, "Feature not implemented: {}", feature)
             },
+            EvaluateError::FuelExhausted { budget, .. } => {
+                write!(f, "Fuel exhausted after {} steps", budget)
+            },
         }
     }
 }
@@ -101,17 +101,72 @@ pub fn is_parse_success(output: &ParseOutput) -> bool {
 }
 
 /// 获取解析错误的简短描述
-pub fn get_error_summary(output: &ParseOutput) -> Option<String> {
+/// 
+/// `source` 为原始源代码（错误中的偏移量基于原始文本，而非重建的 `source_text`），
+/// 据此附加出错的源码行及指向出错列的插入符号。
+pub fn get_error_summary(output: &ParseOutput, source: &str) -> Option<String> {
     match &output.result {
-        Err(error) => Some(format!("{}", error)),
+        Err(error) => {
+            let offset = match error {
+                ParseError::UnexpectedToken { found, .. } => found.span.start,
+                ParseError::LexError(lex_error) => lex_error.position(),
+            };
+            match render_snippet(source, offset, offset + 1) {
+                Some(snippet) => Some(format!("{}\n{}", error, snippet)),
+                None => Some(format!("{}", error)),
+            }
+        },
         Ok(_) => None,
     }
 }
 
+/// 渲染字符偏移量区间 [start, end) 起始处所在的源码行，并在下一行用 `^` 标出该区间；
+/// 区间跨行时只标到行尾，空区间标出一列
+pub(crate) fn render_snippet(source: &str, start: usize, end: usize) -> Option<String> {
+    let mut line_no = 1;
+    let mut line_start = 0;
+    for (index, ch) in source.chars().enumerate() {
+        if index == start {
+            break;
+        }
+        if ch == '\n' {
+            line_no += 1;
+            line_start = index + 1;
+        }
+    }
+    let line: String = source.chars().skip(line_start).take_while(|&ch| ch != '\n').collect();
+    let column = start.checked_sub(line_start)?;
+    let line_len = line.chars().count();
+    if column > line_len {
+        return None;
+    }
+    // 保留制表符，使插入符号与源码对齐
+    let padding: String = line.chars().take(column)
+        .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+        .collect();
+    let width = end.min(line_start + line_len).saturating_sub(start).max(1);
+    let gutter = " ".repeat(line_no.to_string().len());
+    Some(format!("{gutter} |\n{line_no} | {line}\n{gutter} | {padding}{}", "^".repeat(width)))
+}
+
 // ============================================================================
 // 测试支持
 // ============================================================================
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_summary_snippet() {
+        // 第二行第 4 列出现多余的右括号
+        let source = "(define x 1)\n(+ ))";
+        let output = parse_from_string(source);
+        let summary = get_error_summary(&output, source).expect("expected a parse error");
+        assert!(summary.ends_with("  |\n2 | (+ ))\n  |     ^"), "unexpected summary: {summary}");
+    }
+}
+
 #[cfg(test)]
 pub mod test_utils {
     use crate::interpreter::lexer::types::{Token, TokenType, Span, LexError};
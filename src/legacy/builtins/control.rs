@@ -0,0 +1,166 @@
+use crate::legacy::types::{Value, SchemeError, Result};
+use crate::legacy::eval::{CoreEvaluator, EvaluationContext};
+
+/// 将值列表打包为多值；单个值直接返回该值
+pub fn make_values(mut values: Vec<Value>) -> Value {
+    if values.len() == 1 {
+        values.pop().unwrap()
+    } else {
+        Value::MultipleValues(values)
+    }
+}
+
+/// 将（可能的）多值展开为值列表
+pub fn values_to_vec(mut value: Value) -> Vec<Value> {
+    match &mut value {
+        Value::MultipleValues(values) => std::mem::take(values),
+        _ => vec![value],
+    }
+}
+
+/// (values obj ...)
+pub fn values(args: &[Value]) -> Result<Value> {
+    Ok(make_values(args.to_vec()))
+}
+
+/// (call-with-values producer consumer)：以 producer 产生的值调用 consumer
+pub fn call_with_values(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("call-with-values requires exactly 2 arguments".to_string(), None));
+    }
+
+    let produced = evaluator.apply(&args[0], vec![], context)?;
+    evaluator.apply(&args[1], values_to_vec(produced), context)
+}
+
+/// (raise obj)：以 obj 作为条件对象抛出，处理过程返回时报错
+pub fn raise(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("raise requires exactly 1 argument".to_string(), None));
+    }
+    evaluator.raise(args[0].clone(), false, context)
+}
+
+/// (raise-continuable obj)：以 obj 作为条件对象抛出，返回处理过程的返回值
+pub fn raise_continuable(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("raise-continuable requires exactly 1 argument".to_string(), None));
+    }
+    evaluator.raise(args[0].clone(), true, context)
+}
+
+/// (with-exception-handler handler thunk)：在安装 handler 的动态环境中调用 thunk
+pub fn with_exception_handler(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("with-exception-handler requires exactly 2 arguments".to_string(), None));
+    }
+    evaluator.with_exception_handler(&args[0], &args[1], context)
+}
+
+/// (error message irritant ...)：以新建的错误对象调用 raise
+pub fn error(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    let Some((message, irritants)) = args.split_first() else {
+        return Err(SchemeError::ArityError("error requires at least 1 argument".to_string(), None));
+    };
+    evaluator.raise(Value::error_object(message.clone(), Value::from_vec(irritants.to_vec())), false, context)
+}
+
+/// (error-object? obj)，error? 为其别名
+pub fn is_error_object(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("error-object? requires exactly 1 argument".to_string(), None));
+    }
+    Ok(Value::Bool(args[0].error_object_parts().is_some()))
+}
+
+/// 取出错误对象参数的消息与附加对象
+fn expect_error_object(name: &str, args: &[Value]) -> Result<(Value, Value)> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError(format!("{name} requires exactly 1 argument"), None));
+    }
+    args[0].error_object_parts().ok_or_else(|| {
+        SchemeError::TypeError(format!("{name} expects an error object, got {}", args[0]), None)
+    })
+}
+
+/// (error-object-message error)
+pub fn error_object_message(args: &[Value]) -> Result<Value> {
+    expect_error_object("error-object-message", args).map(|(message, _)| message)
+}
+
+/// (error-object-irritants error)
+pub fn error_object_irritants(args: &[Value]) -> Result<Value> {
+    expect_error_object("error-object-irritants", args).map(|(_, irritants)| irritants)
+}
+
+/// (exit [obj])：请求以状态码退出；省略或 #t 为 0，#f 为 1，整数原样使用
+pub fn exit(args: &[Value]) -> Result<Value> {
+    let code = match args {
+        [] | [Value::Bool(true)] => 0,
+        [Value::Bool(false)] => 1,
+        [Value::Integer(n)] => i32::try_from(*n).map_err(|_| {
+            SchemeError::RuntimeError(format!("exit status out of range: {n}"), None)
+        })?,
+        [other] => return Err(SchemeError::TypeError(format!("exit expects an integer or boolean, got {other}"), None)),
+        _ => return Err(SchemeError::ArityError("exit requires at most 1 argument".to_string(), None)),
+    };
+    Err(SchemeError::Exit(code))
+}
+
+/// (emergency-exit [obj])：没有 dynamic-wind 需要跳过，行为与 exit 相同
+pub fn emergency_exit(args: &[Value]) -> Result<Value> {
+    exit(args)
+}
+
+/// 实现所支持的特性标识符（features 的结果，按 R7RS 附录 B 的命名），
+/// 包括求值器本身的能力以及运行平台的系统族、系统名与字节序
+pub const FEATURES: &[&str] = &[
+    "r7rs", "arbores", "tail-calls", "ieee-float", "full-unicode", "syntax-rules",
+    std::env::consts::FAMILY, std::env::consts::OS,
+    if cfg!(target_endian = "little") { "little-endian" } else { "big-endian" },
+];
+
+/// 实现版本号（全局变量 *version* 的值）
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// (features)：返回特性标识符符号的列表
+pub fn features(args: &[Value]) -> Result<Value> {
+    if !args.is_empty() {
+        return Err(SchemeError::ArityError("features requires no arguments".to_string(), None));
+    }
+    Ok(Value::from_vec(FEATURES.iter().map(|feature| Value::symbol(feature)).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values_packing() {
+        assert_eq!(values(&[Value::Integer(1)]).unwrap(), Value::Integer(1));
+        assert_eq!(
+            values(&[Value::Integer(1), Value::Integer(2)]).unwrap(),
+            Value::MultipleValues(vec![Value::Integer(1), Value::Integer(2)])
+        );
+        assert_eq!(values_to_vec(values(&[]).unwrap()), vec![]);
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(exit(&[]), Err(SchemeError::Exit(0)));
+        assert_eq!(exit(&[Value::Integer(3)]), Err(SchemeError::Exit(3)));
+        assert_eq!(exit(&[Value::Bool(true)]), Err(SchemeError::Exit(0)));
+        assert_eq!(exit(&[Value::Bool(false)]), Err(SchemeError::Exit(1)));
+        assert_eq!(emergency_exit(&[Value::Integer(2)]), Err(SchemeError::Exit(2)));
+        assert!(matches!(exit(&[Value::string("x")]), Err(SchemeError::TypeError(..))));
+    }
+
+    #[test]
+    fn test_features() {
+        let list = features(&[]).unwrap().to_vec().unwrap();
+        assert!(!list.is_empty());
+        assert!(list.iter().all(|feature| matches!(feature, Value::Symbol(_))));
+        assert!(list.contains(&Value::symbol("arbores")));
+        assert!(features(&[Value::Nil]).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+use crate::legacy::types::{Value, SchemeError, Result};
+use crate::legacy::eval::{CoreEvaluator, EvaluationContext};
+use crate::legacy::parser::Parser;
+
+/// (eval-string string)：在全局环境中解析并求值字符串中的所有表达式，返回最后一个结果
+pub fn eval_string(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("eval-string requires exactly 1 argument".to_string(), None));
+    }
+
+    let source = match args[0].string_contents() {
+        Some(s) => s,
+        None => return Err(SchemeError::TypeError(format!("eval-string expects a string, got {}", args[0]), None)),
+    };
+
+    evaluator.with_nesting_guard("eval-string", || {
+        let exprs = Parser::parse_multiple(&source)?;
+        let global_env = evaluator.get_global_env();
+        let mut result = Value::Nil;
+        for expr in &exprs {
+            result = evaluator.eval(expr, &global_env, context)?;
+        }
+        Ok(result)
+    })
+}
+
+/// (load filename)：读取文件并在全局环境中依次求值其中的所有表达式；
+/// 文件无法读取时返回 IoError
+pub fn load(args: &[Value], evaluator: &CoreEvaluator, _context: Option<&EvaluationContext>) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("load requires exactly 1 argument".to_string(), None));
+    }
+
+    let path = match args[0].string_contents() {
+        Some(s) => s,
+        None => return Err(SchemeError::TypeError(format!("load expects a file name string, got {}", args[0]), None)),
+    };
+
+    let source = std::fs::read_to_string(&*path)
+        .map_err(|error| std::io::Error::new(error.kind(), format!("cannot load {path}: {error}")))?;
+    evaluator.with_nesting_guard("load", || evaluator.eval_all(&source))?;
+    Ok(Value::Unspecified)
+}
+
+/// (read-tokens string)：解析字符串中的所有数据并以列表返回，不求值
+pub fn read_tokens(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("read-tokens requires exactly 1 argument".to_string(), None));
+    }
+
+    let source = match args[0].string_contents() {
+        Some(s) => s,
+        None => return Err(SchemeError::TypeError(format!("read-tokens expects a string, got {}", args[0]), None)),
+    };
+
+    Ok(Value::from_vec(Parser::parse_multiple(&source)?))
+}
+
+/// (eval datum [environment])：在指定环境（默认为全局环境）中求值数据
+pub fn eval(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    let env = match args {
+        [_] => evaluator.get_global_env(),
+        [_, Value::Environment(env_id)] => evaluator.environment(*env_id),
+        [_, other] => return Err(SchemeError::TypeError(format!("eval expects an environment, got {other}"), None)),
+        _ => return Err(SchemeError::ArityError("eval requires 1 or 2 arguments".to_string(), None)),
+    };
+
+    evaluator.with_nesting_guard("eval", || evaluator.eval(&args[0], &env, context))
+}
+
+/// (environment-bindings [environment])：返回从指定环境（默认为全局环境）可见的所有绑定，
+/// 形如 ((name . value) ...) 的关联列表，内层绑定遮蔽外层，按名字排序
+pub fn environment_bindings(args: &[Value], evaluator: &CoreEvaluator, _context: Option<&EvaluationContext>) -> Result<Value> {
+    let env = match args {
+        [] => evaluator.get_global_env(),
+        [Value::Environment(env_id)] => evaluator.environment(*env_id),
+        [other] => return Err(SchemeError::TypeError(format!("environment-bindings expects an environment, got {other}"), None)),
+        _ => return Err(SchemeError::ArityError("environment-bindings requires at most 1 argument".to_string(), None)),
+    };
+
+    Ok(Value::from_vec(env.all_visible_bindings().into_iter()
+        .map(|(name, value)| Value::cons(Value::symbol(&name), value))
+        .collect()))
+}
+
+/// (profile-report)：返回按过程名排序的调用统计，形如 ((name calls . seconds) ...)；
+/// 统计需由宿主通过 Evaluator::set_profiling 开启
+pub fn profile_report(args: &[Value], evaluator: &CoreEvaluator, _context: Option<&EvaluationContext>) -> Result<Value> {
+    if !args.is_empty() {
+        return Err(SchemeError::ArityError("profile-report requires no arguments".to_string(), None));
+    }
+
+    Ok(Value::from_vec(evaluator.profile_report().into_iter()
+        .map(|(name, entry)| Value::cons(
+            Value::symbol(&name),
+            Value::cons(Value::Integer(entry.calls as i64), Value::Float(entry.time.as_secs_f64())),
+        ))
+        .collect()))
+}
+
+/// (interaction-environment)：返回全局环境
+pub fn interaction_environment(args: &[Value], evaluator: &CoreEvaluator, _context: Option<&EvaluationContext>) -> Result<Value> {
+    if !args.is_empty() {
+        return Err(SchemeError::ArityError("interaction-environment requires no arguments".to_string(), None));
+    }
+    Ok(Value::Environment(evaluator.get_global_env().id()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_tokens() {
+        let read = |source: &str| read_tokens(&[Value::string(source.to_string())]);
+        
+        assert_eq!(read("1 2 3").unwrap().to_string(), "(1 2 3)");
+        assert_eq!(read("  ").unwrap(), Value::Nil);
+        
+        // 数字、符号与嵌套列表混合，均不求值
+        let data = read("42 foo 2.5 (a (b 3)) \"s\"").unwrap();
+        assert_eq!(data.to_string(), "(42 foo 2.5 (a (b 3)) \"s\")");
+        assert_eq!(data.car(), Some(Value::Integer(42)));
+        
+        assert!(read("(unclosed").is_err());
+        assert!(matches!(read_tokens(&[Value::Integer(1)]), Err(SchemeError::TypeError(..))));
+    }
+}
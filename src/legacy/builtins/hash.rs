@@ -0,0 +1,244 @@
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+use crate::legacy::types::{Value, SchemeError, Result, HashTable};
+use crate::legacy::eval::{CoreEvaluator, EvaluationContext};
+
+/// 检查值能否作为哈希表的键，不能时返回错误
+pub fn check_hashable(name: &str, key: &Value) -> Result<()> {
+    check_acyclic(name, key)?;
+    if key.is_hashable() {
+        Ok(())
+    } else {
+        Err(SchemeError::TypeError(format!("{name}: unhashable key {key}"), None))
+    }
+}
+
+/// 检查用于查找的键没有环；对有环的结构求哈希不会终止
+fn check_acyclic(name: &str, key: &Value) -> Result<()> {
+    if key.has_pair_cycle() {
+        Err(SchemeError::TypeError(format!("{name}: circular list"), None))
+    } else {
+        Ok(())
+    }
+}
+
+/// (equal-hash obj)：与 equal? 一致的哈希值
+pub fn equal_hash(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("equal-hash requires exactly 1 argument".to_string(), None));
+    }
+
+    check_hashable("equal-hash", &args[0])?;
+    let mut hasher = DefaultHasher::new();
+    args[0].hash(&mut hasher);
+    Ok(Value::Integer(hasher.finish() as i64))
+}
+
+/// 检查参数为哈希表并返回
+fn expect_hash_table<'a>(name: &str, value: &'a Value) -> Result<&'a Rc<RefCell<HashTable>>> {
+    match value {
+        Value::HashTable(table) => Ok(table),
+        other => Err(SchemeError::TypeError(format!("{name} expects a hash table, got {other}"), None)),
+    }
+}
+
+/// 检查参数个数
+fn check_arity(name: &str, expected: usize, args: &[Value]) -> Result<()> {
+    if args.len() != expected {
+        return Err(SchemeError::ArityError(
+            format!("{name} requires exactly {expected} arguments, got {}", args.len()), None
+        ));
+    }
+    Ok(())
+}
+
+/// (make-hash-table)：创建空哈希表，键按 equal? 比较
+pub fn make_hash_table(args: &[Value]) -> Result<Value> {
+    check_arity("make-hash-table", 0, args)?;
+    Ok(Value::HashTable(Rc::new(RefCell::new(HashTable::new()))))
+}
+
+/// (hash-table? obj)
+pub fn is_hash_table(args: &[Value]) -> Result<Value> {
+    check_arity("hash-table?", 1, args)?;
+    Ok(Value::Bool(matches!(args[0], Value::HashTable(_))))
+}
+
+/// (hash-table-set! table key value)
+pub fn hash_table_set(args: &[Value]) -> Result<Value> {
+    check_arity("hash-table-set!", 3, args)?;
+    let table = expect_hash_table("hash-table-set!", &args[0])?;
+    check_hashable("hash-table-set!", &args[1])?;
+    table.borrow_mut().insert(args[1].clone(), args[2].clone());
+    Ok(Value::Unspecified)
+}
+
+/// (hash-table-ref/default table key default)
+pub fn hash_table_ref_default(args: &[Value]) -> Result<Value> {
+    check_arity("hash-table-ref/default", 3, args)?;
+    let table = expect_hash_table("hash-table-ref/default", &args[0])?;
+    check_acyclic("hash-table-ref/default", &args[1])?;
+    let value = table.borrow().get(&args[1]).cloned();
+    Ok(value.unwrap_or_else(|| args[2].clone()))
+}
+
+/// (hash-table-contains? table key)
+pub fn hash_table_contains(args: &[Value]) -> Result<Value> {
+    check_arity("hash-table-contains?", 2, args)?;
+    let table = expect_hash_table("hash-table-contains?", &args[0])?;
+    check_acyclic("hash-table-contains?", &args[1])?;
+    let contains = table.borrow().contains_key(&args[1]);
+    Ok(Value::Bool(contains))
+}
+
+/// (hash-table-delete! table key)
+pub fn hash_table_delete(args: &[Value]) -> Result<Value> {
+    check_arity("hash-table-delete!", 2, args)?;
+    let table = expect_hash_table("hash-table-delete!", &args[0])?;
+    check_acyclic("hash-table-delete!", &args[1])?;
+    table.borrow_mut().remove(&args[1]);
+    Ok(Value::Unspecified)
+}
+
+/// (hash-table-update! table key updater [failure])：以 updater 作用于键的当前值并存回；
+/// 键不存在时以无参过程 failure 的结果作为当前值，未提供 failure 则报错
+pub fn hash_table_update(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    if args.len() != 3 && args.len() != 4 {
+        return Err(SchemeError::ArityError("hash-table-update! requires 3 or 4 arguments".to_string(), None));
+    }
+    let table = expect_hash_table("hash-table-update!", &args[0])?;
+    check_hashable("hash-table-update!", &args[1])?;
+
+    // 调用过程期间不持有借用，updater 和 failure 可以访问同一个哈希表
+    let current = table.borrow().get(&args[1]).cloned();
+    let current = match (current, args.get(3)) {
+        (Some(value), _) => value,
+        (None, Some(failure)) => evaluator.apply(failure, vec![], context)?,
+        (None, None) => return Err(SchemeError::RuntimeError(
+            format!("hash-table-update!: key not found: {}", args[1]), None
+        )),
+    };
+    let updated = evaluator.apply(&args[2], vec![current], context)?;
+    table.borrow_mut().insert(args[1].clone(), updated);
+    Ok(Value::Unspecified)
+}
+
+/// (hash-table-update!/default table key updater default)：键不存在时以 default 作为当前值
+pub fn hash_table_update_default(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    check_arity("hash-table-update!/default", 4, args)?;
+    let table = expect_hash_table("hash-table-update!/default", &args[0])?;
+    check_hashable("hash-table-update!/default", &args[1])?;
+
+    let current = table.borrow().get(&args[1]).cloned().unwrap_or_else(|| args[3].clone());
+    let updated = evaluator.apply(&args[2], vec![current], context)?;
+    table.borrow_mut().insert(args[1].clone(), updated);
+    Ok(Value::Unspecified)
+}
+
+/// (hash-table-count table)：键值对个数
+pub fn hash_table_count(args: &[Value]) -> Result<Value> {
+    check_arity("hash-table-count", 1, args)?;
+    let table = expect_hash_table("hash-table-count", &args[0])?;
+    let count = table.borrow().len();
+    Ok(Value::Integer(count as i64))
+}
+
+/// (alist->hash-table alist)：由关联列表构建哈希表，键按 equal? 比较；
+/// 重复的键保留最先出现的绑定（与 assoc 的查找结果一致）
+// 键经 check_hashable 检查，只含可哈希的值
+#[allow(clippy::mutable_key_type)]
+pub fn alist_to_hash_table(args: &[Value]) -> Result<Value> {
+    check_arity("alist->hash-table", 1, args)?;
+    let entries = args[0].to_vec().ok_or_else(|| {
+        SchemeError::TypeError(format!("alist->hash-table expects an association list, got {}", args[0]), None)
+    })?;
+
+    let mut table = HashTable::new();
+    for entry in entries {
+        match &entry {
+            Value::Cons(key, value, _) => {
+                let key = key.borrow().clone();
+                check_hashable("alist->hash-table", &key)?;
+                table.entry(key).or_insert_with(|| value.borrow().clone());
+            },
+            other => return Err(SchemeError::TypeError(
+                format!("alist->hash-table expects pairs, got {other}"), None
+            )),
+        }
+    }
+    Ok(Value::HashTable(Rc::new(RefCell::new(table))))
+}
+
+/// (hash-table->alist table)：以关联列表返回所有键值对，顺序不确定
+pub fn hash_table_to_alist(args: &[Value]) -> Result<Value> {
+    check_arity("hash-table->alist", 1, args)?;
+    let table = expect_hash_table("hash-table->alist", &args[0])?;
+    let entries = table.borrow().iter()
+        .map(|(key, value)| Value::cons(key.clone(), value.clone()))
+        .collect();
+    Ok(Value::from_vec(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::legacy::types::Arity;
+
+    #[test]
+    fn test_equal_hash() {
+        let a = Value::from_vec(vec![Value::Integer(1), Value::Integer(2)]);
+        let b = Value::from_vec(vec![Value::Integer(1), Value::Integer(2)]);
+        assert_eq!(equal_hash(&[a]).unwrap(), equal_hash(&[b]).unwrap());
+
+        let procedure = Value::BuiltinFunction { name: "f".to_string(), func: |_| Ok(Value::Nil), arity: Arity::AtLeast(0) };
+        assert!(equal_hash(&[procedure]).is_err());
+    }
+
+    #[test]
+    fn test_alist_round_trip() {
+        let pair = |key: &str, value: i64| Value::cons(Value::symbol(key), Value::Integer(value));
+        let alist = Value::from_vec(vec![pair("a", 1), pair("b", 2), pair("a", 3)]);
+        let table = alist_to_hash_table(&[alist]).unwrap();
+
+        // 重复的键保留最先出现的绑定
+        assert_eq!(hash_table_count(&[table.clone()]).unwrap(), Value::Integer(2));
+        assert_eq!(hash_table_ref_default(&[table.clone(), Value::symbol("a"), Value::Nil]).unwrap(), Value::Integer(1));
+
+        // 往返后得到同样的键值对（顺序不确定）
+        let mut entries = hash_table_to_alist(&[table.clone()]).unwrap().to_vec().unwrap();
+        entries.sort_by_key(|entry| entry.to_string());
+        assert_eq!(Value::from_vec(entries).to_string(), "((a . 1) (b . 2))");
+
+        let rebuilt = alist_to_hash_table(&[hash_table_to_alist(&[table]).unwrap()]).unwrap();
+        assert_eq!(hash_table_ref_default(&[rebuilt.clone(), Value::symbol("b"), Value::Nil]).unwrap(), Value::Integer(2));
+        assert_eq!(hash_table_contains(&[rebuilt, Value::symbol("c")]).unwrap(), Value::Bool(false));
+    }
+
+    #[test]
+    fn test_hash_table_equal_keys() {
+        let table = make_hash_table(&[]).unwrap();
+        let key = || Value::from_vec(vec![Value::Integer(1), Value::string("x")]);
+        hash_table_set(&[table.clone(), key(), Value::Integer(10)]).unwrap();
+        assert_eq!(hash_table_ref_default(&[table.clone(), key(), Value::Nil]).unwrap(), Value::Integer(10));
+
+        hash_table_delete(&[table.clone(), key()]).unwrap();
+        assert_eq!(hash_table_count(&[table.clone()]).unwrap(), Value::Integer(0));
+
+        let procedure = Value::BuiltinFunction { name: "f".to_string(), func: |_| Ok(Value::Nil), arity: Arity::AtLeast(0) };
+        assert!(hash_table_set(&[table, procedure, Value::Nil]).is_err());
+    }
+    #[test]
+    fn test_hash_table_numeric_keys_follow_eqv() {
+        let table = make_hash_table(&[]).unwrap();
+        hash_table_set(&[table.clone(), Value::Integer(1), Value::symbol("exact")]).unwrap();
+        hash_table_set(&[table.clone(), Value::Float(1.0), Value::symbol("inexact")]).unwrap();
+        hash_table_set(&[table.clone(), Value::Integer(1), Value::symbol("exact")]).unwrap();
+
+        // 1 与 1.0 是两个独立的条目，两个精确的 1 是同一个键
+        assert_eq!(hash_table_count(&[table.clone()]).unwrap(), Value::Integer(2));
+        assert_eq!(hash_table_ref_default(&[table.clone(), Value::Float(1.0), Value::Nil]).unwrap(), Value::symbol("inexact"));
+        assert_eq!(hash_table_ref_default(&[table, Value::Integer(1), Value::Nil]).unwrap(), Value::symbol("exact"));
+    }
+}
@@ -1,4 +1,22 @@
-use crate::legacy::types::{Value, SchemeError, Result};
+use crate::legacy::types::{Value, SchemeError, Result, Parameter, Arity};
+use crate::legacy::eval::{CoreEvaluator, EvaluationContext};
+
+pub mod control;
+pub mod eval;
+pub mod hash;
+pub mod numeric;
+pub mod port;
+pub mod promise;
+pub mod random;
+pub mod sort;
+pub mod string;
+pub mod time;
+pub mod vector;
+
+/// 整数运算溢出错误
+fn integer_overflow() -> SchemeError {
+    SchemeError::RuntimeError("integer overflow".to_string(), None)
+}
 
 /// 算术运算函数
 pub fn add(args: &[Value]) -> Result<Value> {
@@ -16,7 +34,7 @@ pub fn add(args: &[Value]) -> Result<Value> {
                 if is_float {
                     float_result += *n as f64;
                 } else {
-                    result += n;
+                    result = result.checked_add(*n).ok_or_else(integer_overflow)?;
                 }
             },
             Value::Float(f) => {
@@ -46,7 +64,7 @@ pub fn subtract(args: &[Value]) -> Result<Value> {
     if args.len() == 1 {
         // 一元减法（取负数）
         match &args[0] {
-            Value::Integer(n) => return Ok(Value::Integer(-n)),
+            Value::Integer(n) => return n.checked_neg().map(Value::Integer).ok_or_else(integer_overflow),
             Value::Float(f) => return Ok(Value::Float(-f)),
             _ => return Err(SchemeError::TypeError(format!("- expects numbers, got {}", args[0]), None)),
         }
@@ -54,30 +72,42 @@ pub fn subtract(args: &[Value]) -> Result<Value> {
 
     // 二元及多元减法
     let mut is_float = false;
-    let mut result = match &args[0] {
-        Value::Integer(n) => *n as f64,
+    let mut result = 0i64;
+    let mut float_result = 0.0f64;
+    match &args[0] {
+        Value::Integer(n) => result = *n,
         Value::Float(f) => {
             is_float = true;
-            *f
+            float_result = *f;
         },
         _ => return Err(SchemeError::TypeError(format!("- expects numbers, got {}", args[0]), None)),
-    };
+    }
 
     for arg in &args[1..] {
         match arg {
-            Value::Integer(n) => result -= *n as f64,
+            Value::Integer(n) => {
+                if is_float {
+                    float_result -= *n as f64;
+                } else {
+                    result = result.checked_sub(*n).ok_or_else(integer_overflow)?;
+                }
+            },
             Value::Float(f) => {
-                is_float = true;
-                result -= f;
+                if !is_float {
+                    is_float = true;
+                    float_result = result as f64 - f;
+                } else {
+                    float_result -= f;
+                }
             },
             _ => return Err(SchemeError::TypeError(format!("- expects numbers, got {arg}"), None)),
         }
     }
 
     if is_float {
-        Ok(Value::Float(result))
+        Ok(Value::Float(float_result))
     } else {
-        Ok(Value::Integer(result as i64))
+        Ok(Value::Integer(result))
     }
 }
 
@@ -96,7 +126,7 @@ pub fn multiply(args: &[Value]) -> Result<Value> {
                 if is_float {
                     float_result *= *n as f64;
                 } else {
-                    result *= n;
+                    result = result.checked_mul(*n).ok_or_else(integer_overflow)?;
                 }
             },
             Value::Float(f) => {
@@ -132,12 +162,8 @@ pub fn divide(args: &[Value]) -> Result<Value> {
                 }
                 return Ok(Value::Float(1.0 / (*n as f64)));
             },
-            Value::Float(f) => {
-                if *f == 0.0 {
-                    return Err(SchemeError::DivisionByZero(None));
-                }
-                return Ok(Value::Float(1.0 / f));
-            },
+            // 非精确除法遵循 IEEE 754，除以 0.0 得到无穷大
+            Value::Float(f) => return Ok(Value::Float(1.0 / f)),
             _ => return Err(SchemeError::TypeError(format!("/ expects numbers, got {}", args[0]), None)),
         }
     }
@@ -157,12 +183,7 @@ pub fn divide(args: &[Value]) -> Result<Value> {
                 }
                 result /= *n as f64;
             },
-            Value::Float(f) => {
-                if *f == 0.0 {
-                    return Err(SchemeError::DivisionByZero(None));
-                }
-                result /= f;
-            },
+            Value::Float(f) => result /= f,
             _ => return Err(SchemeError::TypeError(format!("/ expects numbers, got {arg}"), None)),
         }
     }
@@ -264,88 +285,60 @@ pub fn abs_func(args: &[Value]) -> Result<Value> {
     }
 }
 
-pub fn max_func(args: &[Value]) -> Result<Value> {
+/// max/min 的公共实现
+///
+/// 任一参数为 NaN 时结果为 NaN；有任一浮点参数时结果为浮点数（非精确性传染），
+/// 仅当全部参数为精确整数时结果为整数。
+fn numeric_extremum(name: &str, args: &[Value], wanted: std::cmp::Ordering) -> Result<Value> {
     if args.is_empty() {
-        return Err(SchemeError::ArityError("max requires at least 1 argument".to_string(), None));
+        return Err(SchemeError::ArityError(format!("{name} requires at least 1 argument"), None));
     }
 
-    let mut max_val = &args[0];
     let mut is_float = false;
-
-    // 检查是否有浮点数
+    let mut has_nan = false;
     for arg in args {
         match arg {
-            Value::Float(_) => is_float = true,
             Value::Integer(_) => {},
-            _ => return Err(SchemeError::TypeError(format!("max expects numbers, got {}", arg), None)),
+            Value::Float(f) => {
+                is_float = true;
+                has_nan |= f.is_nan();
+            },
+            _ => return Err(SchemeError::TypeError(format!("{name} expects numbers, got {arg}"), None)),
         }
     }
 
-    for arg in &args[1..] {
-        let greater = match (max_val, arg) {
-            (Value::Integer(a), Value::Integer(b)) => b > a,
-            (Value::Float(a), Value::Float(b)) => b > a,
-            (Value::Integer(a), Value::Float(b)) => b > &(*a as f64),
-            (Value::Float(a), Value::Integer(b)) => (*b as f64) > *a,
-            _ => return Err(SchemeError::TypeError("max expects numbers".to_string(), None)),
-        };
-        
-        if greater {
-            max_val = arg;
-        }
+    if has_nan {
+        return Ok(Value::Float(f64::NAN));
     }
 
     if is_float {
-        match max_val {
-            Value::Integer(n) => Ok(Value::Float(*n as f64)),
-            Value::Float(f) => Ok(Value::Float(*f)),
-            _ => unreachable!(),
-        }
+        let best = args.iter()
+            .map(|arg| match arg {
+                Value::Integer(n) => *n as f64,
+                Value::Float(f) => *f,
+                _ => unreachable!("arguments are validated above"),
+            })
+            .reduce(|best, x| if x.partial_cmp(&best) == Some(wanted) { x } else { best })
+            .unwrap();
+        Ok(Value::Float(best))
     } else {
-        Ok(max_val.clone())
+        let best = args.iter()
+            .filter_map(|arg| match arg {
+                Value::Integer(n) => Some(*n),
+                _ => None,
+            })
+            .reduce(|best, x| if x.cmp(&best) == wanted { x } else { best })
+            .unwrap();
+        Ok(Value::Integer(best))
     }
 }
 
-pub fn min_func(args: &[Value]) -> Result<Value> {
-    if args.is_empty() {
-        return Err(SchemeError::ArityError("min requires at least 1 argument".to_string(), None));
-    }
-
-    let mut min_val = &args[0];
-    let mut is_float = false;
-
-    // 检查是否有浮点数
-    for arg in args {
-        match arg {
-            Value::Float(_) => is_float = true,
-            Value::Integer(_) => {},
-            _ => return Err(SchemeError::TypeError(format!("min expects numbers, got {}", arg), None)),
-        }
-    }
-
-    for arg in &args[1..] {
-        let less = match (min_val, arg) {
-            (Value::Integer(a), Value::Integer(b)) => b < a,
-            (Value::Float(a), Value::Float(b)) => b < a,
-            (Value::Integer(a), Value::Float(b)) => b < &(*a as f64),
-            (Value::Float(a), Value::Integer(b)) => (*b as f64) < *a,
-            _ => return Err(SchemeError::TypeError("min expects numbers".to_string(), None)),
-        };
-        
-        if less {
-            min_val = arg;
-        }
-    }
+pub fn max_func(args: &[Value]) -> Result<Value> {
+    numeric_extremum("max", args, std::cmp::Ordering::Greater)
+}
 
-    if is_float {
-        match min_val {
-            Value::Integer(n) => Ok(Value::Float(*n as f64)),
-            Value::Float(f) => Ok(Value::Float(*f)),
-            _ => unreachable!(),
-        }
-    } else {
-        Ok(min_val.clone())
-    }
+pub fn min_func(args: &[Value]) -> Result<Value> {
+    numeric_extremum("min", args, std::cmp::Ordering::Less)
 }
 
 /// 列表操作函数
@@ -354,40 +347,489 @@ pub fn cons(args: &[Value]) -> Result<Value> {
         return Err(SchemeError::ArityError("cons requires exactly 2 arguments".to_string(), None));
     }
 
-    Ok(Value::Cons(
-        std::rc::Rc::new(args[0].clone()),
-        std::rc::Rc::new(args[1].clone())
-    ))
+    Ok(Value::cons(args[0].clone(), args[1].clone()))
 }
 
+/// (car pair)：克隆指向的值只复制一层；若其为序对，仅复制内部的 Rc 句柄，子结构保持共享
 pub fn car(args: &[Value]) -> Result<Value> {
     if args.len() != 1 {
         return Err(SchemeError::ArityError("car requires exactly 1 argument".to_string(), None));
     }
 
     match &args[0] {
-        Value::Cons(car_val, _) => Ok((**car_val).clone()),
+        Value::Cons(car_val, _, _) => Ok(car_val.borrow().clone()),
         Value::Nil => Err(SchemeError::RuntimeError("car of empty list".to_string(), None)),
         _ => Err(SchemeError::TypeError(format!("car expects a pair, got {}", args[0]), None)),
     }
 }
 
+/// (cdr pair)：与 car 相同，返回的列表与原列表共享尾部结构
 pub fn cdr(args: &[Value]) -> Result<Value> {
     if args.len() != 1 {
         return Err(SchemeError::ArityError("cdr requires exactly 1 argument".to_string(), None));
     }
 
     match &args[0] {
-        Value::Cons(_, cdr_val) => Ok((**cdr_val).clone()),
+        Value::Cons(_, cdr_val, _) => Ok(cdr_val.borrow().clone()),
         Value::Nil => Err(SchemeError::RuntimeError("cdr of empty list".to_string(), None)),
         _ => Err(SchemeError::TypeError(format!("cdr expects a pair, got {}", args[0]), None)),
     }
 }
 
+/// (set-car! pair obj)：修改序对的 car，所有共享该序对的值都能看到修改
+pub fn set_car(args: &[Value]) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("set-car! requires exactly 2 arguments".to_string(), None));
+    }
+
+    match &args[0] {
+        Value::Cons(_, _, true) => Err(literal_pair_error("set-car!", &args[0])),
+        Value::Cons(car_val, _, _) => {
+            *car_val.borrow_mut() = args[1].clone();
+            Ok(Value::Unspecified)
+        },
+        other => Err(SchemeError::TypeError(format!("set-car! expects a pair, got {other}"), None)),
+    }
+}
+
+/// (set-cdr! pair obj)：修改序对的 cdr，可用于构造循环列表
+pub fn set_cdr(args: &[Value]) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("set-cdr! requires exactly 2 arguments".to_string(), None));
+    }
+
+    match &args[0] {
+        Value::Cons(_, _, true) => Err(literal_pair_error("set-cdr!", &args[0])),
+        Value::Cons(_, cdr_val, _) => {
+            *cdr_val.borrow_mut() = args[1].clone();
+            Ok(Value::Unspecified)
+        },
+        other => Err(SchemeError::TypeError(format!("set-cdr! expects a pair, got {other}"), None)),
+    }
+}
+
+/// 修改字面量序对时的错误
+fn literal_pair_error(name: &str, value: &Value) -> SchemeError {
+    SchemeError::RuntimeError(format!("{name}: cannot mutate literal {value}"), None)
+}
+
+/// (append! list ...)：破坏性连接，把每个非空列表最后一个序对的 cdr 改为指向下一个非空列表，
+/// 不分配新序对。除最后一个参数外，参数列表被消耗，之后不应再单独使用
+pub fn append_bang(args: &[Value]) -> Result<Value> {
+    let Some((last, lists)) = args.split_last() else {
+        return Ok(Value::Nil);
+    };
+
+    let mut result = last.clone();
+    for list in lists.iter().rev() {
+        match list {
+            Value::Nil => {},
+            Value::Cons(_, _, _) if list.is_proper_list() => {
+                match &list.last_pair() {
+                    Some(Value::Cons(_, _, true)) => return Err(literal_pair_error("append!", list)),
+                    Some(Value::Cons(_, cdr, _)) => *cdr.borrow_mut() = result,
+                    _ => {},
+                }
+                result = list.clone();
+            },
+            other => return Err(SchemeError::TypeError(format!("append! expects proper lists, got {other}"), None)),
+        }
+    }
+    Ok(result)
+}
+
+/// (reverse! list)：通过重新链接各序对的 cdr 原地反转列表，不分配新序对。
+/// 参数列表被消耗：原来的第一个序对成为结果的最后一个序对
+pub fn reverse_bang(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("reverse! requires exactly 1 argument".to_string(), None));
+    }
+    if !args[0].is_proper_list() {
+        return Err(SchemeError::TypeError(format!("reverse! expects a proper list, got {}", args[0]), None));
+    }
+    let mut current = args[0].clone();
+    while let Value::Cons(_, cdr, literal) = &current {
+        if *literal {
+            return Err(literal_pair_error("reverse!", &args[0]));
+        }
+        let next = cdr.borrow().clone();
+        current = next;
+    }
+
+    let mut reversed = Value::Nil;
+    let mut current = args[0].clone();
+    while let Value::Cons(_, cdr, _) = &current {
+        let next = std::mem::replace(&mut *cdr.borrow_mut(), reversed);
+        reversed = current;
+        current = next;
+    }
+    Ok(reversed)
+}
+
 pub fn list(args: &[Value]) -> Result<Value> {
     Ok(Value::from_vec(args.to_vec()))
 }
 
+/// (length list)：正常列表的长度，非正常列表报类型错误
+pub fn length(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("length requires exactly 1 argument".to_string(), None));
+    }
+
+    let mut count = 0;
+    match args[0].walk_list(|_| count += 1) {
+        Some(Value::Nil) => Ok(Value::Integer(count)),
+        Some(_) => Err(SchemeError::TypeError(format!("length expects a proper list, got {}", args[0]), None)),
+        None => Err(SchemeError::TypeError("length: circular list".to_string(), None)),
+    }
+}
+
+/// (last-pair list)：返回最后一个 pair
+pub fn last_pair(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("last-pair requires exactly 1 argument".to_string(), None));
+    }
+
+    match (&args[0], args[0].last_pair()) {
+        (_, Some(pair)) => Ok(pair),
+        (Value::Cons(_, _, _), None) => Err(SchemeError::TypeError("last-pair: circular list".to_string(), None)),
+        (other, None) => Err(SchemeError::TypeError(format!("last-pair expects a pair, got {other}"), None)),
+    }
+}
+
+/// (delete x list)：移除列表中所有与 x equal? 的元素
+pub fn delete(args: &[Value]) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("delete requires exactly 2 arguments".to_string(), None));
+    }
+
+    let items = args[1].to_vec().ok_or_else(|| {
+        SchemeError::TypeError(format!("delete expects a list, got {}", args[1]), None)
+    })?;
+
+    Ok(Value::from_vec(items.into_iter().filter(|item| !item.is_equal(&args[0])).collect()))
+}
+
+/// 在关联列表中查找第一个键满足 matches 的条目
+fn find_assoc(name: &str, alist: &Value, mut matches: impl FnMut(&Value) -> Result<bool>) -> Result<Value> {
+    let entries = alist.to_vec().ok_or_else(|| {
+        SchemeError::TypeError(format!("{name} expects an association list, got {alist}"), None)
+    })?;
+
+    for entry in entries {
+        match entry.car() {
+            Some(key) => if matches(&key)? {
+                return Ok(entry);
+            },
+            None => return Err(SchemeError::TypeError(format!("{name} expects pairs in the association list, got {entry}"), None)),
+        }
+    }
+    Ok(Value::Bool(false))
+}
+
+/// 在列表中查找第一个满足 matches 的元素，返回以它开头的子列表；
+/// 没有找到而 cdr 链成环时报错（环的检测同 Value::walk_list）
+fn find_member(name: &str, list: &Value, mut matches: impl FnMut(&Value) -> Result<bool>) -> Result<Value> {
+    let mut current = list.clone();
+    let mut slow = list.clone();
+    let mut steps = 0usize;
+    loop {
+        let next = match &current {
+            Value::Cons(car, cdr, _) => {
+                if matches(&car.borrow())? {
+                    return Ok(current);
+                }
+                cdr.borrow().clone()
+            },
+            Value::Nil => return Ok(Value::Bool(false)),
+            _ => return Err(SchemeError::TypeError(format!("{name} expects a list, got {list}"), None)),
+        };
+        current = next;
+        steps += 1;
+        if steps.is_multiple_of(2) {
+            slow = slow.cdr().unwrap_or(Value::Nil);
+            if matches!(current, Value::Cons(_, _, _)) && current.is_eq(&slow) {
+                return Err(SchemeError::TypeError(format!("{name}: circular list"), None));
+            }
+        }
+    }
+}
+
+/// (assq key alist)：使用 eq? 比较键
+pub fn assq(args: &[Value]) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("assq requires exactly 2 arguments".to_string(), None));
+    }
+    find_assoc("assq", &args[1], |key| Ok(key.is_eq(&args[0])))
+}
+
+/// (assv key alist)：使用 eqv? 比较键
+pub fn assv(args: &[Value]) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("assv requires exactly 2 arguments".to_string(), None));
+    }
+    find_assoc("assv", &args[1], |key| Ok(key.is_eqv(&args[0])))
+}
+
+/// (assoc key alist [compare])：默认使用 equal? 比较键，提供 compare 时以 (compare key 条目键) 比较
+pub fn assoc(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    match args {
+        [key, alist] => find_assoc("assoc", alist, |entry_key| Ok(entry_key.is_equal(key))),
+        [key, alist, compare] => find_assoc("assoc", alist, |entry_key| {
+            Ok(evaluator.apply(compare, vec![key.clone(), entry_key.clone()], context)?.is_truthy())
+        }),
+        _ => Err(SchemeError::ArityError("assoc requires 2 or 3 arguments".to_string(), None)),
+    }
+}
+
+/// (member obj list [compare])：默认使用 equal? 比较，提供 compare 时以 (compare obj 元素) 比较
+pub fn member(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    match args {
+        [obj, list] => find_member("member", list, |item| Ok(item.is_equal(obj))),
+        [obj, list, compare] => find_member("member", list, |item| {
+            Ok(evaluator.apply(compare, vec![obj.clone(), item.clone()], context)?.is_truthy())
+        }),
+        _ => Err(SchemeError::ArityError("member requires 2 or 3 arguments".to_string(), None)),
+    }
+}
+
+/// (alist-update key value alist)：返回新的关联列表，替换 key（equal?）对应的值；
+/// key 不存在时在表头添加新条目
+pub fn alist_update(args: &[Value]) -> Result<Value> {
+    if args.len() != 3 {
+        return Err(SchemeError::ArityError("alist-update requires exactly 3 arguments".to_string(), None));
+    }
+
+    let entries = args[2].to_vec().ok_or_else(|| {
+        SchemeError::TypeError(format!("alist-update expects an association list, got {}", args[2]), None)
+    })?;
+    let new_entry = || Value::cons(args[0].clone(), args[1].clone());
+
+    let mut found = false;
+    let mut updated = Vec::with_capacity(entries.len() + 1);
+    for entry in entries {
+        match entry.car() {
+            Some(key) if key.is_equal(&args[0]) => {
+                found = true;
+                updated.push(new_entry());
+            },
+            Some(_) => updated.push(entry),
+            None => return Err(SchemeError::TypeError(format!("alist-update expects pairs in the association list, got {entry}"), None)),
+        }
+    }
+    if !found {
+        updated.insert(0, new_entry());
+    }
+    Ok(Value::from_vec(updated))
+}
+
+/// (del-assq key alist)：返回移除所有键与 key eq? 的条目后的新关联列表
+pub fn del_assq(args: &[Value]) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("del-assq requires exactly 2 arguments".to_string(), None));
+    }
+
+    let entries = args[1].to_vec().ok_or_else(|| {
+        SchemeError::TypeError(format!("del-assq expects an association list, got {}", args[1]), None)
+    })?;
+    let mut kept = Vec::new();
+    for entry in entries {
+        match entry.car() {
+            Some(key) if key.is_eq(&args[0]) => {},
+            Some(_) => kept.push(entry),
+            None => return Err(SchemeError::TypeError(format!("del-assq expects pairs in the association list, got {entry}"), None)),
+        }
+    }
+    Ok(Value::from_vec(kept))
+}
+
+/// (remove pred list)：保留谓词结果为假的元素
+pub fn remove(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("remove requires exactly 2 arguments".to_string(), None));
+    }
+
+    let items = args[1].to_vec().ok_or_else(|| {
+        SchemeError::TypeError(format!("remove expects a list, got {}", args[1]), None)
+    })?;
+
+    let mut kept = Vec::new();
+    for item in items {
+        if !evaluator.apply(&args[0], vec![item.clone()], context)?.is_truthy() {
+            kept.push(item);
+        }
+    }
+    Ok(Value::from_vec(kept))
+}
+
+/// 将 (proc list ...) 的列表参数按位置组合为每次调用的参数，以最短的列表为准
+fn collect_list_args(name: &str, args: &[Value]) -> Result<Vec<Vec<Value>>> {
+    if args.len() < 2 {
+        return Err(SchemeError::ArityError(format!("{name} requires a procedure and at least 1 list"), None));
+    }
+
+    let lists = args[1..].iter()
+        .map(|list| list.to_vec().ok_or_else(|| {
+            SchemeError::TypeError(format!("{name} expects lists, got {list}"), None)
+        }))
+        .collect::<Result<Vec<_>>>()?;
+    let len = lists.iter().map(|list| list.len()).min().unwrap_or(0);
+    Ok((0..len).map(|i| lists.iter().map(|list| list[i].clone()).collect()).collect())
+}
+
+/// (map proc list ...)：以各列表对应位置的元素调用 proc，返回结果列表；在最短的列表处结束
+pub fn map(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    let mut results = Vec::new();
+    for call_args in collect_list_args("map", args)? {
+        results.push(evaluator.apply(&args[0], call_args, context)?);
+    }
+    Ok(Value::from_vec(results))
+}
+
+/// (for-each proc list ...)：依次以各列表对应位置的元素调用 proc；在最短的列表处结束
+pub fn for_each(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    for call_args in collect_list_args("for-each", args)? {
+        evaluator.apply(&args[0], call_args, context)?;
+    }
+    Ok(Value::Unspecified)
+}
+
+/// (apply proc arg ... list)：以 arg ... 与 list 的元素为参数调用 proc
+pub fn apply(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    let [proc, init @ .., last] = args else {
+        return Err(SchemeError::ArityError("apply requires a procedure and a list".to_string(), None));
+    };
+
+    let mut call_args = init.to_vec();
+    call_args.extend(last.to_vec().ok_or_else(|| {
+        SchemeError::TypeError(format!("apply expects a list as its last argument, got {last}"), None)
+    })?);
+    evaluator.apply(proc, call_args, context)
+}
+
+/// 依次对列表元素调用谓词，返回第一个结果为真的 (下标, 元素, 结果)；之后的元素不再求值
+fn find_first(name: &str, args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Option<(usize, Value, Value)>> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError(format!("{name} requires exactly 2 arguments"), None));
+    }
+
+    let items = args[1].to_vec().ok_or_else(|| {
+        SchemeError::TypeError(format!("{name} expects a list, got {}", args[1]), None)
+    })?;
+
+    for (index, item) in items.into_iter().enumerate() {
+        let result = evaluator.apply(&args[0], vec![item.clone()], context)?;
+        if result.is_truthy() {
+            return Ok(Some((index, item, result)));
+        }
+    }
+    Ok(None)
+}
+
+/// (find pred list)：返回第一个满足谓词的元素，找不到时返回 #f
+pub fn find(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    Ok(find_first("find", args, evaluator, context)?.map_or(Value::Bool(false), |(_, item, _)| item))
+}
+
+/// (list-index pred list)：返回第一个满足谓词的元素的下标，找不到时返回 #f
+pub fn list_index(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    Ok(find_first("list-index", args, evaluator, context)?.map_or(Value::Bool(false), |(index, _, _)| Value::Integer(index as i64)))
+}
+
+/// (any pred list)：返回第一个为真的谓词结果，全部为假时返回 #f
+pub fn any(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    Ok(find_first("any", args, evaluator, context)?.map_or(Value::Bool(false), |(_, _, result)| result))
+}
+
+/// (every pred list)：全部满足时返回最后一个谓词结果（空表返回 #t），
+/// 遇到第一个为假的结果即返回 #f
+pub fn every(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("every requires exactly 2 arguments".to_string(), None));
+    }
+
+    let items = args[1].to_vec().ok_or_else(|| {
+        SchemeError::TypeError(format!("every expects a list, got {}", args[1]), None)
+    })?;
+
+    let mut last = Value::Bool(true);
+    for item in items {
+        last = evaluator.apply(&args[0], vec![item], context)?;
+        if !last.is_truthy() {
+            return Ok(Value::Bool(false));
+        }
+    }
+    Ok(last)
+}
+
+/// (make-parameter value [converter])：创建参数对象
+pub fn make_parameter(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(SchemeError::ArityError("make-parameter requires 1 or 2 arguments".to_string(), None));
+    }
+
+    let converter = args.get(1).cloned();
+    let value = match &converter {
+        Some(converter) => evaluator.apply(converter, vec![args[0].clone()], context)?,
+        None => args[0].clone(),
+    };
+    Ok(Value::Parameter(std::rc::Rc::new(Parameter { value, converter })))
+}
+
+/// (type-of obj)：返回命名运行时类型的符号，如 integer、pair、procedure
+pub fn type_of(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("type-of requires exactly 1 argument".to_string(), None));
+    }
+
+    Ok(Value::symbol(args[0].type_name()))
+}
+
+/// (procedure-arity proc)：返回 (min . max) 序对描述接受的参数个数，max 为 #f 表示无上限
+pub fn procedure_arity(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("procedure-arity requires exactly 1 argument".to_string(), None));
+    }
+
+    let (min, max) = match args[0].arity() {
+        Some(Arity::Exact(n)) => (n, Some(n)),
+        Some(Arity::Range(min, max)) => (min, Some(max)),
+        Some(Arity::AtLeast(min)) => (min, None),
+        None => return Err(SchemeError::TypeError(
+            format!("procedure-arity expects a procedure, got {}", args[0]), None
+        )),
+    };
+    let bound = |n: usize| Value::Integer(n as i64);
+    Ok(Value::cons(bound(min), max.map_or(Value::Bool(false), bound)))
+}
+
+/// (eq? a b)：身份比较，规则见 [`Value::is_eq`]
+pub fn eq(args: &[Value]) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("eq? requires exactly 2 arguments".to_string(), None));
+    }
+
+    Ok(Value::Bool(args[0].is_eq(&args[1])))
+}
+
+/// (eqv? a b)：在 eq? 的基础上按值比较同精确性的数字
+pub fn eqv(args: &[Value]) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("eqv? requires exactly 2 arguments".to_string(), None));
+    }
+
+    Ok(Value::Bool(args[0].is_eqv(&args[1])))
+}
+
+/// (equal? a b)：结构比较，规则见 [`Value::is_equal`]；与 = 不同，不跨精确性转换数字
+pub fn deep_equal(args: &[Value]) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("equal? requires exactly 2 arguments".to_string(), None));
+    }
+
+    Ok(Value::Bool(args[0].is_equal(&args[1])))
+}
+
 /// 类型谓词函数
 pub fn is_null(args: &[Value]) -> Result<Value> {
     if args.len() != 1 {
@@ -402,7 +844,7 @@ pub fn is_pair(args: &[Value]) -> Result<Value> {
         return Err(SchemeError::ArityError("pair? requires exactly 1 argument".to_string(), None));
     }
 
-    Ok(Value::Bool(matches!(args[0], Value::Cons(_, _))))
+    Ok(Value::Bool(matches!(args[0], Value::Cons(_, _, _))))
 }
 
 pub fn is_number(args: &[Value]) -> Result<Value> {
@@ -421,17 +863,150 @@ pub fn is_symbol(args: &[Value]) -> Result<Value> {
     Ok(Value::Bool(matches!(args[0], Value::Symbol(_))))
 }
 
+/// (symbol=? symbol1 symbol2 ...)：驻留符号的比较只是编号比较
+pub fn symbol_equal(args: &[Value]) -> Result<Value> {
+    if args.is_empty() {
+        return Err(SchemeError::ArityError("symbol=? requires at least 1 argument".to_string(), None));
+    }
+
+    let mut symbols = Vec::with_capacity(args.len());
+    for arg in args {
+        match arg {
+            Value::Symbol(symbol) => symbols.push(*symbol),
+            other => return Err(SchemeError::TypeError(format!("symbol=? expects symbols, got {other}"), None)),
+        }
+    }
+    Ok(Value::Bool(symbols.windows(2).all(|pair| pair[0] == pair[1])))
+}
+
 pub fn is_string(args: &[Value]) -> Result<Value> {
     if args.len() != 1 {
         return Err(SchemeError::ArityError("string? requires exactly 1 argument".to_string(), None));
     }
 
-    Ok(Value::Bool(matches!(args[0], Value::String(_))))
+    Ok(Value::Bool(matches!(args[0], Value::String(_) | Value::MutableString(_))))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::rc::Rc;
+    use crate::legacy::types::Arity;
+
+    #[test]
+    fn test_eq_identity() {
+        let is_eq = |a: Value, b: Value| eq(&[a, b]).unwrap() == Value::Bool(true);
+
+        // 原子按值比较
+        assert!(is_eq(Value::symbol("a"), Value::symbol("a")));
+        assert!(!is_eq(Value::symbol("a"), Value::symbol("b")));
+        assert!(is_eq(Value::Bool(false), Value::Bool(false)));
+        assert!(is_eq(Value::Char('x'), Value::Char('x')));
+        assert!(is_eq(Value::Nil, Value::Nil));
+        assert!(is_eq(Value::Integer(1 << 40), Value::Integer(1 << 40)));
+        assert!(!is_eq(Value::Integer(1), Value::Float(1.0)));
+
+        // 浮点数只 eqv?
+        assert!(!is_eq(Value::Float(1.5), Value::Float(1.5)));
+        assert_eq!(eqv(&[Value::Float(1.5), Value::Float(1.5)]).unwrap(), Value::Bool(true));
+        assert_eq!(eqv(&[Value::Integer(2), Value::Float(2.0)]).unwrap(), Value::Bool(false));
+
+        // 序对与可变字符串按身份比较
+        let pair = Value::from_vec(vec![Value::Integer(1)]);
+        assert!(is_eq(pair.clone(), pair.clone()));
+        assert!(!is_eq(pair, Value::from_vec(vec![Value::Integer(1)])));
+        let s = string::make_string(&[Value::Integer(1)]).unwrap();
+        assert!(is_eq(s.clone(), s.clone()));
+        assert!(!is_eq(s, string::make_string(&[Value::Integer(1)]).unwrap()));
+    }
+
+    #[test]
+    fn test_destructive_append_and_reverse() {
+        let ints = |range: std::ops::Range<i64>| Value::from_vec(range.map(Value::Integer).collect());
+
+        // append! 重用参数的序对，跳过空表，最后一个参数可以不是列表
+        let a = ints(0..2);
+        let b = ints(2..4);
+        let joined = append_bang(&[a.clone(), Value::Nil, b.clone(), Value::Integer(4)]).unwrap();
+        assert_eq!(joined.to_string(), "(0 1 2 3 . 4)");
+        assert!(joined.is_eq(&a));
+        assert!(a.cdr().unwrap().cdr().unwrap().is_eq(&b));
+        assert_eq!(append_bang(&[]).unwrap(), Value::Nil);
+        assert_eq!(append_bang(&[Value::Nil, Value::Nil]).unwrap(), Value::Nil);
+        assert!(append_bang(&[Value::Integer(1), Value::Nil]).is_err());
+
+        // 分块构建长列表：结果中的序对全部来自原来的块，没有复制
+        let chunks: Vec<Value> = (0..100).map(|i| ints(i * 100..(i + 1) * 100)).collect();
+        let long = append_bang(&chunks).unwrap();
+        assert_eq!(long.length(), Some(10_000));
+        let mut cell = long.clone();
+        for i in 0..10_000 {
+            if i % 100 == 0 {
+                assert!(cell.is_eq(&chunks[(i / 100) as usize]));
+            }
+            cell = cell.cdr().unwrap();
+        }
+
+        // reverse! 原地重新链接：原来的首个序对变成最后一个
+        let list = ints(0..5);
+        let reversed = reverse_bang(&[list.clone()]).unwrap();
+        assert_eq!(reversed.to_string(), "(4 3 2 1 0)");
+        assert!(reversed.last_pair().unwrap().is_eq(&list));
+        assert_eq!(list.to_string(), "(0)");
+        assert_eq!(reverse_bang(&[Value::Nil]).unwrap(), Value::Nil);
+        assert!(reverse_bang(&[Value::cons(Value::Integer(1), Value::Integer(2))]).is_err());
+    }
+
+    #[test]
+    fn test_car_cdr_share_structure() {
+        let original = Value::from_vec((0..10_000).map(Value::Integer).collect());
+        let nested = cons(&[original.clone(), Value::Nil]).unwrap();
+        
+        // car 返回的列表与原列表是同一组序对
+        match (&car(&[nested]).unwrap(), &original) {
+            (Value::Cons(a_car, a_cdr, _), Value::Cons(b_car, b_cdr, _)) => {
+                assert!(Rc::ptr_eq(a_car, b_car));
+                assert!(Rc::ptr_eq(a_cdr, b_cdr));
+            },
+            _ => panic!("expected pairs"),
+        }
+        
+        // 反复取 cdr 遍历整个列表，每一步都指向原列表的尾部而不是副本
+        let mut current = original.clone();
+        let mut expected_tail = match &original {
+            Value::Cons(_, tail, _) => Rc::clone(tail),
+            _ => panic!("expected a pair"),
+        };
+        for _ in 0..9_999 {
+            current = cdr(&[current]).unwrap();
+            match &current {
+                Value::Cons(_, tail, _) => {
+                    let next_tail = Rc::clone(tail);
+                    assert!(matches!(&*expected_tail.borrow(), Value::Cons(_, t, _) if Rc::ptr_eq(t, &next_tail)));
+                    expected_tail = next_tail;
+                },
+                _ => panic!("expected a pair"),
+            }
+        }
+        assert_eq!(cdr(&[current]).unwrap(), Value::Nil);
+    }
+
+    #[test]
+    fn test_type_of() {
+        let type_of_value = |value: Value| type_of(&[value]).unwrap();
+        assert_eq!(type_of_value(Value::Integer(1)), Value::symbol("integer"));
+        assert_eq!(type_of_value(Value::Float(1.5)), Value::symbol("float"));
+        assert_eq!(type_of_value(Value::string("s")), Value::symbol("string"));
+        assert_eq!(type_of_value(Value::symbol("a")), Value::symbol("symbol"));
+        assert_eq!(type_of_value(Value::cons(Value::Integer(1), Value::Nil)), Value::symbol("pair"));
+        assert_eq!(type_of_value(Value::Nil), Value::symbol("null"));
+        assert_eq!(type_of_value(Value::Bool(false)), Value::symbol("boolean"));
+        assert_eq!(type_of_value(Value::Char('c')), Value::symbol("char"));
+        assert_eq!(type_of_value(Value::Vector(Rc::new(std::cell::RefCell::new(vec![])), false)), Value::symbol("vector"));
+        let procedure = Value::BuiltinFunction { name: "f".to_string(), func: type_of, arity: Arity::Exact(1) };
+        assert_eq!(type_of_value(procedure), Value::symbol("procedure"));
+        assert!(type_of(&[]).is_err());
+    }
 
     #[test]
     fn test_add() {
@@ -442,6 +1017,70 @@ mod tests {
         assert_eq!(add(&args).unwrap(), Value::Float(3.5));
     }
 
+    #[test]
+    fn test_add_multiply_mixed_order() {
+        // 浮点数出现在开头或结尾，结果相同
+        assert_eq!(add(&[Value::Float(1.0), Value::Integer(2)]).unwrap(), Value::Float(3.0));
+        assert_eq!(add(&[Value::Integer(2), Value::Float(1.0)]).unwrap(), Value::Float(3.0));
+        assert_eq!(multiply(&[Value::Float(0.5), Value::Integer(4)]).unwrap(), Value::Float(2.0));
+        assert_eq!(multiply(&[Value::Integer(4), Value::Float(0.5)]).unwrap(), Value::Float(2.0));
+
+        // 单位元
+        assert_eq!(add(&[]).unwrap(), Value::Integer(0));
+        assert_eq!(multiply(&[]).unwrap(), Value::Integer(1));
+
+        // 大量整数之后出现的浮点数不会丢失已累加的整数和
+        let mut args: Vec<Value> = (1..=1000).map(Value::Integer).collect();
+        args.push(Value::Float(0.5));
+        assert_eq!(add(&args).unwrap(), Value::Float(500_500.5));
+    }
+
+    #[test]
+    fn test_add_multiply_random_mixed_arguments() {
+        // 固定种子的 xorshift，保证测试可重复
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for _ in 0..500 {
+            let len = (next() % 12) as usize;
+            let with_floats = next() % 2 == 0;
+            let args: Vec<Value> = (0..len).map(|_| {
+                let n = (next() % 201) as i64 - 100;
+                if with_floats && next() % 3 == 0 {
+                    Value::Float(n as f64 / 8.0)
+                } else {
+                    Value::Integer(n)
+                }
+            }).collect();
+            let as_f64 = |value: &Value| match value {
+                Value::Integer(n) => *n as f64,
+                Value::Float(f) => *f,
+                _ => unreachable!(),
+            };
+            let has_float = args.iter().any(|arg| matches!(arg, Value::Float(_)));
+
+            let sum = add(&args).unwrap();
+            let product = multiply(&args[..len.min(6)]).unwrap();
+            if has_float {
+                let expected_sum: f64 = args.iter().map(as_f64).sum();
+                assert!((as_f64(&sum) - expected_sum).abs() < 1e-9, "sum of {args:?} = {sum}");
+                assert!(matches!(sum, Value::Float(_)));
+                let expected_product: f64 = args[..len.min(6)].iter().map(as_f64).product();
+                assert!((as_f64(&product) - expected_product).abs() <= 1e-9 * expected_product.abs().max(1.0));
+            } else {
+                let expected_sum: i64 = args.iter().map(|arg| as_f64(arg) as i64).sum();
+                assert_eq!(sum, Value::Integer(expected_sum));
+                let expected_product: i64 = args[..len.min(6)].iter().map(|arg| as_f64(arg) as i64).product();
+                assert_eq!(product, Value::Integer(expected_product));
+            }
+        }
+    }
+
     #[test]
     fn test_cons_car_cdr() {
         let args = vec![Value::Integer(1), Value::Integer(2)];
@@ -493,4 +1132,57 @@ mod tests {
         assert_eq!(min_func(&[Value::Integer(3), Value::Integer(1), Value::Integer(2)]).unwrap(), Value::Integer(1));
         assert_eq!(min_func(&[Value::Float(1.5), Value::Integer(2)]).unwrap(), Value::Float(1.5));
     }
+
+    #[test]
+    fn test_delete() {
+        let items = Value::from_vec(vec![Value::Integer(1), Value::Integer(2), Value::Integer(3), Value::Integer(2)]);
+        assert_eq!(
+            delete(&[Value::Integer(2), items]).unwrap(),
+            Value::from_vec(vec![Value::Integer(1), Value::Integer(3)])
+        );
+
+        // 空列表
+        assert_eq!(delete(&[Value::Integer(2), Value::Nil]).unwrap(), Value::Nil);
+
+        // 全部移除
+        let items = Value::from_vec(vec![Value::symbol("a"), Value::symbol("a")]);
+        assert_eq!(delete(&[Value::symbol("a"), items]).unwrap(), Value::Nil);
+
+        // 非列表参数
+        assert!(delete(&[Value::Integer(1), Value::Integer(2)]).is_err());
+    }
+
+    #[test]
+    fn test_integer_overflow() {
+        let overflow = Err(SchemeError::RuntimeError("integer overflow".to_string(), None));
+
+        assert_eq!(multiply(&[Value::Integer(i64::MAX), Value::Integer(2)]), overflow);
+        assert_eq!(add(&[Value::Integer(i64::MAX), Value::Integer(1)]), overflow);
+        assert_eq!(subtract(&[Value::Integer(i64::MIN), Value::Integer(1)]), overflow);
+        assert_eq!(subtract(&[Value::Integer(i64::MIN)]), overflow);
+
+        // 未溢出时保持精确整数
+        assert_eq!(subtract(&[Value::Integer(i64::MAX), Value::Integer(1)]).unwrap(), Value::Integer(i64::MAX - 1));
+        assert_eq!(subtract(&[Value::Integer(10), Value::Float(0.5)]).unwrap(), Value::Float(9.5));
+    }
+
+    #[test]
+    fn test_min_max_nan_and_exactness() {
+        // NaN 传染
+        let result = max_func(&[Value::Integer(1), Value::Float(f64::NAN)]).unwrap();
+        assert!(matches!(result, Value::Float(f) if f.is_nan()));
+        let result = min_func(&[Value::Float(f64::NAN), Value::Integer(1)]).unwrap();
+        assert!(matches!(result, Value::Float(f) if f.is_nan()));
+
+        // 混合精确/非精确参数得到非精确结果
+        assert_eq!(max_func(&[Value::Integer(3), Value::Float(1.5)]).unwrap(), Value::Float(3.0));
+        assert_eq!(min_func(&[Value::Integer(3), Value::Float(1.5)]).unwrap(), Value::Float(1.5));
+
+        // 全部精确时保持整数
+        assert_eq!(max_func(&[Value::Integer(-2), Value::Integer(7)]).unwrap(), Value::Integer(7));
+        assert_eq!(min_func(&[Value::Integer(5)]).unwrap(), Value::Integer(5));
+
+        assert!(max_func(&[]).is_err());
+        assert!(min_func(&[Value::Integer(1), Value::Nil]).is_err());
+    }
 }
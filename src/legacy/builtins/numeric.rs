@@ -0,0 +1,479 @@
+use crate::legacy::types::{Value, SchemeError, Result};
+use crate::legacy::builtins::control::make_values;
+
+/// 整数除法的操作数：都是精确整数时按精确数计算，含整数值的浮点数时按非精确数计算
+enum Operands {
+    Exact(i64, i64),
+    Inexact(f64, f64),
+}
+
+/// 取出两个整数参数（可以是整数值的浮点数），除数为零时报错
+fn integer_operands(name: &str, args: &[Value]) -> Result<Operands> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError(format!("{name} requires exactly 2 arguments"), None));
+    }
+
+    match (&args[0], &args[1]) {
+        (Value::Integer(_), Value::Integer(0)) => Err(SchemeError::DivisionByZero(None)),
+        (Value::Integer(n), Value::Integer(d)) => Ok(Operands::Exact(*n, *d)),
+        (n, d) => match (integer_value(name, n)?, integer_value(name, d)?) {
+            (_, 0.0) => Err(SchemeError::DivisionByZero(None)),
+            (n, d) => Ok(Operands::Inexact(n, d)),
+        },
+    }
+}
+
+/// 整数或整数值的浮点数（如 7.0）转为浮点数
+fn integer_value(name: &str, value: &Value) -> Result<f64> {
+    match value {
+        Value::Integer(n) => Ok(*n as f64),
+        Value::Float(f) if f.is_finite() && f.fract() == 0.0 => Ok(*f),
+        other => Err(SchemeError::TypeError(format!("{name} expects integers, got {other}"), None)),
+    }
+}
+
+/// 截断除法：商向零取整，余数与被除数同号；有非精确操作数时结果也是非精确数
+fn truncate_div(name: &str, args: &[Value]) -> Result<(Value, Value)> {
+    match integer_operands(name, args)? {
+        Operands::Exact(n, d) => {
+            let (quotient, remainder) = truncate_div_integers(n, d)?;
+            Ok((Value::Integer(quotient), Value::Integer(remainder)))
+        },
+        Operands::Inexact(n, d) => {
+            let (quotient, remainder) = truncate_div_floats(n, d);
+            Ok((Value::Float(quotient), Value::Float(remainder)))
+        },
+    }
+}
+
+fn truncate_div_integers(n: i64, d: i64) -> Result<(i64, i64)> {
+    let quotient = n.checked_div(d).ok_or_else(|| SchemeError::RuntimeError("integer overflow".to_string(), None))?;
+    Ok((quotient, n - quotient * d))
+}
+
+fn truncate_div_floats(n: f64, d: f64) -> (f64, f64) {
+    let remainder = n % d;
+    ((n - remainder) / d, remainder)
+}
+
+/// 向下取整除法：商向负无穷取整，余数与除数同号；有非精确操作数时结果也是非精确数
+fn floor_div(name: &str, args: &[Value]) -> Result<(Value, Value)> {
+    match integer_operands(name, args)? {
+        Operands::Exact(n, d) => {
+            let (mut quotient, mut remainder) = truncate_div_integers(n, d)?;
+            if remainder != 0 && (remainder < 0) != (d < 0) {
+                quotient -= 1;
+                remainder += d;
+            }
+            Ok((Value::Integer(quotient), Value::Integer(remainder)))
+        },
+        Operands::Inexact(n, d) => {
+            let (mut quotient, mut remainder) = truncate_div_floats(n, d);
+            if remainder != 0.0 && (remainder < 0.0) != (d < 0.0) {
+                quotient -= 1.0;
+                remainder += d;
+            }
+            Ok((Value::Float(quotient), Value::Float(remainder)))
+        },
+    }
+}
+
+/// (floor/ n d)：返回两个值：商和余数
+pub fn floor_slash(args: &[Value]) -> Result<Value> {
+    let (q, r) = floor_div("floor/", args)?;
+    Ok(make_values(vec![q, r]))
+}
+
+/// (floor-quotient n d)
+pub fn floor_quotient(args: &[Value]) -> Result<Value> {
+    floor_div("floor-quotient", args).map(|(q, _)| q)
+}
+
+/// (floor-remainder n d)
+pub fn floor_remainder(args: &[Value]) -> Result<Value> {
+    floor_div("floor-remainder", args).map(|(_, r)| r)
+}
+
+/// (truncate/ n d)：返回两个值：商和余数
+pub fn truncate_slash(args: &[Value]) -> Result<Value> {
+    let (q, r) = truncate_div("truncate/", args)?;
+    Ok(make_values(vec![q, r]))
+}
+
+/// (truncate-quotient n d)
+pub fn truncate_quotient(args: &[Value]) -> Result<Value> {
+    truncate_div("truncate-quotient", args).map(|(q, _)| q)
+}
+
+/// (truncate-remainder n d)
+pub fn truncate_remainder(args: &[Value]) -> Result<Value> {
+    truncate_div("truncate-remainder", args).map(|(_, r)| r)
+}
+
+/// 按 rounding 对实数取整：精确整数原样返回（保持精确），浮点数返回取整后的浮点数（保持非精确）。
+/// 尚无有理数类型，精确的非整数输入（如 7/2）无从表示
+fn round_with(name: &str, value: &Value, rounding: fn(f64) -> f64) -> Result<Value> {
+    match value {
+        Value::Integer(n) => Ok(Value::Integer(*n)),
+        Value::Float(f) => Ok(Value::Float(rounding(*f))),
+        other => Err(SchemeError::TypeError(format!("{name} expects a real number, got {other}"), None)),
+    }
+}
+
+/// (floor x)：不大于 x 的最大整数
+pub fn floor(args: &[Value]) -> Result<Value> {
+    round_with("floor", &args[0], f64::floor)
+}
+
+/// (ceiling x)：不小于 x 的最小整数
+pub fn ceiling(args: &[Value]) -> Result<Value> {
+    round_with("ceiling", &args[0], f64::ceil)
+}
+
+/// (truncate x)：向零取整
+pub fn truncate(args: &[Value]) -> Result<Value> {
+    round_with("truncate", &args[0], f64::trunc)
+}
+
+/// (round x)：取最接近的整数，恰在两者中间时取偶数
+pub fn round(args: &[Value]) -> Result<Value> {
+    round_with("round", &args[0], f64::round_ties_even)
+}
+
+/// (number->string z [radix])：整数支持 2、8、10、16 进制（负数带负号，不加进制前缀），
+/// 浮点数只支持 10 进制
+pub fn number_to_string(args: &[Value]) -> Result<Value> {
+    let radix = radix_argument("number->string", args.get(1))?;
+
+    match &args[0] {
+        Value::Integer(n) => {
+            let magnitude = n.unsigned_abs();
+            let digits = match radix {
+                2 => format!("{magnitude:b}"),
+                8 => format!("{magnitude:o}"),
+                16 => format!("{magnitude:x}"),
+                _ => magnitude.to_string(),
+            };
+            Ok(Value::string(if *n < 0 { format!("-{digits}") } else { digits }))
+        },
+        Value::Float(_) if radix == 10 => Ok(Value::string(args[0].to_string())),
+        Value::Float(_) => Err(SchemeError::RuntimeError(
+            format!("number->string: inexact numbers only support radix 10, got {radix}"), None
+        )),
+        other => Err(SchemeError::TypeError(format!("number->string expects a number, got {other}"), None)),
+    }
+}
+
+/// 取出可选的进制参数，只支持 2、8、10、16
+fn radix_argument(name: &str, value: Option<&Value>) -> Result<u32> {
+    match value {
+        None => Ok(10),
+        Some(Value::Integer(radix @ (2 | 8 | 10 | 16))) => Ok(*radix as u32),
+        Some(other) => Err(SchemeError::RuntimeError(format!("{name}: radix must be 2, 8, 10 or 16, got {other}"), None)),
+    }
+}
+
+/// 解析数字文本：#b/#o/#d/#x 前缀覆盖 radix；非 10 进制只接受整数。
+/// 10 进制还接受小数、指数以及 +inf.0、-inf.0、+nan.0。无法解析（包括溢出）时返回 None
+fn parse_number(text: &str, radix: u32) -> Option<Value> {
+    let (radix, digits) = match text.get(..2).map(str::to_ascii_lowercase).as_deref() {
+        Some("#b") => (2, &text[2..]),
+        Some("#o") => (8, &text[2..]),
+        Some("#d") => (10, &text[2..]),
+        Some("#x") => (16, &text[2..]),
+        _ => (radix, text),
+    };
+
+    // 符号之后必须都是该进制的数字，排除空串以及 Rust 接受而 Scheme 不接受的写法（如 "inf"）
+    let unsigned = digits.strip_prefix(['+', '-']).unwrap_or(digits);
+    if unsigned.is_empty() {
+        return None;
+    }
+    if unsigned.chars().all(|c| c.is_digit(radix)) {
+        return i64::from_str_radix(digits, radix).ok().map(Value::Integer);
+    }
+    if radix != 10 {
+        return None;
+    }
+    match digits {
+        "+inf.0" => Some(Value::Float(f64::INFINITY)),
+        "-inf.0" => Some(Value::Float(f64::NEG_INFINITY)),
+        "+nan.0" | "-nan.0" => Some(Value::Float(f64::NAN)),
+        _ if unsigned.chars().all(|c| c.is_ascii_digit() || ".eE+-".contains(c)) => {
+            digits.parse().ok().map(Value::Float)
+        },
+        _ => None,
+    }
+}
+
+/// (string->number string [radix])：按 radix（默认 10）解析数字，无法解析时返回 #f；
+/// 字符串自带的 #b/#o/#d/#x 前缀优先于 radix 参数
+pub fn string_to_number(args: &[Value]) -> Result<Value> {
+    let Some(text) = args[0].string_contents() else {
+        return Err(SchemeError::TypeError(format!("string->number expects a string, got {}", args[0]), None));
+    };
+    let radix = radix_argument("string->number", args.get(1))?;
+    Ok(parse_number(&text, radix).unwrap_or(Value::Bool(false)))
+}
+
+/// (square z)：z 与自身的乘积，保持精确性
+pub fn square(args: &[Value]) -> Result<Value> {
+    match &args[0] {
+        Value::Integer(n) => n.checked_mul(*n).map(Value::Integer)
+            .ok_or_else(|| SchemeError::RuntimeError("integer overflow".to_string(), None)),
+        Value::Float(f) => Ok(Value::Float(f * f)),
+        other => Err(SchemeError::TypeError(format!("square expects a number, got {other}"), None)),
+    }
+}
+
+/// (exact-integer-sqrt k)：返回两个值 s 与 r，s 为不超过 k 的平方根的最大整数，k = s² + r
+pub fn exact_integer_sqrt(args: &[Value]) -> Result<Value> {
+    match &args[0] {
+        Value::Integer(k) if *k >= 0 => {
+            let root = k.isqrt();
+            Ok(make_values(vec![Value::Integer(root), Value::Integer(k - root * root)]))
+        },
+        other => Err(SchemeError::TypeError(
+            format!("exact-integer-sqrt expects a non-negative exact integer, got {other}"), None
+        )),
+    }
+}
+
+/// (real? obj)：当前所有数值（整数与浮点数）都是实数
+pub fn is_real(args: &[Value]) -> Result<Value> {
+    Ok(Value::Bool(matches!(args[0], Value::Integer(_) | Value::Float(_))))
+}
+
+/// (rational? obj)：整数与有限浮点数是有理数，无穷与 NaN 不是
+pub fn is_rational(args: &[Value]) -> Result<Value> {
+    Ok(Value::Bool(match args[0] {
+        Value::Integer(_) => true,
+        Value::Float(f) => f.is_finite(),
+        _ => false,
+    }))
+}
+
+/// (exact-integer? obj)：仅精确整数
+pub fn is_exact_integer(args: &[Value]) -> Result<Value> {
+    Ok(Value::Bool(matches!(args[0], Value::Integer(_))))
+}
+
+/// 取出实数参数的符号，非数值时报错；NaN 既不为正也不为负
+fn sign(name: &str, value: &Value) -> Result<std::cmp::Ordering> {
+    match value {
+        Value::Integer(n) => Ok(n.cmp(&0)),
+        Value::Float(f) => Ok(f.partial_cmp(&0.0).unwrap_or(std::cmp::Ordering::Equal)),
+        other => Err(SchemeError::TypeError(format!("{name} expects a real number, got {other}"), None)),
+    }
+}
+
+/// (positive? x)
+pub fn is_positive(args: &[Value]) -> Result<Value> {
+    sign("positive?", &args[0]).map(|ordering| Value::Bool(ordering.is_gt()))
+}
+
+/// (negative? x)
+pub fn is_negative(args: &[Value]) -> Result<Value> {
+    sign("negative?", &args[0]).map(|ordering| Value::Bool(ordering.is_lt()))
+}
+
+/// 判断整数参数是否为奇数；整值浮点数（如 2.0）也视为整数，其余报错
+fn is_odd_integer(name: &str, value: &Value) -> Result<bool> {
+    match value {
+        Value::Integer(n) => Ok(n % 2 != 0),
+        Value::Float(f) if f.is_finite() && f.fract() == 0.0 => Ok(f % 2.0 != 0.0),
+        other => Err(SchemeError::TypeError(format!("{name} expects an integer, got {other}"), None)),
+    }
+}
+
+/// (odd? n)
+pub fn is_odd(args: &[Value]) -> Result<Value> {
+    is_odd_integer("odd?", &args[0]).map(Value::Bool)
+}
+
+/// (even? n)
+pub fn is_even(args: &[Value]) -> Result<Value> {
+    is_odd_integer("even?", &args[0]).map(|odd| Value::Bool(!odd))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ints(n: i64, d: i64) -> [Value; 2] {
+        [Value::Integer(n), Value::Integer(d)]
+    }
+
+    #[test]
+    fn test_floor_vs_truncate_signs() {
+        // 正数两者一致
+        assert_eq!(floor_slash(&ints(7, 2)).unwrap(), Value::MultipleValues(vec![Value::Integer(3), Value::Integer(1)]));
+        assert_eq!(truncate_slash(&ints(7, 2)).unwrap(), Value::MultipleValues(vec![Value::Integer(3), Value::Integer(1)]));
+
+        // 负被除数
+        assert_eq!(floor_slash(&ints(-7, 2)).unwrap(), Value::MultipleValues(vec![Value::Integer(-4), Value::Integer(1)]));
+        assert_eq!(truncate_slash(&ints(-7, 2)).unwrap(), Value::MultipleValues(vec![Value::Integer(-3), Value::Integer(-1)]));
+
+        // 负除数
+        assert_eq!(floor_quotient(&ints(7, -2)).unwrap(), Value::Integer(-4));
+        assert_eq!(floor_remainder(&ints(7, -2)).unwrap(), Value::Integer(-1));
+        assert_eq!(truncate_quotient(&ints(7, -2)).unwrap(), Value::Integer(-3));
+        assert_eq!(truncate_remainder(&ints(7, -2)).unwrap(), Value::Integer(1));
+
+        // 两者皆负
+        assert_eq!(floor_remainder(&ints(-7, -2)).unwrap(), Value::Integer(-1));
+        assert_eq!(truncate_remainder(&ints(-7, -2)).unwrap(), Value::Integer(-1));
+    }
+
+    #[test]
+    fn test_integer_division_with_inexact_operands() {
+        let floats = |values: &[f64]| Value::MultipleValues(values.iter().map(|f| Value::Float(*f)).collect());
+
+        // 整数值的浮点数可以参与运算，结果为非精确数
+        assert_eq!(floor_slash(&[Value::Float(7.0), Value::Integer(2)]).unwrap(), floats(&[3.0, 1.0]));
+        assert_eq!(floor_slash(&[Value::Integer(-7), Value::Float(2.0)]).unwrap(), floats(&[-4.0, 1.0]));
+        assert_eq!(truncate_slash(&[Value::Float(-7.0), Value::Integer(2)]).unwrap(), floats(&[-3.0, -1.0]));
+        assert_eq!(floor_remainder(&[Value::Float(7.0), Value::Float(-2.0)]).unwrap(), Value::Float(-1.0));
+        assert_eq!(truncate_quotient(&[Value::Float(7.0), Value::Float(-2.0)]).unwrap(), Value::Float(-3.0));
+
+        // 非整数值与零除数仍然报错
+        assert!(matches!(floor_slash(&[Value::Float(7.5), Value::Integer(2)]), Err(SchemeError::TypeError(..))));
+        assert!(matches!(truncate_slash(&[Value::Float(7.0), Value::Float(0.0)]), Err(SchemeError::DivisionByZero(_))));
+    }
+
+    #[test]
+    fn test_number_to_string_radix() {
+        let to_string = |args: &[Value]| number_to_string(args).unwrap();
+
+        assert_eq!(to_string(&[Value::Integer(255), Value::Integer(16)]), Value::string("ff"));
+        assert_eq!(to_string(&[Value::Integer(5), Value::Integer(2)]), Value::string("101"));
+        assert_eq!(to_string(&[Value::Integer(8), Value::Integer(8)]), Value::string("10"));
+        assert_eq!(to_string(&[Value::Integer(-255), Value::Integer(16)]), Value::string("-ff"));
+        assert_eq!(to_string(&[Value::Integer(i64::MIN), Value::Integer(2)]), Value::string(format!("-1{}", "0".repeat(63))));
+        assert_eq!(to_string(&[Value::Integer(42)]), Value::string("42"));
+
+        // 浮点数只支持 10 进制
+        assert_eq!(to_string(&[Value::Float(1.5)]), Value::string("1.5"));
+        assert_eq!(to_string(&[Value::Float(1.5), Value::Integer(10)]), Value::string("1.5"));
+        assert!(number_to_string(&[Value::Float(1.5), Value::Integer(16)]).is_err());
+
+        assert!(number_to_string(&[Value::Integer(1), Value::Integer(3)]).is_err());
+        assert!(number_to_string(&[Value::string("1")]).is_err());
+    }
+
+    #[test]
+    fn test_string_to_number_radix() {
+        let parse = |text: &str, radix: Option<i64>| {
+            let mut args = vec![Value::string(text.to_string())];
+            args.extend(radix.map(Value::Integer));
+            string_to_number(&args).unwrap()
+        };
+
+        assert_eq!(parse("ff", Some(16)), Value::Integer(255));
+        assert_eq!(parse("-FF", Some(16)), Value::Integer(-255));
+        assert_eq!(parse("101", Some(2)), Value::Integer(5));
+        assert_eq!(parse("17", Some(8)), Value::Integer(15));
+        assert_eq!(parse("42", None), Value::Integer(42));
+        assert_eq!(parse("-1.5e2", None), Value::Float(-150.0));
+        assert_eq!(parse("+inf.0", None), Value::Float(f64::INFINITY));
+
+        // 前缀优先于 radix 参数
+        assert_eq!(parse("#xff", None), Value::Integer(255));
+        assert_eq!(parse("#XFF", Some(2)), Value::Integer(255));
+        assert_eq!(parse("#b101", Some(16)), Value::Integer(5));
+        assert_eq!(parse("#o-17", None), Value::Integer(-15));
+        assert_eq!(parse("#d10", Some(16)), Value::Integer(10));
+
+        // 无法解析时返回 #f
+        for (text, radix) in [("ff", None), ("2", Some(2)), ("", None), ("-", None), ("#x", None), ("1.5", Some(16)),
+                              ("inf", None), ("1e", None), ("abc", None), ("99999999999999999999", None), ("#xg", None)] {
+            assert_eq!(parse(text, radix), Value::Bool(false), "{text:?}");
+        }
+
+        assert!(string_to_number(&[Value::string("1"), Value::Integer(3)]).is_err());
+        assert!(string_to_number(&[Value::Integer(1)]).is_err());
+    }
+
+    #[test]
+    fn test_rounding_preserves_exactness() {
+        // 精确整数输入得到精确整数
+        for f in [floor, ceiling, truncate, round] {
+            assert_eq!(f(&[Value::Integer(-7)]).unwrap(), Value::Integer(-7));
+        }
+
+        // 浮点数输入得到浮点数
+        assert_eq!(floor(&[Value::Float(3.5)]).unwrap(), Value::Float(3.0));
+        assert_eq!(ceiling(&[Value::Float(3.5)]).unwrap(), Value::Float(4.0));
+        assert_eq!(truncate(&[Value::Float(-3.5)]).unwrap(), Value::Float(-3.0));
+        assert_eq!(floor(&[Value::Float(-3.5)]).unwrap(), Value::Float(-4.0));
+
+        // round 在中间值时取偶数
+        assert_eq!(round(&[Value::Float(2.5)]).unwrap(), Value::Float(2.0));
+        assert_eq!(round(&[Value::Float(3.5)]).unwrap(), Value::Float(4.0));
+        assert_eq!(round(&[Value::Float(-2.5)]).unwrap(), Value::Float(-2.0));
+        assert_eq!(round(&[Value::Float(2.6)]).unwrap(), Value::Float(3.0));
+
+        assert!(floor(&[Value::string("1")]).is_err());
+    }
+
+    #[test]
+    fn test_square_and_exact_integer_sqrt() {
+        assert_eq!(square(&[Value::Integer(5)]).unwrap(), Value::Integer(25));
+        assert_eq!(square(&[Value::Integer(-3)]).unwrap(), Value::Integer(9));
+        assert_eq!(square(&[Value::Float(1.5)]).unwrap(), Value::Float(2.25));
+        assert!(square(&[Value::Integer(i64::MAX)]).is_err());
+        assert!(square(&[Value::symbol("x")]).is_err());
+
+        let sqrt = |k: i64| exact_integer_sqrt(&[Value::Integer(k)]).unwrap();
+        // 完全平方数余数为零
+        assert_eq!(sqrt(16), Value::MultipleValues(vec![Value::Integer(4), Value::Integer(0)]));
+        assert_eq!(sqrt(0), Value::MultipleValues(vec![Value::Integer(0), Value::Integer(0)]));
+        // 非完全平方数
+        assert_eq!(sqrt(17), Value::MultipleValues(vec![Value::Integer(4), Value::Integer(1)]));
+        assert_eq!(sqrt(i64::MAX), Value::MultipleValues(vec![Value::Integer(3037000499), Value::Integer(5928526806)]));
+
+        // 负数与非精确整数报错
+        assert!(matches!(exact_integer_sqrt(&[Value::Integer(-1)]), Err(SchemeError::TypeError(..))));
+        assert!(matches!(exact_integer_sqrt(&[Value::Float(16.0)]), Err(SchemeError::TypeError(..))));
+    }
+
+    #[test]
+    fn test_division_errors() {
+        assert_eq!(floor_slash(&ints(1, 0)), Err(SchemeError::DivisionByZero(None)));
+        assert_eq!(truncate_quotient(&ints(1, 0)), Err(SchemeError::DivisionByZero(None)));
+        assert!(floor_quotient(&[Value::Float(1.5), Value::Integer(1)]).is_err());
+    }
+
+    #[test]
+    fn test_number_predicates() {
+        let check = |f: fn(&[Value]) -> Result<Value>, value: Value| f(&[value]).unwrap();
+
+        // real? / rational? / exact-integer?
+        assert_eq!(check(is_real, Value::Integer(1)), Value::Bool(true));
+        assert_eq!(check(is_real, Value::Float(f64::NAN)), Value::Bool(true));
+        assert_eq!(check(is_real, Value::string("1")), Value::Bool(false));
+        assert_eq!(check(is_rational, Value::Integer(1)), Value::Bool(true));
+        assert_eq!(check(is_rational, Value::Float(0.5)), Value::Bool(true));
+        assert_eq!(check(is_rational, Value::Float(f64::INFINITY)), Value::Bool(false));
+        assert_eq!(check(is_rational, Value::Float(f64::NAN)), Value::Bool(false));
+        assert_eq!(check(is_exact_integer, Value::Integer(3)), Value::Bool(true));
+        assert_eq!(check(is_exact_integer, Value::Float(3.0)), Value::Bool(false));
+
+        // positive? / negative?
+        assert_eq!(check(is_positive, Value::Integer(2)), Value::Bool(true));
+        assert_eq!(check(is_positive, Value::Integer(0)), Value::Bool(false));
+        assert_eq!(check(is_positive, Value::Float(-0.5)), Value::Bool(false));
+        assert_eq!(check(is_negative, Value::Float(-0.5)), Value::Bool(true));
+        assert_eq!(check(is_negative, Value::Integer(0)), Value::Bool(false));
+        assert_eq!(check(is_negative, Value::Float(f64::NAN)), Value::Bool(false));
+        assert!(is_positive(&[Value::Nil]).is_err());
+
+        // odd? / even?：负数与整值浮点数
+        assert_eq!(check(is_odd, Value::Integer(-3)), Value::Bool(true));
+        assert_eq!(check(is_even, Value::Integer(-4)), Value::Bool(true));
+        assert_eq!(check(is_even, Value::Float(2.0)), Value::Bool(true));
+        assert_eq!(check(is_odd, Value::Float(2.0)), Value::Bool(false));
+        assert!(matches!(is_odd(&[Value::Float(1.5)]), Err(SchemeError::TypeError(_, _))));
+        assert!(matches!(is_even(&[Value::string("2")]), Err(SchemeError::TypeError(_, _))));
+    }
+}
@@ -0,0 +1,592 @@
+use std::cell::RefCell;
+use std::io::Write;
+use std::rc::Rc;
+use crate::legacy::types::{Value, SchemeError, Result, Port, InputPort};
+use crate::legacy::types::printer::{self, Style, Labels};
+use crate::legacy::eval::{CoreEvaluator, EvaluationContext};
+use crate::legacy::lexer::{Lexer, Token};
+use crate::legacy::parser::Parser;
+
+/// 取出输入端口参数并对其执行操作
+fn with_input_port<T>(name: &str, value: &Value, f: impl FnOnce(&mut InputPort) -> T) -> Result<T> {
+    if let Value::Port(port) = value {
+        match &mut *port.borrow_mut() {
+            Port::Input(input) => return Ok(f(input)),
+            Port::Closed => return Err(SchemeError::RuntimeError(format!("{name}: port is closed"), None)),
+            _ => {},
+        }
+    }
+    Err(SchemeError::TypeError(format!("{name} expects an input port, got {value}"), None))
+}
+
+/// 将文本写到 port；未提供 port 时写到当前输出
+fn write_to_port(name: &str, port: Option<&Value>, text: &str) -> Result<()> {
+    let Some(port) = port else {
+        write_to_current_output(text);
+        return Ok(());
+    };
+    if let Value::Port(port) = port {
+        match &mut *port.borrow_mut() {
+            Port::Output(buffer) => {
+                buffer.push_str(text);
+                return Ok(());
+            },
+            Port::File(writer) => return Ok(writer.write_all(text.as_bytes())?),
+            Port::Closed => return Err(SchemeError::RuntimeError(format!("{name}: port is closed"), None)),
+            Port::Input(_) => {},
+        }
+    }
+    Err(SchemeError::TypeError(format!("{name} expects an output port, got {port}"), None))
+}
+
+/// (open-input-string string)：创建读取字符串的输入端口
+pub fn open_input_string(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("open-input-string requires exactly 1 argument".to_string(), None));
+    }
+
+    match args[0].string_contents() {
+        Some(s) => Ok(Value::Port(Rc::new(RefCell::new(Port::Input(InputPort::from_string(&s)))))),
+        None => Err(SchemeError::TypeError(format!("open-input-string expects a string, got {}", args[0]), None)),
+    }
+}
+
+/// (open-output-string)：创建累积写入文本的输出端口，内容由 get-output-string 取出
+pub fn open_output_string(args: &[Value]) -> Result<Value> {
+    if !args.is_empty() {
+        return Err(SchemeError::ArityError("open-output-string requires no arguments".to_string(), None));
+    }
+
+    Ok(Value::Port(Rc::new(RefCell::new(Port::Output(String::new())))))
+}
+
+/// (get-output-string port)：返回目前为止写到字符串输出端口的全部文本
+pub fn get_output_string(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("get-output-string requires exactly 1 argument".to_string(), None));
+    }
+
+    if let Value::Port(port) = &args[0] {
+        if let Port::Output(buffer) = &*port.borrow() {
+            return Ok(Value::string(buffer.clone()));
+        }
+    }
+    Err(SchemeError::TypeError(format!("get-output-string expects an output string port, got {}", args[0]), None))
+}
+
+/// (close-port port)：关闭端口。文件输出端口先写出缓冲的内容；输入端口关闭后不能再读取。
+/// 字符串输出端口保持打开，关闭后仍可由 get-output-string 取出内容
+pub fn close_port(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("close-port requires exactly 1 argument".to_string(), None));
+    }
+
+    match &args[0] {
+        Value::Port(port) => {
+            let mut port = port.borrow_mut();
+            match &mut *port {
+                Port::File(writer) => {
+                    writer.flush()?;
+                    *port = Port::Closed;
+                },
+                Port::Input(_) => *port = Port::Closed,
+                Port::Output(_) | Port::Closed => {},
+            }
+            Ok(Value::Unspecified)
+        },
+        other => Err(SchemeError::TypeError(format!("close-port expects a port, got {other}"), None)),
+    }
+}
+
+/// 取出文件名参数
+fn expect_file_name(name: &str, value: &Value) -> Result<String> {
+    match value.string_contents() {
+        Some(s) => Ok(s.to_string()),
+        None => Err(SchemeError::TypeError(format!("{name} expects a file name string, got {value}"), None)),
+    }
+}
+
+/// (open-input-file filename)：打开文件作为输入端口；文件内容在打开时整体读入
+pub fn open_input_file(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("open-input-file requires exactly 1 argument".to_string(), None));
+    }
+
+    let path = expect_file_name("open-input-file", &args[0])?;
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|error| std::io::Error::new(error.kind(), format!("cannot open {path}: {error}")))?;
+    Ok(Value::Port(Rc::new(RefCell::new(Port::Input(InputPort::from_string(&contents))))))
+}
+
+/// (open-output-file filename)：创建（或截断）文件并作为输出端口打开，写入的内容在 close-port 时落盘
+pub fn open_output_file(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("open-output-file requires exactly 1 argument".to_string(), None));
+    }
+
+    let path = expect_file_name("open-output-file", &args[0])?;
+    let file = std::fs::File::create(&path)
+        .map_err(|error| std::io::Error::new(error.kind(), format!("cannot open {path}: {error}")))?;
+    Ok(Value::Port(Rc::new(RefCell::new(Port::File(std::io::BufWriter::new(file))))))
+}
+
+/// 以 port 调用 proc，返回（包括出错）后关闭 port
+fn call_with_port(port: Value, proc: &Value, evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    let result = evaluator.apply(proc, vec![port.clone()], context);
+    let closed = close_port(&[port]);
+    let value = result?;
+    closed?;
+    Ok(value)
+}
+
+/// (call-with-input-file filename proc)：以读取文件的输入端口调用 proc，返回 proc 的结果
+pub fn call_with_input_file(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("call-with-input-file requires exactly 2 arguments".to_string(), None));
+    }
+
+    let port = open_input_file(&args[..1])?;
+    call_with_port(port, &args[1], evaluator, context)
+}
+
+/// (call-with-output-file filename proc)：以写入文件的输出端口调用 proc，
+/// proc 返回（包括出错）后关闭端口，返回 proc 的结果
+pub fn call_with_output_file(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("call-with-output-file requires exactly 2 arguments".to_string(), None));
+    }
+
+    let port = open_output_file(&args[..1])?;
+    call_with_port(port, &args[1], evaluator, context)
+}
+
+/// (peek-char port)：返回下一个字符但不消费
+pub fn peek_char(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("peek-char requires exactly 1 argument".to_string(), None));
+    }
+
+    let next = with_input_port("peek-char", &args[0], |port| port.peek_char())?;
+    Ok(next.map(Value::Char).unwrap_or(Value::Eof))
+}
+
+/// (read-char port)：消费并返回下一个字符，末尾返回 EOF 对象
+pub fn read_char(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("read-char requires exactly 1 argument".to_string(), None));
+    }
+
+    let next = with_input_port("read-char", &args[0], |port| port.read_char())?;
+    Ok(next.map(Value::Char).unwrap_or(Value::Eof))
+}
+
+/// (char-ready? port)：字符串端口总是就绪
+pub fn is_char_ready(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("char-ready? requires exactly 1 argument".to_string(), None));
+    }
+
+    with_input_port("char-ready?", &args[0], |_| Value::Bool(true))
+}
+
+/// (eof-object? obj)
+pub fn is_eof_object(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("eof-object? requires exactly 1 argument".to_string(), None));
+    }
+
+    Ok(Value::Bool(matches!(args[0], Value::Eof)))
+}
+
+/// 用词法分析器找出 text 中第一个完整数据的结束位置（字符下标）。
+/// 还需要更多字符才能确定时返回 None；at_end 表示端口中已没有更多字符，此时不完整的数据是语法错误
+fn datum_end(text: &str, at_end: bool) -> Result<Option<usize>> {
+    let mut lexer = Lexer::new(text);
+    let mut depth = 0;
+    let mut started = false;
+    loop {
+        let token = match lexer.next_token() {
+            Ok(token) => token,
+            // 如未结束的字符串：可能只是还没读到后面的字符
+            Err(_) if !at_end => return Ok(None),
+            Err(e) => return Err(SchemeError::SyntaxError(format!("read: {e}"), None)),
+        };
+        // 引用前缀之后还要继续读取被引用的数据
+        let is_prefix = matches!(token, Token::Quote | Token::Quasiquote | Token::Unquote | Token::UnquoteSplicing);
+        match token {
+            Token::EOF if at_end && started => {
+                return Err(SchemeError::SyntaxError("read: unexpected end of input".to_string(), None));
+            },
+            Token::EOF => return Ok(None),
+            Token::LeftParen | Token::VectorStart => depth += 1,
+            // 多余的 ) 交由语法分析器报错
+            Token::RightParen if depth == 0 => return Ok(Some(lexer.offset())),
+            Token::RightParen => depth -= 1,
+            _ => {},
+        }
+        started = true;
+        if depth == 0 && !is_prefix {
+            return Ok(Some(lexer.offset()));
+        }
+    }
+}
+
+/// 从端口读出下一个数据：逐行读入字符，直到词法分析器能确定第一个数据的结束位置，
+/// 该位置之后多读的字符退回端口。端口中没有剩余数据时返回 EOF 对象
+fn read_datum(port: &mut InputPort) -> Result<Value> {
+    let mut chars = Vec::new();
+    loop {
+        let mut at_end = true;
+        while let Some(ch) = port.read_char() {
+            chars.push(ch);
+            if ch == '\n' {
+                at_end = false;
+                break;
+            }
+        }
+
+        let text: String = chars.iter().collect();
+        match datum_end(&text, at_end)? {
+            Some(end) => {
+                port.unread(&chars[end..]);
+                return Parser::parse(&chars[..end].iter().collect::<String>());
+            },
+            None if at_end => return Ok(Value::Eof),
+            None => {},
+        }
+    }
+}
+
+/// (read [port])：从端口（默认为当前输入端口）读取一个数据，没有剩余数据时返回 EOF 对象
+pub fn read(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    if args.len() > 1 {
+        return Err(SchemeError::ArityError("read accepts at most 1 argument".to_string(), None));
+    }
+
+    let port = match args.first() {
+        Some(port) => port.clone(),
+        None => evaluator.current_input_port(context),
+    };
+    with_input_port("read", &port, read_datum)?
+}
+
+/// (read-line [port])：读取到换行符为止的一行（不含换行符），没有剩余字符时返回 EOF 对象
+pub fn read_line(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    if args.len() > 1 {
+        return Err(SchemeError::ArityError("read-line accepts at most 1 argument".to_string(), None));
+    }
+
+    let port = match args.first() {
+        Some(port) => port.clone(),
+        None => evaluator.current_input_port(context),
+    };
+    let line = with_input_port("read-line", &port, |port| {
+        let mut line = String::new();
+        loop {
+            match port.read_char() {
+                Some('\n') => return Some(line),
+                Some(ch) => line.push(ch),
+                None if line.is_empty() => return None,
+                None => return Some(line),
+            }
+        }
+    })?;
+    Ok(line.map(Value::string).unwrap_or(Value::Eof))
+}
+
+/// (with-input-from-string string thunk)：在 thunk 的动态范围内将当前输入端口重绑定为读取 string 的端口，
+/// 返回（包括出错）后自动恢复
+pub fn with_input_from_string(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("with-input-from-string requires exactly 2 arguments".to_string(), None));
+    }
+
+    let port = open_input_string(&args[..1])?;
+    let new_context = context.cloned().unwrap_or_default()
+        .with_dynamic_binding(evaluator.current_input_port_parameter(), port);
+    evaluator.apply(&args[1], vec![], Some(&new_context))
+}
+
+/// 按格式串生成字符串，支持 ~a (display)、~s (write)、~% (换行) 和 ~~ (波浪号)
+pub fn format_string(template: &str, args: &[Value]) -> Result<String> {
+    let mut result = String::new();
+    let mut remaining = args.iter();
+    let mut chars = template.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '~' {
+            result.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some(directive @ ('a' | 'A' | 's' | 'S')) => {
+                let arg = remaining.next().ok_or_else(|| SchemeError::RuntimeError(
+                    format!("format: too few arguments for directive ~{directive}"), None
+                ))?;
+                if directive.eq_ignore_ascii_case(&'a') {
+                    result.push_str(&arg.to_display_string());
+                } else {
+                    result.push_str(&arg.to_string());
+                }
+            },
+            Some('%') => result.push('\n'),
+            Some('~') => result.push('~'),
+            Some(other) => return Err(SchemeError::RuntimeError(
+                format!("format: unknown directive ~{other}"), None
+            )),
+            None => return Err(SchemeError::RuntimeError(
+                "format: incomplete directive at end of format string".to_string(), None
+            )),
+        }
+    }
+
+    Ok(result)
+}
+
+thread_local! {
+    /// 正在捕获输出时的缓冲区；为 None 时输出写到标准输出
+    static OUTPUT_CAPTURE: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// 运行 f，期间写到当前输出的文本收集到缓冲区而不是标准输出，
+/// 返回 f 的结果与捕获的文本；可以嵌套，结束后恢复外层的捕获状态
+pub fn capture_output<T>(f: impl FnOnce() -> T) -> (T, String) {
+    let outer = OUTPUT_CAPTURE.with(|capture| capture.replace(Some(String::new())));
+    let result = f();
+    let captured = OUTPUT_CAPTURE.with(|capture| capture.replace(outer)).unwrap_or_default();
+    (result, captured)
+}
+
+/// 将文本写到当前输出（标准输出，或 [`capture_output`] 的缓冲区）
+fn write_to_current_output(text: &str) {
+    let captured = OUTPUT_CAPTURE.with(|capture| match &mut *capture.borrow_mut() {
+        Some(buffer) => {
+            buffer.push_str(text);
+            true
+        },
+        None => false,
+    });
+    if captured {
+        return;
+    }
+    let mut stdout = std::io::stdout();
+    let _ = stdout.write_all(text.as_bytes());
+    let _ = stdout.flush();
+}
+
+/// (format destination format-string arg ...)：destination 为 #f 时返回字符串，为 #t 时写到当前输出
+pub fn format(args: &[Value]) -> Result<Value> {
+    if args.len() < 2 {
+        return Err(SchemeError::ArityError("format requires at least 2 arguments".to_string(), None));
+    }
+
+    let template = match args[1].string_contents() {
+        Some(s) => s,
+        None => return Err(SchemeError::TypeError(format!("format expects a format string, got {}", args[1]), None)),
+    };
+    let text = format_string(&template, &args[2..])?;
+
+    match &args[0] {
+        Value::Bool(false) => Ok(Value::string(text)),
+        Value::Bool(true) => {
+            write_to_current_output(&text);
+            Ok(Value::Unspecified)
+        },
+        other => Err(SchemeError::TypeError(format!("format destination must be #t or #f, got {other}"), None)),
+    }
+}
+
+/// (write-string string [port])：将字符串原样写到 port（默认为当前输出）
+pub fn write_string(args: &[Value]) -> Result<Value> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(SchemeError::ArityError("write-string requires 1 or 2 arguments".to_string(), None));
+    }
+
+    match args[0].string_contents() {
+        Some(s) => {
+            write_to_port("write-string", args.get(1), &s)?;
+            Ok(Value::Unspecified)
+        },
+        None => Err(SchemeError::TypeError(format!("write-string expects a string, got {}", args[0]), None)),
+    }
+}
+
+/// (display obj [port])：以 display 形式（字符串和字符不加引号）写到 port（默认为当前输出）
+pub fn display(args: &[Value]) -> Result<Value> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(SchemeError::ArityError("display requires 1 or 2 arguments".to_string(), None));
+    }
+
+    write_to_port("display", args.get(1), &args[0].to_display_string())?;
+    Ok(Value::Unspecified)
+}
+
+/// (write-char char [port])：将单个字符写到 port（默认为当前输出）
+pub fn write_char(args: &[Value]) -> Result<Value> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(SchemeError::ArityError("write-char requires 1 or 2 arguments".to_string(), None));
+    }
+
+    match &args[0] {
+        Value::Char(c) => {
+            write_to_port("write-char", args.get(1), &c.to_string())?;
+            Ok(Value::Unspecified)
+        },
+        other => Err(SchemeError::TypeError(format!("write-char expects a char, got {other}"), None)),
+    }
+}
+
+/// 以 write 形式按指定标签策略写出 (obj [port]) 参数
+fn write_with_labels(name: &str, args: &[Value], labels: Labels) -> Result<Value> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(SchemeError::ArityError(format!("{name} requires 1 or 2 arguments"), None));
+    }
+
+    write_to_port(name, args.get(1), &printer::print(&args[0], Style::Write, labels))?;
+    Ok(Value::Unspecified)
+}
+
+/// (write obj [port])：以 write 形式写出，仅循环结构使用数据标签，因此总会终止
+pub fn write(args: &[Value]) -> Result<Value> {
+    write_with_labels("write", args, Labels::Cycles)
+}
+
+/// (write-shared obj)：与 write 相同，但所有共享结构都使用 #n= / #n# 数据标签
+pub fn write_shared(args: &[Value]) -> Result<Value> {
+    write_with_labels("write-shared", args, Labels::Shared)
+}
+
+/// (write-simple obj)：不使用数据标签；写出循环结构不会终止
+pub fn write_simple(args: &[Value]) -> Result<Value> {
+    write_with_labels("write-simple", args, Labels::None)
+}
+
+/// (newline [port])：向 port（默认为当前输出）写入换行
+pub fn newline(args: &[Value]) -> Result<Value> {
+    if args.len() > 1 {
+        return Err(SchemeError::ArityError("newline accepts at most 1 argument".to_string(), None));
+    }
+
+    write_to_port("newline", args.first(), "\n")?;
+    Ok(Value::Unspecified)
+}
+
+/// pp 的输出文本：lambda 还原为 (lambda (params ...) body)，内置函数显示名称和参数个数
+pub fn pretty_print(value: &Value) -> String {
+    match value {
+        Value::Lambda { params, body, .. } => {
+            let params = Value::from_vec(params.iter().map(|p| Value::symbol(p)).collect());
+            format!("(lambda {params} {body})")
+        },
+        Value::BuiltinFunction { name, arity, .. } | Value::EvaluatorFunction { name, arity, .. } => {
+            format!("#<builtin:{name} arity={arity}>")
+        },
+        other => other.to_string(),
+    }
+}
+
+/// (pp obj)：将对象的可读形式写到当前输出
+pub fn pp(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("pp requires exactly 1 argument".to_string(), None));
+    }
+
+    write_to_current_output(&pretty_print(&args[0]));
+    write_to_current_output("\n");
+    Ok(Value::Unspecified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peek_char_then_read_char() {
+        let port = open_input_string(&[Value::string("hi")]).unwrap();
+
+        assert_eq!(peek_char(&[port.clone()]).unwrap(), Value::Char('h'));
+        assert_eq!(read_char(&[port.clone()]).unwrap(), Value::Char('h'));
+        assert_eq!(peek_char(&[port.clone()]).unwrap(), Value::Char('i'));
+        assert_eq!(read_char(&[port.clone()]).unwrap(), Value::Char('i'));
+    }
+
+    #[test]
+    fn test_read_past_end_yields_eof() {
+        let port = open_input_string(&[Value::string("x")]).unwrap();
+
+        assert_eq!(read_char(&[port.clone()]).unwrap(), Value::Char('x'));
+        assert_eq!(peek_char(&[port.clone()]).unwrap(), Value::Eof);
+        assert_eq!(read_char(&[port.clone()]).unwrap(), Value::Eof);
+        assert_eq!(is_eof_object(&[read_char(&[port]).unwrap()]).unwrap(), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_read_uses_scheme_syntax() {
+        let port = open_input_string(&[Value::string("(a #\\) b) #\\( |a b| \"x)\" ; c\n'(1 . 2) rest")]).unwrap();
+        let read = || with_input_port("read", &port, read_datum).unwrap().unwrap();
+
+        // 字符、|sym| 和字符串中的括号不影响括号配对
+        assert_eq!(read().to_string(), "(a #\\) b)");
+        assert_eq!(read(), Value::Char('('));
+        assert_eq!(read(), Value::symbol("a b"));
+        assert_eq!(read(), Value::string("x)"));
+        assert_eq!(read().to_string(), "(quote (1 . 2))");
+        // 数据之后的字符留在端口中
+        assert_eq!(read_char(&[port.clone()]).unwrap(), Value::Char(' '));
+        assert_eq!(read(), Value::symbol("rest"));
+        assert_eq!(read(), Value::Eof);
+
+        let read_all = |text: &str| {
+            let port = open_input_string(&[Value::string(text.to_string())]).unwrap();
+            with_input_port("read", &port, read_datum).unwrap()
+        };
+        assert!(read_all("(1 2").is_err());
+        assert!(read_all("\"abc").is_err());
+        assert!(read_all("'").is_err());
+        assert!(read_all(")").is_err());
+        assert_eq!(read_all("  ; only a comment\n").unwrap(), Value::Eof);
+    }
+
+    #[test]
+    fn test_port_type_errors() {
+        assert!(read_char(&[Value::Integer(1)]).is_err());
+        assert!(open_input_string(&[Value::Integer(1)]).is_err());
+    }
+
+    #[test]
+    fn test_format_directives() {
+        let fmt = |template: &str, args: Vec<Value>| {
+            let mut all = vec![Value::Bool(false), Value::string(template.to_string())];
+            all.extend(args);
+            format(&all)
+        };
+
+        assert_eq!(
+            fmt("~a + ~a = ~a", vec![Value::Integer(1), Value::Integer(2), Value::Integer(3)]).unwrap(),
+            Value::string("1 + 2 = 3")
+        );
+        // ~a 使用 display 形式，~s 使用 write 形式
+        assert_eq!(fmt("~a", vec![Value::string("hi")]).unwrap(), Value::string("hi"));
+        assert_eq!(fmt("~s", vec![Value::string("hi")]).unwrap(), Value::string("\"hi\""));
+        assert_eq!(
+            fmt("~a", vec![Value::from_vec(vec![Value::string("x"), Value::Char('c')])]).unwrap(),
+            Value::string("(x c)")
+        );
+        assert_eq!(fmt("a~%b", vec![]).unwrap(), Value::string("a\nb"));
+        assert_eq!(fmt("~~", vec![]).unwrap(), Value::string("~"));
+
+        // 参数不足和未知指令
+        assert!(fmt("~a ~a", vec![Value::Integer(1)]).is_err());
+        assert!(fmt("~q", vec![]).is_err());
+    }
+
+    #[test]
+    fn test_format_destinations() {
+        // #t 写到当前输出并返回未指定值
+        assert_eq!(
+            format(&[Value::Bool(true), Value::string("~a~%"), Value::Integer(1)]).unwrap(),
+            Value::Unspecified
+        );
+        assert!(format(&[Value::Integer(1), Value::string("x")]).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::legacy::types::{Value, SchemeError, Result, Promise};
+use crate::legacy::types::promise::resolve_shared;
+use crate::legacy::eval::{CoreEvaluator, EvaluationContext};
+
+/// (force obj)：强制求值承诺；delay-force 产生的承诺链以循环而非递归跟随
+pub fn force(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("force requires exactly 1 argument".to_string(), None));
+    }
+
+    // 非承诺对象原样返回
+    let promise = match &args[0] {
+        Value::Promise(promise) => Rc::clone(promise),
+        other => return Ok(other.clone()),
+    };
+
+    let mut current = resolve_shared(&promise);
+    loop {
+        let state = current.borrow().clone();
+        let (expr, env_id, is_delay_force) = match state {
+            Promise::Forced(value) => return Ok(value),
+            Promise::Delayed { expr, env_id } => (expr, env_id, false),
+            Promise::DelayForce { expr, env_id } => (expr, env_id, true),
+            Promise::Shared(_) => unreachable!("resolve_shared follows all forwarding"),
+        };
+
+        let value = evaluator.eval(&expr, &evaluator.environment(env_id), context)?;
+
+        // 求值过程中承诺可能已被重入强制，以先完成的结果为准
+        current = resolve_shared(&current);
+        if let Promise::Forced(value) = &*current.borrow() {
+            return Ok(value.clone());
+        }
+
+        match &value {
+            // 转发到内层承诺而不是复制其状态，使两者共享同一结果；
+            // 最外层承诺也直接指向内层，中间的承诺随之释放，长链不会累积
+            Value::Promise(inner) if is_delay_force => {
+                let inner = resolve_shared(inner);
+                if !Rc::ptr_eq(&inner, &current) {
+                    *current.borrow_mut() = Promise::Shared(Rc::clone(&inner));
+                    if !Rc::ptr_eq(&promise, &current) {
+                        *promise.borrow_mut() = Promise::Shared(Rc::clone(&inner));
+                    }
+                }
+                current = inner;
+            },
+            _ => *current.borrow_mut() = Promise::Forced(value),
+        }
+    }
+}
+
+/// (make-promise obj)：返回已求值为 obj 的承诺；obj 本身是承诺时直接返回
+pub fn make_promise(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("make-promise requires exactly 1 argument".to_string(), None));
+    }
+
+    match &args[0] {
+        Value::Promise(_) => Ok(args[0].clone()),
+        value => Ok(Value::Promise(Rc::new(RefCell::new(Promise::Forced(value.clone()))))),
+    }
+}
+
+/// (promise? obj)
+pub fn is_promise(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("promise? requires exactly 1 argument".to_string(), None));
+    }
+    Ok(Value::Bool(matches!(args[0], Value::Promise(_))))
+}
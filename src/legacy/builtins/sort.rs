@@ -0,0 +1,98 @@
+use crate::legacy::types::{Value, SchemeError, Result};
+use crate::legacy::eval::{CoreEvaluator, EvaluationContext};
+use super::vector::expect_mutable_vector;
+
+/// 检查比较过程可调用
+fn expect_procedure(name: &str, value: &Value) -> Result<()> {
+    if value.is_procedure() {
+        Ok(())
+    } else {
+        Err(SchemeError::TypeError(format!("{name} expects a procedure, got {value}"), None))
+    }
+}
+
+/// 稳定的归并排序；less 出错时立即返回该错误
+///
+/// 不使用 `slice::sort_by`：用户提供的比较过程可能出错，也可能不是全序
+fn merge_sort(items: Vec<Value>, less: &mut impl FnMut(&Value, &Value) -> Result<bool>) -> Result<Vec<Value>> {
+    if items.len() <= 1 {
+        return Ok(items);
+    }
+
+    let mut left = items;
+    let right = left.split_off(left.len() / 2);
+    let left = merge_sort(left, less)?;
+    let right = merge_sort(right, less)?;
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+    while let (Some(l), Some(r)) = (left.peek(), right.peek()) {
+        // 仅当右侧严格小于左侧时取右侧元素，保证相等元素保持原有顺序
+        if less(r, l)? {
+            merged.extend(right.next());
+        } else {
+            merged.extend(left.next());
+        }
+    }
+    merged.extend(left);
+    merged.extend(right);
+    Ok(merged)
+}
+
+/// 以 Scheme 过程作为 less 比较排序
+fn sort_with(items: Vec<Value>, less: &Value, evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Vec<Value>> {
+    merge_sort(items, &mut |a, b| {
+        Ok(evaluator.apply(less, vec![a.clone(), b.clone()], context)?.is_truthy())
+    })
+}
+
+/// (list-sort < list)：返回稳定排序后的新列表，原列表不变
+pub fn list_sort(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("list-sort requires exactly 2 arguments".to_string(), None));
+    }
+
+    expect_procedure("list-sort", &args[0])?;
+    let items = args[1].to_vec().ok_or_else(|| {
+        SchemeError::TypeError(format!("list-sort expects a list, got {}", args[1]), None)
+    })?;
+    Ok(Value::from_vec(sort_with(items, &args[0], evaluator, context)?))
+}
+
+/// (vector-sort! vector <)：原地稳定排序向量
+///
+/// 排序期间不持有向量的借用，比较过程可以读取该向量；排序完成后一次性写回
+pub fn vector_sort(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("vector-sort! requires exactly 2 arguments".to_string(), None));
+    }
+
+    let items = expect_mutable_vector("vector-sort!", &args[0])?;
+    expect_procedure("vector-sort!", &args[1])?;
+
+    let snapshot = items.borrow().clone();
+    let sorted = sort_with(snapshot, &args[1], evaluator, context)?;
+    *items.borrow_mut() = sorted;
+    Ok(Value::Unspecified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_sort_is_stable() {
+        // 只按 car 比较，相同 car 的元素保持原有顺序
+        let pair = |key: i64, tag: &str| Value::cons(Value::Integer(key), Value::symbol(tag));
+        let items = vec![pair(2, "a"), pair(1, "b"), pair(2, "c"), pair(1, "d")];
+        let sorted = merge_sort(items, &mut |a, b| match (a.car(), b.car()) {
+            (Some(Value::Integer(x)), Some(Value::Integer(y))) => Ok(x < y),
+            _ => unreachable!(),
+        }).unwrap();
+        assert_eq!(
+            Value::from_vec(sorted).to_string(),
+            "((1 . b) (1 . d) (2 . a) (2 . c))"
+        );
+    }
+}
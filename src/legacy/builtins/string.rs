@@ -0,0 +1,497 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::legacy::types::{Value, SchemeError, Result};
+
+/// 检查参数为字符串（可变或不可变）并返回其内容
+fn expect_string<'a>(name: &str, value: &'a Value) -> Result<Cow<'a, str>> {
+    value.string_contents().ok_or_else(|| {
+        SchemeError::TypeError(format!("{name} expects a string, got {value}"), None)
+    })
+}
+
+/// 解析 string-pad 系列的参数：(string s width [char])
+fn pad_arguments<'a>(name: &str, args: &'a [Value]) -> Result<(Cow<'a, str>, usize, char)> {
+    if args.len() < 2 || args.len() > 3 {
+        return Err(SchemeError::ArityError(format!("{name} requires 2 or 3 arguments"), None));
+    }
+
+    let s = expect_string(name, &args[0])?;
+    let width = match &args[1] {
+        Value::Integer(n) if *n >= 0 => *n as usize,
+        other => return Err(SchemeError::TypeError(format!("{name} expects a non-negative width, got {other}"), None)),
+    };
+    let pad = match args.get(2) {
+        None => ' ',
+        Some(Value::Char(c)) => *c,
+        Some(other) => return Err(SchemeError::TypeError(format!("{name} expects a padding character, got {other}"), None)),
+    };
+    Ok((s, width, pad))
+}
+
+/// (string-pad s width [char])：在左侧填充到 width；过长时截断，保留右端字符
+pub fn string_pad(args: &[Value]) -> Result<Value> {
+    let (s, width, pad) = pad_arguments("string-pad", args)?;
+    let chars: Vec<char> = s.chars().collect();
+
+    let result: String = if chars.len() >= width {
+        chars[chars.len() - width..].iter().collect()
+    } else {
+        std::iter::repeat_n(pad, width - chars.len()).chain(chars).collect()
+    };
+    Ok(Value::string(result))
+}
+
+/// (string-pad-right s width [char])：在右侧填充到 width；过长时截断，保留左端字符
+pub fn string_pad_right(args: &[Value]) -> Result<Value> {
+    let (s, width, pad) = pad_arguments("string-pad-right", args)?;
+    let length = s.chars().count();
+
+    let result: String = if length >= width {
+        s.chars().take(width).collect()
+    } else {
+        s.chars().chain(std::iter::repeat_n(pad, width - length)).collect()
+    };
+    Ok(Value::string(result))
+}
+
+/// string-trim 系列要去除的字符
+enum TrimSet<'a> {
+    /// 默认：空白字符
+    Whitespace,
+    /// 单个字符
+    Char(char),
+    /// 字符串中的任一字符
+    Chars(Cow<'a, str>),
+}
+
+impl TrimSet<'_> {
+    fn contains(&self, ch: char) -> bool {
+        match self {
+            TrimSet::Whitespace => ch.is_whitespace(),
+            TrimSet::Char(c) => ch == *c,
+            TrimSet::Chars(set) => set.contains(ch),
+        }
+    }
+}
+
+/// 解析 string-trim 系列的参数：(string s [char-set])，字符集可为字符或字符串，默认去除空白
+fn trim_arguments<'a>(name: &str, args: &'a [Value]) -> Result<(Cow<'a, str>, TrimSet<'a>)> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(SchemeError::ArityError(format!("{name} requires 1 or 2 arguments"), None));
+    }
+
+    let s = expect_string(name, &args[0])?;
+    let set = match args.get(1) {
+        None => TrimSet::Whitespace,
+        Some(Value::Char(c)) => TrimSet::Char(*c),
+        Some(chars @ (Value::String(_) | Value::MutableString(_))) => TrimSet::Chars(expect_string(name, chars)?),
+        Some(other) => return Err(SchemeError::TypeError(
+            format!("{name} expects a character or a string of characters to trim, got {other}"), None
+        )),
+    };
+    Ok((s, set))
+}
+
+/// (string-trim s [char-set])：去除两端的空白（或指定字符）
+pub fn string_trim(args: &[Value]) -> Result<Value> {
+    let (s, set) = trim_arguments("string-trim", args)?;
+    Ok(Value::string(s.trim_matches(|ch| set.contains(ch)).to_string()))
+}
+
+/// (string-trim-left s [char-set])：去除开头的空白（或指定字符）
+pub fn string_trim_left(args: &[Value]) -> Result<Value> {
+    let (s, set) = trim_arguments("string-trim-left", args)?;
+    Ok(Value::string(s.trim_start_matches(|ch| set.contains(ch)).to_string()))
+}
+
+/// (string-trim-right s [char-set])：去除末尾的空白（或指定字符）
+pub fn string_trim_right(args: &[Value]) -> Result<Value> {
+    let (s, set) = trim_arguments("string-trim-right", args)?;
+    Ok(Value::string(s.trim_end_matches(|ch| set.contains(ch)).to_string()))
+}
+
+/// 折叠单个字符的大小写
+///
+/// 基于 Rust 的 `char::to_lowercase`：ASCII 字符与 Scheme 的 foldcase 完全一致；
+/// 其他 Unicode 字符使用小写映射而非完整的 case folding（如 'ß' 保持不变），
+/// 小写形式不止一个字符时保留原字符
+fn fold_char(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(folded), None) => folded,
+        _ => c,
+    }
+}
+
+/// 折叠字符串的大小写（规则同 `fold_char`，但允许映射为多个字符）
+fn fold_string(s: &str) -> String {
+    s.to_lowercase()
+}
+
+/// 检查参数为字符并返回
+fn expect_char(name: &str, value: &Value) -> Result<char> {
+    match value {
+        Value::Char(c) => Ok(*c),
+        other => Err(SchemeError::TypeError(format!("{name} expects a character, got {other}"), None)),
+    }
+}
+
+/// 对折叠大小写后的相邻参数两两比较
+fn compare_folded<T: PartialOrd>(
+    name: &str,
+    args: &[Value],
+    fold: impl Fn(&str, &Value) -> Result<T>,
+    holds: fn(&T, &T) -> bool,
+) -> Result<Value> {
+    if args.is_empty() {
+        return Err(SchemeError::ArityError(format!("{name} requires at least 1 argument"), None));
+    }
+    let folded = args.iter().map(|arg| fold(name, arg)).collect::<Result<Vec<T>>>()?;
+    Ok(Value::Bool(folded.windows(2).all(|pair| holds(&pair[0], &pair[1]))))
+}
+
+/// (char-foldcase c)
+pub fn char_foldcase(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("char-foldcase requires exactly 1 argument".to_string(), None));
+    }
+    Ok(Value::Char(fold_char(expect_char("char-foldcase", &args[0])?)))
+}
+
+/// (string-foldcase s)
+pub fn string_foldcase(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("string-foldcase requires exactly 1 argument".to_string(), None));
+    }
+    Ok(Value::string(fold_string(&expect_string("string-foldcase", &args[0])?)))
+}
+
+/// (string-ci=? s1 s2 ...)
+pub fn string_ci_equal(args: &[Value]) -> Result<Value> {
+    compare_folded("string-ci=?", args, |name, arg| expect_string(name, arg).map(|s| fold_string(&s)), |a, b| a == b)
+}
+
+/// (string-ci<? s1 s2 ...)
+pub fn string_ci_less(args: &[Value]) -> Result<Value> {
+    compare_folded("string-ci<?", args, |name, arg| expect_string(name, arg).map(|s| fold_string(&s)), |a, b| a < b)
+}
+
+/// (char-ci=? c1 c2 ...)
+pub fn char_ci_equal(args: &[Value]) -> Result<Value> {
+    compare_folded("char-ci=?", args, |name, arg| expect_char(name, arg).map(fold_char), |a, b| a == b)
+}
+
+/// 检查参数为字符下标，超出 [0, limit) 时报错
+fn expect_index(name: &str, value: &Value, limit: usize) -> Result<usize> {
+    match value {
+        Value::Integer(k) if *k >= 0 && (*k as usize) < limit => Ok(*k as usize),
+        Value::Integer(k) => Err(SchemeError::RuntimeError(
+            format!("{name}: index {k} out of range for string of length {limit}"), None
+        )),
+        other => Err(SchemeError::TypeError(format!("{name} expects an integer index, got {other}"), None)),
+    }
+}
+
+/// 解析可选的 [start [end]] 字符区间，默认为整个字符串
+fn char_range(name: &str, args: &[Value], length: usize) -> Result<(usize, usize)> {
+    let bound = |value: Option<&Value>, default: usize, lower: usize| match value {
+        None => Ok(default),
+        Some(Value::Integer(k)) if *k >= lower as i64 && *k as usize <= length => Ok(*k as usize),
+        Some(other) => Err(SchemeError::RuntimeError(
+            format!("{name}: invalid range bound {other} for string of length {length}"), None
+        )),
+    };
+    let start = bound(args.first(), 0, 0)?;
+    let end = bound(args.get(1), length, start)?;
+    Ok((start, end))
+}
+
+/// 检查参数为可变字符串；不可变字符串（如字面量）不能被修改
+fn expect_mutable_string<'a>(name: &str, value: &'a Value) -> Result<&'a Rc<RefCell<String>>> {
+    match value {
+        Value::MutableString(s) => Ok(s),
+        Value::String(_) => Err(SchemeError::RuntimeError(
+            format!("{name}: cannot modify an immutable string {value}; use string-copy to obtain a mutable one"), None
+        )),
+        other => Err(SchemeError::TypeError(format!("{name} expects a string, got {other}"), None)),
+    }
+}
+
+/// (make-string k [char])：创建长度为 k 的可变字符串，默认以空格填充
+pub fn make_string(args: &[Value]) -> Result<Value> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(SchemeError::ArityError("make-string requires 1 or 2 arguments".to_string(), None));
+    }
+
+    let length = match &args[0] {
+        Value::Integer(k) if *k >= 0 => *k as usize,
+        other => return Err(SchemeError::TypeError(format!("make-string expects a non-negative length, got {other}"), None)),
+    };
+    let fill = match args.get(1) {
+        None => ' ',
+        Some(value) => expect_char("make-string", value)?,
+    };
+    Ok(Value::MutableString(Rc::new(RefCell::new(std::iter::repeat_n(fill, length).collect()))))
+}
+
+/// 将值序列收集为字符串；遇到非字符元素时报告其下标
+fn collect_chars<'a>(name: &str, kind: &str, values: impl IntoIterator<Item = &'a Value>) -> Result<String> {
+    values.into_iter().enumerate().map(|(index, value)| match value {
+        Value::Char(c) => Ok(*c),
+        _ => Err(SchemeError::TypeError(format!("{name}: {kind} at index {index} is not a char"), None)),
+    }).collect()
+}
+
+/// (string char ...)：由若干字符组成新的可变字符串
+pub fn string(args: &[Value]) -> Result<Value> {
+    let s = collect_chars("string", "argument", args)?;
+    Ok(Value::MutableString(Rc::new(RefCell::new(s))))
+}
+
+/// (list->string list)：由字符列表组成新的可变字符串
+pub fn list_to_string(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("list->string requires exactly 1 argument".to_string(), None));
+    }
+
+    let items = args[0].to_vec().ok_or_else(|| {
+        SchemeError::TypeError(format!("list->string expects a list, got {}", args[0]), None)
+    })?;
+    let s = collect_chars("list->string", "element", &items)?;
+    Ok(Value::MutableString(Rc::new(RefCell::new(s))))
+}
+
+/// (string-copy s [start [end]])：复制（部分）字符串，结果总是可变字符串
+pub fn string_copy(args: &[Value]) -> Result<Value> {
+    if args.is_empty() || args.len() > 3 {
+        return Err(SchemeError::ArityError("string-copy requires 1 to 3 arguments".to_string(), None));
+    }
+
+    let s = expect_string("string-copy", &args[0])?;
+    let (start, end) = char_range("string-copy", &args[1..], s.chars().count())?;
+    let copy: String = s.chars().skip(start).take(end - start).collect();
+    Ok(Value::MutableString(Rc::new(RefCell::new(copy))))
+}
+
+/// (string-ref s k)：按字符（Unicode 标量值）而非字节取下标。字符串以 UTF-8 存储，
+/// 定位第 k 个字符需要 O(k) 遍历；只在越界报错时才完整计算长度
+pub fn string_ref(args: &[Value]) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("string-ref requires exactly 2 arguments".to_string(), None));
+    }
+
+    let s = expect_string("string-ref", &args[0])?;
+    let found = match &args[1] {
+        Value::Integer(k) if *k >= 0 => s.chars().nth(*k as usize),
+        _ => None,
+    };
+    match found {
+        Some(c) => Ok(Value::Char(c)),
+        None => Err(expect_index("string-ref", &args[1], s.chars().count()).unwrap_err()),
+    }
+}
+
+/// (string-set! s k char)：替换可变字符串中的第 k 个字符
+pub fn string_set(args: &[Value]) -> Result<Value> {
+    if args.len() != 3 {
+        return Err(SchemeError::ArityError("string-set! requires exactly 3 arguments".to_string(), None));
+    }
+
+    let cell = expect_mutable_string("string-set!", &args[0])?;
+    let fill = expect_char("string-set!", &args[2])?;
+    let mut chars: Vec<char> = cell.borrow().chars().collect();
+    let index = expect_index("string-set!", &args[1], chars.len())?;
+    chars[index] = fill;
+    *cell.borrow_mut() = chars.into_iter().collect();
+    Ok(Value::Unspecified)
+}
+
+/// (string-fill! s char [start [end]])：以 char 填充可变字符串的（部分）字符
+pub fn string_fill(args: &[Value]) -> Result<Value> {
+    if args.len() < 2 || args.len() > 4 {
+        return Err(SchemeError::ArityError("string-fill! requires 2 to 4 arguments".to_string(), None));
+    }
+
+    let cell = expect_mutable_string("string-fill!", &args[0])?;
+    let fill = expect_char("string-fill!", &args[1])?;
+    let mut chars: Vec<char> = cell.borrow().chars().collect();
+    let (start, end) = char_range("string-fill!", &args[2..], chars.len())?;
+    chars[start..end].fill(fill);
+    *cell.borrow_mut() = chars.into_iter().collect();
+    Ok(Value::Unspecified)
+}
+
+/// 解析可选的基数参数（2 到 16，默认 10）
+fn radix_argument(name: &str, value: Option<&Value>) -> Result<u32> {
+    match value {
+        None => Ok(10),
+        Some(Value::Integer(radix)) if (2..=16).contains(radix) => Ok(*radix as u32),
+        Some(other) => Err(SchemeError::RuntimeError(format!("{name}: radix must be between 2 and 16, got {other}"), None)),
+    }
+}
+
+/// (char->digit c [radix])：字符在给定基数下的数值（大小写字母均可），不是该基数的数字时返回 #f
+pub fn char_to_digit(args: &[Value]) -> Result<Value> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(SchemeError::ArityError("char->digit requires 1 or 2 arguments".to_string(), None));
+    }
+
+    let c = expect_char("char->digit", &args[0])?;
+    let radix = radix_argument("char->digit", args.get(1))?;
+    Ok(c.to_digit(radix).map_or(Value::Bool(false), |digit| Value::Integer(digit as i64)))
+}
+
+/// (digit->char d [radix])：数值在给定基数下对应的数字字符（使用小写字母），超出范围时返回 #f
+pub fn digit_to_char(args: &[Value]) -> Result<Value> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(SchemeError::ArityError("digit->char requires 1 or 2 arguments".to_string(), None));
+    }
+
+    let radix = radix_argument("digit->char", args.get(1))?;
+    match &args[0] {
+        Value::Integer(d) => Ok(u32::try_from(*d).ok()
+            .and_then(|d| char::from_digit(d, radix))
+            .map_or(Value::Bool(false), Value::Char)),
+        other => Err(SchemeError::TypeError(format!("digit->char expects an integer, got {other}"), None)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(s: &str) -> Value {
+        Value::string(s.to_string())
+    }
+
+    #[test]
+    fn test_list_to_string_names_bad_index() {
+        let chars = Value::from_vec(vec![Value::Char('a'), Value::Char('b')]);
+        assert_eq!(list_to_string(&[chars]).unwrap().string_contents().unwrap(), "ab");
+        assert_eq!(super::string(&[Value::Char('x'), Value::Char('y')]).unwrap().string_contents().unwrap(), "xy");
+
+        let mixed = Value::from_vec(vec![Value::Char('a'), Value::Char('b'), Value::Integer(1)]);
+        assert_eq!(
+            list_to_string(&[mixed]).unwrap_err(),
+            SchemeError::TypeError("list->string: element at index 2 is not a char".to_string(), None)
+        );
+        assert_eq!(
+            super::string(&[Value::Char('a'), string("b")]).unwrap_err(),
+            SchemeError::TypeError("string: argument at index 1 is not a char".to_string(), None)
+        );
+        assert!(matches!(list_to_string(&[Value::Integer(1)]), Err(SchemeError::TypeError(..))));
+    }
+
+    #[test]
+    fn test_string_pad() {
+        // 短字符串被填充
+        assert_eq!(string_pad(&[string("42"), Value::Integer(5)]).unwrap(), string("   42"));
+        assert_eq!(string_pad(&[string("42"), Value::Integer(5), Value::Char('0')]).unwrap(), string("00042"));
+        assert_eq!(string_pad_right(&[string("ab"), Value::Integer(4)]).unwrap(), string("ab  "));
+
+        // 长字符串被截断：左填充保留右端，右填充保留左端
+        assert_eq!(string_pad(&[string("abcdef"), Value::Integer(3)]).unwrap(), string("def"));
+        assert_eq!(string_pad_right(&[string("abcdef"), Value::Integer(3)]).unwrap(), string("abc"));
+
+        assert!(string_pad(&[string("a"), Value::Integer(-1)]).is_err());
+    }
+
+    #[test]
+    fn test_string_trim() {
+        let mixed = string(" \t\n hello world \r\n\t");
+        assert_eq!(string_trim(&[mixed.clone()]).unwrap(), string("hello world"));
+        assert_eq!(string_trim_left(&[mixed.clone()]).unwrap(), string("hello world \r\n\t"));
+        assert_eq!(string_trim_right(&[mixed]).unwrap(), string(" \t\n hello world"));
+
+        // 指定字符或字符集
+        assert_eq!(string_trim(&[string("xxhixx"), Value::Char('x')]).unwrap(), string("hi"));
+        assert_eq!(string_trim(&[string("-_-hi_-"), string("-_")]).unwrap(), string("hi"));
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        assert_eq!(string_ci_equal(&[string("Hello"), string("hello")]).unwrap(), Value::Bool(true));
+        assert_eq!(string_ci_equal(&[string("Hello"), string("HELLO"), string("help")]).unwrap(), Value::Bool(false));
+        assert_eq!(string_ci_less(&[string("apple"), string("BANANA")]).unwrap(), Value::Bool(true));
+        assert_eq!(string_ci_less(&[string("b"), string("A")]).unwrap(), Value::Bool(false));
+        assert_eq!(char_ci_equal(&[Value::Char('A'), Value::Char('a')]).unwrap(), Value::Bool(true));
+
+        assert_eq!(string_foldcase(&[string("ÀbC")]).unwrap(), string("àbc"));
+        assert_eq!(char_foldcase(&[Value::Char('Z')]).unwrap(), Value::Char('z'));
+        assert!(string_ci_equal(&[string("a"), Value::Char('a')]).is_err());
+    }
+
+    #[test]
+    fn test_string_ref_by_scalar_value() {
+        let ascii = string("abc");
+        assert_eq!(string_ref(&[ascii.clone(), Value::Integer(0)]).unwrap(), Value::Char('a'));
+        assert_eq!(string_ref(&[ascii.clone(), Value::Integer(2)]).unwrap(), Value::Char('c'));
+
+        // 多字节字符各算一个下标
+        let mixed = string("a€𝄞b");
+        assert_eq!(string_ref(&[mixed.clone(), Value::Integer(1)]).unwrap(), Value::Char('€'));
+        assert_eq!(string_ref(&[mixed.clone(), Value::Integer(2)]).unwrap(), Value::Char('𝄞'));
+        assert_eq!(string_ref(&[mixed.clone(), Value::Integer(3)]).unwrap(), Value::Char('b'));
+
+        // 越界（包括负数）是运行时错误，错误信息给出字符长度
+        assert_eq!(
+            string_ref(&[mixed.clone(), Value::Integer(4)]).unwrap_err(),
+            SchemeError::RuntimeError("string-ref: index 4 out of range for string of length 4".to_string(), None)
+        );
+        assert!(matches!(string_ref(&[ascii.clone(), Value::Integer(-1)]), Err(SchemeError::RuntimeError(..))));
+        assert!(matches!(string_ref(&[ascii, Value::Float(1.0)]), Err(SchemeError::TypeError(..))));
+        assert!(matches!(string_ref(&[string(""), Value::Integer(0)]), Err(SchemeError::RuntimeError(..))));
+    }
+
+    #[test]
+    fn test_mutable_strings() {
+        // make-string 创建可变字符串，string-set! 后可读回
+        let s = make_string(&[Value::Integer(3), Value::Char('a')]).unwrap();
+        assert_eq!(s, string("aaa"));
+        string_set(&[s.clone(), Value::Integer(1), Value::Char('λ')]).unwrap();
+        assert_eq!(string_ref(&[s.clone(), Value::Integer(1)]).unwrap(), Value::Char('λ'));
+        assert_eq!(string_ref(&[s.clone(), Value::Integer(2)]).unwrap(), Value::Char('a'));
+        assert_eq!(s.to_string(), "\"aλa\"");
+
+        // string-copy 得到独立的可变副本
+        let copy = string_copy(&[s.clone(), Value::Integer(1)]).unwrap();
+        string_fill(&[copy.clone(), Value::Char('z')]).unwrap();
+        assert_eq!(copy, string("zz"));
+        assert_eq!(s, string("aλa"));
+        string_fill(&[s.clone(), Value::Char('-'), Value::Integer(0), Value::Integer(1)]).unwrap();
+        assert_eq!(s, string("-λa"));
+
+        // string-ref 同样适用于不可变字符串；字面量不能修改
+        assert_eq!(string_ref(&[string("héllo"), Value::Integer(1)]).unwrap(), Value::Char('é'));
+        assert!(matches!(string_set(&[string("abc"), Value::Integer(0), Value::Char('x')]), Err(SchemeError::RuntimeError(..))));
+        assert!(matches!(string_ref(&[s, Value::Integer(3)]), Err(SchemeError::RuntimeError(..))));
+
+        // 其他字符串过程也接受可变字符串
+        let padded = string_pad(&[make_string(&[Value::Integer(1), Value::Char('x')]).unwrap(), Value::Integer(3)]).unwrap();
+        assert_eq!(padded, string("  x"));
+    }
+
+    #[test]
+    fn test_char_digit_conversion() {
+        let radix = |r: i64| Value::Integer(r);
+
+        // 十进制（默认基数）
+        assert_eq!(char_to_digit(&[Value::Char('7')]).unwrap(), Value::Integer(7));
+        assert_eq!(char_to_digit(&[Value::Char('a')]).unwrap(), Value::Bool(false));
+        assert_eq!(digit_to_char(&[Value::Integer(3)]).unwrap(), Value::Char('3'));
+        assert_eq!(digit_to_char(&[Value::Integer(10)]).unwrap(), Value::Bool(false));
+
+        // 十六进制，大小写均可
+        assert_eq!(char_to_digit(&[Value::Char('a'), radix(16)]).unwrap(), Value::Integer(10));
+        assert_eq!(char_to_digit(&[Value::Char('F'), radix(16)]).unwrap(), Value::Integer(15));
+        assert_eq!(char_to_digit(&[Value::Char('g'), radix(16)]).unwrap(), Value::Bool(false));
+        assert_eq!(digit_to_char(&[Value::Integer(10), radix(16)]).unwrap(), Value::Char('a'));
+        assert_eq!(digit_to_char(&[Value::Integer(-1), radix(16)]).unwrap(), Value::Bool(false));
+
+        // 二进制与非法基数
+        assert_eq!(char_to_digit(&[Value::Char('2'), radix(2)]).unwrap(), Value::Bool(false));
+        assert!(char_to_digit(&[Value::Char('1'), radix(17)]).is_err());
+        assert!(digit_to_char(&[Value::Integer(1), radix(1)]).is_err());
+    }
+}
@@ -0,0 +1,61 @@
+use std::sync::OnceLock;
+use std::time::Instant;
+use crate::legacy::types::{Value, SchemeError, Result};
+use crate::legacy::storage::since_unix_epoch;
+
+/// 每秒的 jiffy 数（微秒精度）
+pub const JIFFIES_PER_SECOND: i64 = 1_000_000;
+
+/// current-jiffy 的计时起点（首次调用时确定）
+static JIFFY_EPOCH: OnceLock<Instant> = OnceLock::new();
+
+/// (current-time)：距 Unix 纪元的秒数（浮点数）
+pub fn current_time(args: &[Value]) -> Result<Value> {
+    if !args.is_empty() {
+        return Err(SchemeError::ArityError("current-time requires no arguments".to_string(), None));
+    }
+
+    Ok(Value::Float(since_unix_epoch().as_secs_f64()))
+}
+
+/// (current-jiffy)：自任意固定起点以来的 jiffy 数，单调不减
+pub fn current_jiffy(args: &[Value]) -> Result<Value> {
+    if !args.is_empty() {
+        return Err(SchemeError::ArityError("current-jiffy requires no arguments".to_string(), None));
+    }
+
+    let epoch = JIFFY_EPOCH.get_or_init(Instant::now);
+    Ok(Value::Integer(epoch.elapsed().as_micros() as i64))
+}
+
+/// (jiffies-per-second)
+pub fn jiffies_per_second(args: &[Value]) -> Result<Value> {
+    if !args.is_empty() {
+        return Err(SchemeError::ArityError("jiffies-per-second requires no arguments".to_string(), None));
+    }
+
+    Ok(Value::Integer(JIFFIES_PER_SECOND))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_current_time_type_and_monotonic() {
+        let (Value::Float(first), Value::Float(second)) = (current_time(&[]).unwrap(), current_time(&[]).unwrap()) else {
+            panic!("current-time should return a float");
+        };
+        assert!(first > 0.0);
+        assert!(second >= first);
+    }
+
+    #[test]
+    fn test_current_jiffy_monotonic() {
+        let (Value::Integer(first), Value::Integer(second)) = (current_jiffy(&[]).unwrap(), current_jiffy(&[]).unwrap()) else {
+            panic!("current-jiffy should return an integer");
+        };
+        assert!(second >= first);
+        assert_eq!(jiffies_per_second(&[]).unwrap(), Value::Integer(JIFFIES_PER_SECOND));
+    }
+}
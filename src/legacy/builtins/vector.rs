@@ -0,0 +1,276 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::legacy::types::{Value, SchemeError, Result};
+use crate::legacy::eval::{CoreEvaluator, EvaluationContext};
+
+/// 由元素创建向量值
+pub fn make_vector_value(items: Vec<Value>) -> Value {
+    Value::Vector(Rc::new(RefCell::new(items)), false)
+}
+
+/// 取出向量参数
+fn expect_vector<'a>(name: &str, value: &'a Value) -> Result<&'a Rc<RefCell<Vec<Value>>>> {
+    match value {
+        Value::Vector(items, _) => Ok(items),
+        other => Err(SchemeError::TypeError(format!("{name} expects a vector, got {other}"), None)),
+    }
+}
+
+/// 取出将被原地修改的向量参数；字面量向量不可修改
+pub(crate) fn expect_mutable_vector<'a>(name: &str, value: &'a Value) -> Result<&'a Rc<RefCell<Vec<Value>>>> {
+    match value {
+        Value::Vector(_, true) => Err(SchemeError::RuntimeError(format!("{name}: cannot mutate literal {value}"), None)),
+        _ => expect_vector(name, value),
+    }
+}
+
+/// 取出向量下标参数并检查范围
+fn expect_index(name: &str, value: &Value, len: usize) -> Result<usize> {
+    match value {
+        Value::Integer(k) if *k >= 0 && (*k as usize) < len => Ok(*k as usize),
+        Value::Integer(k) => Err(SchemeError::RuntimeError(format!("{name}: index {k} out of range for length {len}"), None)),
+        other => Err(SchemeError::TypeError(format!("{name} expects an integer index, got {other}"), None)),
+    }
+}
+
+/// 取出可选的 [start [end]] 范围参数，默认为整个向量；要求 0 <= start <= end <= len
+fn vector_range(name: &str, args: &[Value], len: usize) -> Result<(usize, usize)> {
+    let bound = |value: Option<&Value>, default: usize| match value {
+        None => Ok(default),
+        Some(Value::Integer(k)) if *k >= 0 && (*k as usize) <= len => Ok(*k as usize),
+        Some(Value::Integer(k)) => Err(SchemeError::RuntimeError(format!("{name}: index {k} out of range for length {len}"), None)),
+        Some(other) => Err(SchemeError::TypeError(format!("{name} expects an integer index, got {other}"), None)),
+    };
+    let start = bound(args.first(), 0)?;
+    let end = bound(args.get(1), len)?;
+    if start > end {
+        return Err(SchemeError::RuntimeError(format!("{name}: start {start} is greater than end {end}"), None));
+    }
+    Ok((start, end))
+}
+
+/// (vector obj ...)
+pub fn vector(args: &[Value]) -> Result<Value> {
+    Ok(make_vector_value(args.to_vec()))
+}
+
+/// (make-vector k [fill])
+pub fn make_vector(args: &[Value]) -> Result<Value> {
+    if args.is_empty() || args.len() > 2 {
+        return Err(SchemeError::ArityError("make-vector requires 1 or 2 arguments".to_string(), None));
+    }
+
+    match &args[0] {
+        Value::Integer(k) if *k >= 0 => {
+            let fill = args.get(1).cloned().unwrap_or(Value::Integer(0));
+            Ok(make_vector_value(vec![fill; *k as usize]))
+        },
+        other => Err(SchemeError::TypeError(format!("make-vector expects a non-negative integer, got {other}"), None)),
+    }
+}
+
+/// (vector? obj)
+pub fn is_vector(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("vector? requires exactly 1 argument".to_string(), None));
+    }
+
+    Ok(Value::Bool(matches!(args[0], Value::Vector(_, _))))
+}
+
+/// (vector-length vector)
+pub fn vector_length(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("vector-length requires exactly 1 argument".to_string(), None));
+    }
+
+    let items = expect_vector("vector-length", &args[0])?;
+    let len = items.borrow().len();
+    Ok(Value::Integer(len as i64))
+}
+
+/// (vector-ref vector k)
+pub fn vector_ref(args: &[Value]) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("vector-ref requires exactly 2 arguments".to_string(), None));
+    }
+
+    let items = expect_vector("vector-ref", &args[0])?.borrow();
+    let index = expect_index("vector-ref", &args[1], items.len())?;
+    Ok(items[index].clone())
+}
+
+/// (vector-set! vector k obj)
+pub fn vector_set(args: &[Value]) -> Result<Value> {
+    if args.len() != 3 {
+        return Err(SchemeError::ArityError("vector-set! requires exactly 3 arguments".to_string(), None));
+    }
+
+    let mut items = expect_mutable_vector("vector-set!", &args[0])?.borrow_mut();
+    let index = expect_index("vector-set!", &args[1], items.len())?;
+    items[index] = args[2].clone();
+    Ok(Value::Unspecified)
+}
+
+/// (vector->list vector)
+pub fn vector_to_list(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("vector->list requires exactly 1 argument".to_string(), None));
+    }
+
+    let items = expect_vector("vector->list", &args[0])?.borrow().clone();
+    Ok(Value::from_vec(items))
+}
+
+/// (list->vector list)
+pub fn list_to_vector(args: &[Value]) -> Result<Value> {
+    if args.len() != 1 {
+        return Err(SchemeError::ArityError("list->vector requires exactly 1 argument".to_string(), None));
+    }
+
+    let items = args[0].to_vec().ok_or_else(|| {
+        SchemeError::TypeError(format!("list->vector expects a list, got {}", args[0]), None)
+    })?;
+    Ok(make_vector_value(items))
+}
+
+/// (vector-copy vector [start [end]])：返回范围内元素组成的新向量
+pub fn vector_copy(args: &[Value]) -> Result<Value> {
+    if args.is_empty() || args.len() > 3 {
+        return Err(SchemeError::ArityError("vector-copy requires 1 to 3 arguments".to_string(), None));
+    }
+
+    let items = expect_vector("vector-copy", &args[0])?.borrow();
+    let (start, end) = vector_range("vector-copy", &args[1..], items.len())?;
+    Ok(make_vector_value(items[start..end].to_vec()))
+}
+
+/// (vector-copy! to at from [start [end]])：将 from 的范围复制到 to 的 at 处。
+/// 先读出源范围再写入，因此同一向量内重叠的复制也按源的原始内容进行
+pub fn vector_copy_bang(args: &[Value]) -> Result<Value> {
+    if args.len() < 3 || args.len() > 5 {
+        return Err(SchemeError::ArityError("vector-copy! requires 3 to 5 arguments".to_string(), None));
+    }
+
+    let source = {
+        let from = expect_vector("vector-copy!", &args[2])?.borrow();
+        let (start, end) = vector_range("vector-copy!", &args[3..], from.len())?;
+        from[start..end].to_vec()
+    };
+
+    let mut to = expect_mutable_vector("vector-copy!", &args[0])?.borrow_mut();
+    let at = match &args[1] {
+        Value::Integer(k) if *k >= 0 && (*k as usize) <= to.len() => *k as usize,
+        Value::Integer(k) => return Err(SchemeError::RuntimeError(format!("vector-copy!: index {k} out of range for length {}", to.len()), None)),
+        other => return Err(SchemeError::TypeError(format!("vector-copy! expects an integer index, got {other}"), None)),
+    };
+    if to.len() - at < source.len() {
+        return Err(SchemeError::RuntimeError(
+            format!("vector-copy!: {} elements do not fit at index {at} of a vector of length {}", source.len(), to.len()),
+            None,
+        ));
+    }
+    to[at..at + source.len()].clone_from_slice(&source);
+    Ok(Value::Unspecified)
+}
+
+/// (vector-fill! vector fill)：将所有元素设为 fill
+pub fn vector_fill(args: &[Value]) -> Result<Value> {
+    if args.len() != 2 {
+        return Err(SchemeError::ArityError("vector-fill! requires exactly 2 arguments".to_string(), None));
+    }
+
+    let mut items = expect_mutable_vector("vector-fill!", &args[0])?.borrow_mut();
+    for item in items.iter_mut() {
+        *item = args[1].clone();
+    }
+    Ok(Value::Unspecified)
+}
+
+/// 收集 vector-map / vector-for-each 的参数：按最短向量的长度逐位置取出各向量的元素
+fn collect_vector_args(name: &str, args: &[Value]) -> Result<Vec<Vec<Value>>> {
+    if args.len() < 2 {
+        return Err(SchemeError::ArityError(format!("{name} requires a procedure and at least 1 vector"), None));
+    }
+
+    // 先复制元素，回调过程中修改向量不会影响本次遍历
+    let vectors = args[1..].iter()
+        .map(|v| expect_vector(name, v).map(|items| items.borrow().clone()))
+        .collect::<Result<Vec<_>>>()?;
+    let len = vectors.iter().map(|v| v.len()).min().unwrap_or(0);
+    Ok((0..len).map(|i| vectors.iter().map(|v| v[i].clone()).collect()).collect())
+}
+
+/// (vector-map proc vector ...)：返回新向量
+pub fn vector_map(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    let mut results = Vec::new();
+    for call_args in collect_vector_args("vector-map", args)? {
+        results.push(evaluator.apply(&args[0], call_args, context)?);
+    }
+    Ok(make_vector_value(results))
+}
+
+/// (vector-for-each proc vector ...)：依次对元素调用 proc
+pub fn vector_for_each(args: &[Value], evaluator: &CoreEvaluator, context: Option<&EvaluationContext>) -> Result<Value> {
+    for call_args in collect_vector_args("vector-for-each", args)? {
+        evaluator.apply(&args[0], call_args, context)?;
+    }
+    Ok(Value::Unspecified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vector_list_round_trip() {
+        let list = Value::from_vec(vec![Value::Integer(1), Value::Integer(2), Value::Integer(3)]);
+        let vector = list_to_vector(&[list.clone()]).unwrap();
+        assert_eq!(vector.to_string(), "#(1 2 3)");
+        assert_eq!(vector_to_list(&[vector]).unwrap(), list);
+        assert_eq!(vector_to_list(&[list_to_vector(&[Value::Nil]).unwrap()]).unwrap(), Value::Nil);
+    }
+
+    #[test]
+    fn test_vector_fill_and_ref() {
+        let v = make_vector(&[Value::Integer(3)]).unwrap();
+        vector_fill(&[v.clone(), Value::symbol("x")]).unwrap();
+        assert_eq!(v.to_string(), "#(x x x)");
+        vector_set(&[v.clone(), Value::Integer(1), Value::Integer(9)]).unwrap();
+        assert_eq!(vector_ref(&[v.clone(), Value::Integer(1)]).unwrap(), Value::Integer(9));
+        assert!(vector_ref(&[v, Value::Integer(3)]).is_err());
+    }
+
+    #[test]
+    fn test_vector_copy_ranges() {
+        let v = vector(&(1..=5).map(Value::Integer).collect::<Vec<_>>()).unwrap();
+        assert_eq!(vector_copy(&[v.clone()]).unwrap().to_string(), "#(1 2 3 4 5)");
+        assert_eq!(vector_copy(&[v.clone(), Value::Integer(3)]).unwrap().to_string(), "#(4 5)");
+        assert_eq!(vector_copy(&[v.clone(), Value::Integer(1), Value::Integer(3)]).unwrap().to_string(), "#(2 3)");
+        assert_eq!(vector_copy(&[v.clone(), Value::Integer(5)]).unwrap().to_string(), "#()");
+        // 副本是新分配的
+        assert!(!vector_copy(&[v.clone()]).unwrap().is_eq(&v));
+        assert!(matches!(vector_copy(&[v.clone(), Value::Integer(6)]), Err(SchemeError::RuntimeError(..))));
+        assert!(matches!(vector_copy(&[v.clone(), Value::Integer(3), Value::Integer(2)]), Err(SchemeError::RuntimeError(..))));
+
+        let dest = make_vector(&[Value::Integer(4), Value::symbol("_")]).unwrap();
+        vector_copy_bang(&[dest.clone(), Value::Integer(1), v.clone(), Value::Integer(0), Value::Integer(2)]).unwrap();
+        assert_eq!(dest.to_string(), "#(_ 1 2 _)");
+        // 放不下时报错，且目标不变
+        assert!(matches!(vector_copy_bang(&[dest.clone(), Value::Integer(2), v.clone()]), Err(SchemeError::RuntimeError(..))));
+        assert_eq!(dest.to_string(), "#(_ 1 2 _)");
+    }
+
+    #[test]
+    fn test_vector_copy_overlapping() {
+        // 向右重叠复制：结果如同先读出源范围
+        let v = vector(&(1..=5).map(Value::Integer).collect::<Vec<_>>()).unwrap();
+        vector_copy_bang(&[v.clone(), Value::Integer(1), v.clone(), Value::Integer(0), Value::Integer(4)]).unwrap();
+        assert_eq!(v.to_string(), "#(1 1 2 3 4)");
+
+        // 向左重叠复制
+        let v = vector(&(1..=5).map(Value::Integer).collect::<Vec<_>>()).unwrap();
+        vector_copy_bang(&[v.clone(), Value::Integer(0), v.clone(), Value::Integer(1)]).unwrap();
+        assert_eq!(v.to_string(), "#(2 3 4 5 5)");
+    }
+}
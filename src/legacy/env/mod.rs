@@ -1,7 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use std::cell::RefCell;
-use crate::legacy::types::{Value, SchemeError, Result};
+use crate::legacy::types::{Value, SchemeError, Result, Symbol, Promise};
 
 /// 环境 ID 类型
 pub type EnvironmentId = usize;
@@ -18,12 +18,25 @@ pub struct EnvironmentManager {
 /// 单个环境的数据
 #[derive(Debug, Clone)]
 pub struct EnvironmentData {
-    /// 当前环境的变量绑定
-    bindings: HashMap<String, Value>,
+    /// 当前环境的变量绑定（以驻留符号为键，查找时只哈希符号编号）
+    bindings: HashMap<Symbol, Value>,
     /// 父环境的 ID
     parent_id: Option<EnvironmentId>,
 }
 
+/// 某一时刻仍可达的环境的绑定（由 [`EnvironmentManager::snapshot`] 创建）
+#[derive(Debug, Clone)]
+pub struct EnvSnapshot {
+    environments: HashMap<EnvironmentId, EnvironmentData>,
+}
+
+impl EnvSnapshot {
+    /// 快照中保存的环境个数
+    pub fn environment_count(&self) -> usize {
+        self.environments.len()
+    }
+}
+
 /// 环境引用（轻量级，不包含实际数据）
 #[derive(Debug, Clone)]
 pub struct Environment {
@@ -77,22 +90,42 @@ impl EnvironmentManager {
     /// 在环境中定义变量
     pub fn define(&mut self, env_id: EnvironmentId, name: String, value: Value) -> Result<()> {
         if let Some(env_data) = self.environments.get_mut(&env_id) {
-            env_data.bindings.insert(name, value);
+            env_data.bindings.insert(Symbol::intern(&name), value);
             Ok(())
         } else {
             Err(SchemeError::RuntimeError(format!("Environment {env_id} not found"), None))
         }
     }
 
+    /// 在环境中一次定义多个变量（同名时后者覆盖前者）
+    pub fn define_many(&mut self, env_id: EnvironmentId, bindings: Vec<(String, Value)>) -> Result<()> {
+        let env_data = self.environments.get_mut(&env_id)
+            .ok_or_else(|| SchemeError::RuntimeError(format!("Environment {env_id} not found"), None))?;
+        env_data.bindings.reserve(bindings.len());
+        for (name, value) in bindings {
+            env_data.bindings.insert(Symbol::intern(&name), value);
+        }
+        Ok(())
+    }
+
     /// 查找变量（递归查找父环境）
     pub fn lookup(&self, env_id: EnvironmentId, name: &str) -> Result<Value> {
+        // 从未驻留过的名字不可能被绑定
+        match Symbol::lookup(name) {
+            Some(symbol) => self.lookup_symbol(env_id, symbol),
+            None => Err(SchemeError::UndefinedVariable(name.to_string(), None)),
+        }
+    }
+
+    /// 按驻留符号查找变量（递归查找父环境）
+    pub fn lookup_symbol(&self, env_id: EnvironmentId, symbol: Symbol) -> Result<Value> {
         if let Some(env_data) = self.environments.get(&env_id) {
-            if let Some(value) = env_data.bindings.get(name) {
+            if let Some(value) = env_data.bindings.get(&symbol) {
                 Ok(value.clone())
             } else if let Some(parent_id) = env_data.parent_id {
-                self.lookup(parent_id, name)
+                self.lookup_symbol(parent_id, symbol)
             } else {
-                Err(SchemeError::UndefinedVariable(name.to_string(), None))
+                Err(SchemeError::UndefinedVariable(symbol.to_string(), None))
             }
         } else {
             Err(SchemeError::RuntimeError(format!("Environment {env_id} not found"), None))
@@ -102,8 +135,9 @@ impl EnvironmentManager {
     /// 设置变量值（必须是已存在的变量）
     pub fn set(&mut self, env_id: EnvironmentId, name: &str, value: Value) -> Result<()> {
         if let Some(env_data) = self.environments.get_mut(&env_id) {
-            if env_data.bindings.contains_key(name) {
-                env_data.bindings.insert(name.to_string(), value);
+            let symbol = Symbol::lookup(name);
+            if let Some(binding) = symbol.and_then(|symbol| env_data.bindings.get_mut(&symbol)) {
+                *binding = value;
                 Ok(())
             } else if let Some(parent_id) = env_data.parent_id {
                 self.set(parent_id, name, value)
@@ -131,14 +165,111 @@ impl EnvironmentManager {
         Ok(new_env_id)
     }
 
+    /// 只保留指定环境中满足条件的绑定
+    pub fn retain_bindings(&mut self, env_id: EnvironmentId, mut keep: impl FnMut(&str, &Value) -> bool) {
+        if let Some(env_data) = self.environments.get_mut(&env_id) {
+            env_data.bindings.retain(|symbol, value| keep(symbol.as_str(), value));
+        }
+    }
+
     /// 获取指定环境中定义的所有变量名
     pub fn get_local_bindings(&self, env_id: EnvironmentId) -> Vec<String> {
         if let Some(env_data) = self.environments.get(&env_id) {
-            env_data.bindings.keys().cloned().collect()
+            env_data.bindings.keys().map(|symbol| symbol.to_string()).collect()
         } else {
             Vec::new()
         }
     }
+
+    /// 获取从指定环境可见的所有绑定（沿父环境链收集，内层遮蔽外层），按名字排序
+    pub fn all_visible_bindings(&self, env_id: EnvironmentId) -> Vec<(String, Value)> {
+        let mut visible: HashMap<Symbol, Value> = HashMap::new();
+        let mut current = self.environments.get(&env_id);
+        while let Some(env_data) = current {
+            for (symbol, value) in &env_data.bindings {
+                visible.entry(*symbol).or_insert_with(|| value.clone());
+            }
+            current = env_data.parent_id.and_then(|parent_id| self.environments.get(&parent_id));
+        }
+
+        let mut bindings: Vec<(String, Value)> = visible.into_iter()
+            .map(|(symbol, value)| (symbol.to_string(), value))
+            .collect();
+        bindings.sort_by(|a, b| a.0.cmp(&b.0));
+        bindings
+    }
+
+    /// 复制从 root（通常为全局环境）可达的环境的绑定：沿父环境链，以及绑定值中
+    /// （包括序对、向量、记录、哈希表等容器内）的闭包、一等环境和承诺所引用的环境。
+    /// 环境从不释放，但已结束的调用留下的局部环境不可达，不被复制，
+    /// 因此快照的开销只取决于仍可达的数据，而不随历史调用次数增长
+    pub fn snapshot(&self, root: EnvironmentId) -> EnvSnapshot {
+        let mut environments = HashMap::new();
+        let mut visited = HashSet::new();
+        let mut pending = vec![root];
+        while let Some(env_id) = pending.pop() {
+            if environments.contains_key(&env_id) {
+                continue;
+            }
+            let Some(env_data) = self.environments.get(&env_id) else {
+                continue;
+            };
+            pending.extend(env_data.parent_id);
+            for value in env_data.bindings.values() {
+                referenced_environments(value, &mut visited, &mut pending);
+            }
+            environments.insert(env_id, env_data.clone());
+        }
+        EnvSnapshot { environments }
+    }
+
+    /// 将环境恢复为快照时的绑定；快照未保存的环境（快照之后创建的，或当时已不可达的）被丢弃。
+    /// 环境 ID 不回收：仍引用被丢弃环境的闭包调用时报告环境不存在，而不会误用新环境
+    pub fn restore(&mut self, snapshot: &EnvSnapshot) {
+        self.environments = snapshot.environments.clone();
+    }
+}
+
+/// 收集值中引用的环境 ID 到 found；visited 记录已检查过的容器，共享或成环的结构只检查一次
+fn referenced_environments(value: &Value, visited: &mut HashSet<*const ()>, found: &mut Vec<EnvironmentId>) {
+    let mut pending = vec![value.clone()];
+    while let Some(value) = pending.pop() {
+        match &value {
+            Value::Lambda { env_id, .. } | Value::Environment(env_id) => found.push(*env_id),
+            Value::Cons(car, cdr, _) if visited.insert(Rc::as_ptr(car).cast()) => {
+                pending.push(car.borrow().clone());
+                pending.push(cdr.borrow().clone());
+            },
+            Value::Vector(items, _) if visited.insert(Rc::as_ptr(items).cast()) => {
+                pending.extend(items.borrow().iter().cloned());
+            },
+            Value::Record { fields, .. } if visited.insert(Rc::as_ptr(fields).cast()) => {
+                pending.extend(fields.borrow().iter().map(|(_, value)| value.clone()));
+            },
+            Value::HashTable(table) if visited.insert(Rc::as_ptr(table).cast()) => {
+                for (key, value) in table.borrow().iter() {
+                    pending.push(key.clone());
+                    pending.push(value.clone());
+                }
+            },
+            Value::Object(object) if visited.insert(Rc::as_ptr(object).cast()) => {
+                pending.extend(object.methods.iter().map(|(_, method)| method.clone()));
+            },
+            Value::Parameter(parameter) if visited.insert(Rc::as_ptr(parameter).cast()) => {
+                pending.push(parameter.value.clone());
+                pending.extend(parameter.converter.clone());
+            },
+            Value::Promise(promise) if visited.insert(Rc::as_ptr(promise).cast()) => {
+                match &*promise.borrow() {
+                    Promise::Forced(value) => pending.push(value.clone()),
+                    Promise::Delayed { env_id, .. } | Promise::DelayForce { env_id, .. } => found.push(*env_id),
+                    Promise::Shared(inner) => pending.push(Value::Promise(Rc::clone(inner))),
+                }
+            },
+            Value::MultipleValues(values) => pending.extend(values.iter().cloned()),
+            _ => {},
+        }
+    }
 }
 
 impl Environment {
@@ -167,11 +298,21 @@ impl Environment {
         self.manager.borrow_mut().define(self.id, name, value)
     }
 
+    /// 批量定义变量（只借用一次环境管理器）
+    pub fn define_many(&self, bindings: Vec<(String, Value)>) -> Result<()> {
+        self.manager.borrow_mut().define_many(self.id, bindings)
+    }
+
     /// 查找变量
     pub fn lookup(&self, name: &str) -> Result<Value> {
         self.manager.borrow().lookup(self.id, name)
     }
 
+    /// 按驻留符号查找变量
+    pub fn lookup_symbol(&self, symbol: Symbol) -> Result<Value> {
+        self.manager.borrow().lookup_symbol(self.id, symbol)
+    }
+
     /// 设置变量
     pub fn set(&self, name: &str, value: Value) -> Result<()> {
         self.manager.borrow_mut().set(self.id, name, value)
@@ -191,10 +332,20 @@ impl Environment {
         })
     }
 
+    /// 只保留当前环境中满足条件的绑定
+    pub fn retain_bindings(&self, keep: impl FnMut(&str, &Value) -> bool) {
+        self.manager.borrow_mut().retain_bindings(self.id, keep)
+    }
+
     /// 获取当前环境中定义的所有变量名
     pub fn get_local_bindings(&self) -> Vec<String> {
         self.manager.borrow().get_local_bindings(self.id)
     }
+
+    /// 获取从当前环境可见的所有绑定（内层遮蔽外层），按名字排序
+    pub fn all_visible_bindings(&self) -> Vec<(String, Value)> {
+        self.manager.borrow().all_visible_bindings(self.id)
+    }
 }
 
 impl Default for Environment {
@@ -223,17 +374,93 @@ mod tests {
         assert!(env.lookup("y").is_err());
     }
 
+    #[test]
+    fn test_snapshot_restore() {
+        let env = Environment::default();
+        env.define("x".to_string(), Value::Integer(1)).unwrap();
+        let snapshot = env.manager.borrow().snapshot(env.id());
+
+        env.set("x", Value::Integer(2)).unwrap();
+        let child = env.new_child();
+        child.define("y".to_string(), Value::Integer(3)).unwrap();
+        env.manager.borrow_mut().restore(&snapshot);
+
+        // 绑定恢复，快照之后创建的环境被丢弃，新环境不复用其 ID
+        assert_eq!(env.lookup("x"), Ok(Value::Integer(1)));
+        assert!(child.lookup("y").is_err());
+        assert_ne!(env.new_child().id(), child.id());
+    }
+
+    #[test]
+    fn test_snapshot_copies_only_reachable_environments() {
+        let env = Environment::default();
+        // 已结束的调用留下的局部环境
+        for _ in 0..1000 {
+            env.new_child().define("tmp".to_string(), Value::Integer(0)).unwrap();
+        }
+        // 被全局闭包（放在列表中）捕获的局部环境及其父环境
+        let outer = env.new_child();
+        let inner = outer.new_child();
+        inner.define("n".to_string(), Value::Integer(1)).unwrap();
+        let closure = Value::Lambda { params: vec![], body: Rc::new(Value::symbol("n")), env_id: inner.id() };
+        env.define("closures".to_string(), Value::from_vec(vec![closure])).unwrap();
+
+        let snapshot = env.manager.borrow().snapshot(env.id());
+        assert_eq!(snapshot.environment_count(), 3);
+
+        inner.set("n", Value::Integer(2)).unwrap();
+        env.manager.borrow_mut().restore(&snapshot);
+        assert_eq!(inner.lookup("n"), Ok(Value::Integer(1)));
+    }
+
+    #[test]
+    fn test_define_many() {
+        let env = Environment::default();
+        let bindings: Vec<(String, Value)> = (0..100)
+            .map(|i| (format!("v{i}"), Value::Integer(i)))
+            .collect();
+        env.define_many(bindings).unwrap();
+        
+        for i in 0..100 {
+            assert_eq!(env.lookup(&format!("v{i}")), Ok(Value::Integer(i)));
+        }
+        assert_eq!(env.get_local_bindings().len(), 100);
+        
+        // 不存在的环境报错
+        let mut manager = EnvironmentManager::new();
+        assert!(manager.define_many(42, vec![("x".to_string(), Value::Nil)]).is_err());
+    }
+
+    #[test]
+    fn test_all_visible_bindings() {
+        let parent = Environment::default();
+        parent.define("x".to_string(), Value::Integer(1)).unwrap();
+        parent.define("y".to_string(), Value::Integer(2)).unwrap();
+        
+        let child = parent.new_child();
+        child.define("x".to_string(), Value::Integer(10)).unwrap();
+        child.define("z".to_string(), Value::Integer(3)).unwrap();
+        
+        // 内层的 x 遮蔽外层的 x，结果按名字排序
+        assert_eq!(child.all_visible_bindings(), vec![
+            ("x".to_string(), Value::Integer(10)),
+            ("y".to_string(), Value::Integer(2)),
+            ("z".to_string(), Value::Integer(3)),
+        ]);
+        assert_eq!(parent.all_visible_bindings().len(), 2);
+    }
+
     #[test]
     fn test_environment_parent() {
         let parent = Environment::default();
         parent.define("x".to_string(), Value::Integer(42)).unwrap();
         
         let child = parent.new_child();
-        child.define("y".to_string(), Value::String("hello".to_string())).unwrap();
+        child.define("y".to_string(), Value::string("hello")).unwrap();
         
         // 子环境可以访问父环境的变量
         assert_eq!(child.lookup("x"), Ok(Value::Integer(42)));
-        assert_eq!(child.lookup("y"), Ok(Value::String("hello".to_string())));
+        assert_eq!(child.lookup("y"), Ok(Value::string("hello")));
     }
 
     #[test]
@@ -1,137 +1,1047 @@
-use crate::legacy::types::Value;
+use crate::legacy::types::{Value, Arity};
 use crate::legacy::env::Environment;
 use crate::legacy::builtins;
 
 /// 注册所有内置函数到环境中
 pub fn register_builtins(env: &Environment) {
-    // 算术运算
-    env.define("+".to_string(), Value::BuiltinFunction {
-        name: "+".to_string(),
-        func: builtins::add,
-        arity: None, // 可变参数
-    }).unwrap();
-    
-    env.define("-".to_string(), Value::BuiltinFunction {
-        name: "-".to_string(),
-        func: builtins::subtract,
-        arity: None,
-    }).unwrap();
-    
-    env.define("*".to_string(), Value::BuiltinFunction {
-        name: "*".to_string(),
-        func: builtins::multiply,
-        arity: None,
-    }).unwrap();
-    
-    env.define("/".to_string(), Value::BuiltinFunction {
-        name: "/".to_string(),
-        func: builtins::divide,
-        arity: None,
-    }).unwrap();
-
-    // 比较运算
-    env.define("=".to_string(), Value::BuiltinFunction {
-        name: "=".to_string(),
-        func: builtins::equal,
-        arity: Some(2),
-    }).unwrap();
-    
-    env.define("<".to_string(), Value::BuiltinFunction {
-        name: "<".to_string(),
-        func: builtins::less_than,
-        arity: Some(2),
-    }).unwrap();
-    
-    env.define(">".to_string(), Value::BuiltinFunction {
-        name: ">".to_string(),
-        func: builtins::greater_than,
-        arity: Some(2),
-    }).unwrap();
-    
-    env.define("<=".to_string(), Value::BuiltinFunction {
-        name: "<=".to_string(),
-        func: builtins::less_equal,
-        arity: Some(2),
-    }).unwrap();
-    
-    env.define(">=".to_string(), Value::BuiltinFunction {
-        name: ">=".to_string(),
-        func: builtins::greater_equal,
-        arity: Some(2),
-    }).unwrap();
-
-    // 数学函数
-    env.define("abs".to_string(), Value::BuiltinFunction {
-        name: "abs".to_string(),
-        func: builtins::abs_func,
-        arity: Some(1),
-    }).unwrap();
-    
-    env.define("max".to_string(), Value::BuiltinFunction {
-        name: "max".to_string(),
-        func: builtins::max_func,
-        arity: None,
-    }).unwrap();
-    
-    env.define("min".to_string(), Value::BuiltinFunction {
-        name: "min".to_string(),
-        func: builtins::min_func,
-        arity: None,
-    }).unwrap();
-
-    // 列表操作
-    env.define("cons".to_string(), Value::BuiltinFunction {
-        name: "cons".to_string(),
-        func: builtins::cons,
-        arity: Some(2),
-    }).unwrap();
-    
-    env.define("car".to_string(), Value::BuiltinFunction {
-        name: "car".to_string(),
-        func: builtins::car,
-        arity: Some(1),
-    }).unwrap();
-    
-    env.define("cdr".to_string(), Value::BuiltinFunction {
-        name: "cdr".to_string(),
-        func: builtins::cdr,
-        arity: Some(1),
-    }).unwrap();
-    
-    env.define("list".to_string(), Value::BuiltinFunction {
-        name: "list".to_string(),
-        func: builtins::list,
-        arity: None,
-    }).unwrap();
-
-    // 类型谓词
-    env.define("null?".to_string(), Value::BuiltinFunction {
-        name: "null?".to_string(),
-        func: builtins::is_null,
-        arity: Some(1),
-    }).unwrap();
-    
-    env.define("pair?".to_string(), Value::BuiltinFunction {
-        name: "pair?".to_string(),
-        func: builtins::is_pair,
-        arity: Some(1),
-    }).unwrap();
-    
-    env.define("number?".to_string(), Value::BuiltinFunction {
-        name: "number?".to_string(),
-        func: builtins::is_number,
-        arity: Some(1),
-    }).unwrap();
-    
-    env.define("symbol?".to_string(), Value::BuiltinFunction {
-        name: "symbol?".to_string(),
-        func: builtins::is_symbol,
-        arity: Some(1),
-    }).unwrap();
-    
-    env.define("string?".to_string(), Value::BuiltinFunction {
-        name: "string?".to_string(),
-        func: builtins::is_string,
-        arity: Some(1),
-    }).unwrap();
-} 
\ No newline at end of file
+    let bindings = vec![
+        // 算术运算
+        ("+".to_string(), Value::BuiltinFunction {
+            name: "+".to_string(),
+            func: builtins::add,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("-".to_string(), Value::BuiltinFunction {
+            name: "-".to_string(),
+            func: builtins::subtract,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("*".to_string(), Value::BuiltinFunction {
+            name: "*".to_string(),
+            func: builtins::multiply,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("/".to_string(), Value::BuiltinFunction {
+            name: "/".to_string(),
+            func: builtins::divide,
+            arity: Arity::AtLeast(0),
+        }),
+
+        // 比较运算
+        ("=".to_string(), Value::BuiltinFunction {
+            name: "=".to_string(),
+            func: builtins::equal,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("<".to_string(), Value::BuiltinFunction {
+            name: "<".to_string(),
+            func: builtins::less_than,
+            arity: Arity::Exact(2),
+        }),
+    
+        (">".to_string(), Value::BuiltinFunction {
+            name: ">".to_string(),
+            func: builtins::greater_than,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("<=".to_string(), Value::BuiltinFunction {
+            name: "<=".to_string(),
+            func: builtins::less_equal,
+            arity: Arity::Exact(2),
+        }),
+    
+        (">=".to_string(), Value::BuiltinFunction {
+            name: ">=".to_string(),
+            func: builtins::greater_equal,
+            arity: Arity::Exact(2),
+        }),
+
+        // 数学函数
+        ("abs".to_string(), Value::BuiltinFunction {
+            name: "abs".to_string(),
+            func: builtins::abs_func,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("max".to_string(), Value::BuiltinFunction {
+            name: "max".to_string(),
+            func: builtins::max_func,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("min".to_string(), Value::BuiltinFunction {
+            name: "min".to_string(),
+            func: builtins::min_func,
+            arity: Arity::AtLeast(0),
+        }),
+
+        // 列表操作
+        ("cons".to_string(), Value::BuiltinFunction {
+            name: "cons".to_string(),
+            func: builtins::cons,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("car".to_string(), Value::BuiltinFunction {
+            name: "car".to_string(),
+            func: builtins::car,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("cdr".to_string(), Value::BuiltinFunction {
+            name: "cdr".to_string(),
+            func: builtins::cdr,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("set-car!".to_string(), Value::BuiltinFunction {
+            name: "set-car!".to_string(),
+            func: builtins::set_car,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("set-cdr!".to_string(), Value::BuiltinFunction {
+            name: "set-cdr!".to_string(),
+            func: builtins::set_cdr,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("append!".to_string(), Value::BuiltinFunction {
+            name: "append!".to_string(),
+            func: builtins::append_bang,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("reverse!".to_string(), Value::BuiltinFunction {
+            name: "reverse!".to_string(),
+            func: builtins::reverse_bang,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("list".to_string(), Value::BuiltinFunction {
+            name: "list".to_string(),
+            func: builtins::list,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("length".to_string(), Value::BuiltinFunction {
+            name: "length".to_string(),
+            func: builtins::length,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("last-pair".to_string(), Value::BuiltinFunction {
+            name: "last-pair".to_string(),
+            func: builtins::last_pair,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("delete".to_string(), Value::BuiltinFunction {
+            name: "delete".to_string(),
+            func: builtins::delete,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("assq".to_string(), Value::BuiltinFunction {
+            name: "assq".to_string(),
+            func: builtins::assq,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("assv".to_string(), Value::BuiltinFunction {
+            name: "assv".to_string(),
+            func: builtins::assv,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("assoc".to_string(), Value::EvaluatorFunction {
+            name: "assoc".to_string(),
+            func: builtins::assoc,
+            arity: Arity::Range(2, 3),
+        }),
+    
+        ("member".to_string(), Value::EvaluatorFunction {
+            name: "member".to_string(),
+            func: builtins::member,
+            arity: Arity::Range(2, 3),
+        }),
+    
+        ("alist-update".to_string(), Value::BuiltinFunction {
+            name: "alist-update".to_string(),
+            func: builtins::alist_update,
+            arity: Arity::Exact(3),
+        }),
+    
+        ("del-assq".to_string(), Value::BuiltinFunction {
+            name: "del-assq".to_string(),
+            func: builtins::del_assq,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("remove".to_string(), Value::EvaluatorFunction {
+            name: "remove".to_string(),
+            func: builtins::remove,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("map".to_string(), Value::EvaluatorFunction {
+            name: "map".to_string(),
+            func: builtins::map,
+            arity: Arity::AtLeast(2),
+        }),
+    
+        ("for-each".to_string(), Value::EvaluatorFunction {
+            name: "for-each".to_string(),
+            func: builtins::for_each,
+            arity: Arity::AtLeast(2),
+        }),
+    
+        ("apply".to_string(), Value::EvaluatorFunction {
+            name: "apply".to_string(),
+            func: builtins::apply,
+            arity: Arity::AtLeast(2),
+        }),
+    
+        ("find".to_string(), Value::EvaluatorFunction {
+            name: "find".to_string(),
+            func: builtins::find,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("list-index".to_string(), Value::EvaluatorFunction {
+            name: "list-index".to_string(),
+            func: builtins::list_index,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("any".to_string(), Value::EvaluatorFunction {
+            name: "any".to_string(),
+            func: builtins::any,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("every".to_string(), Value::EvaluatorFunction {
+            name: "every".to_string(),
+            func: builtins::every,
+            arity: Arity::Exact(2),
+        }),
+    
+        // 运行时求值
+        ("eval-string".to_string(), Value::EvaluatorFunction {
+            name: "eval-string".to_string(),
+            func: builtins::eval::eval_string,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("load".to_string(), Value::EvaluatorFunction {
+            name: "load".to_string(),
+            func: builtins::eval::load,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("read-tokens".to_string(), Value::BuiltinFunction {
+            name: "read-tokens".to_string(),
+            func: builtins::eval::read_tokens,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("eval".to_string(), Value::EvaluatorFunction {
+            name: "eval".to_string(),
+            func: builtins::eval::eval,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("interaction-environment".to_string(), Value::EvaluatorFunction {
+            name: "interaction-environment".to_string(),
+            func: builtins::eval::interaction_environment,
+            arity: Arity::Exact(0),
+        }),
+    
+        ("environment-bindings".to_string(), Value::EvaluatorFunction {
+            name: "environment-bindings".to_string(),
+            func: builtins::eval::environment_bindings,
+            arity: Arity::Range(0, 1),
+        }),
+    
+        ("profile-report".to_string(), Value::EvaluatorFunction {
+            name: "profile-report".to_string(),
+            func: builtins::eval::profile_report,
+            arity: Arity::Exact(0),
+        }),
+    
+        // 参数对象
+        ("make-parameter".to_string(), Value::EvaluatorFunction {
+            name: "make-parameter".to_string(),
+            func: builtins::make_parameter,
+            arity: Arity::AtLeast(0),
+        }),
+
+        // 类型谓词
+        ("type-of".to_string(), Value::BuiltinFunction {
+            name: "type-of".to_string(),
+            func: builtins::type_of,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("procedure-arity".to_string(), Value::BuiltinFunction {
+            name: "procedure-arity".to_string(),
+            func: builtins::procedure_arity,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("null?".to_string(), Value::BuiltinFunction {
+            name: "null?".to_string(),
+            func: builtins::is_null,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("pair?".to_string(), Value::BuiltinFunction {
+            name: "pair?".to_string(),
+            func: builtins::is_pair,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("number?".to_string(), Value::BuiltinFunction {
+            name: "number?".to_string(),
+            func: builtins::is_number,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("real?".to_string(), Value::BuiltinFunction {
+            name: "real?".to_string(),
+            func: builtins::numeric::is_real,
+            arity: Arity::Exact(1),
+        }),
+
+        ("rational?".to_string(), Value::BuiltinFunction {
+            name: "rational?".to_string(),
+            func: builtins::numeric::is_rational,
+            arity: Arity::Exact(1),
+        }),
+
+        ("exact-integer?".to_string(), Value::BuiltinFunction {
+            name: "exact-integer?".to_string(),
+            func: builtins::numeric::is_exact_integer,
+            arity: Arity::Exact(1),
+        }),
+
+        ("positive?".to_string(), Value::BuiltinFunction {
+            name: "positive?".to_string(),
+            func: builtins::numeric::is_positive,
+            arity: Arity::Exact(1),
+        }),
+
+        ("negative?".to_string(), Value::BuiltinFunction {
+            name: "negative?".to_string(),
+            func: builtins::numeric::is_negative,
+            arity: Arity::Exact(1),
+        }),
+
+        ("odd?".to_string(), Value::BuiltinFunction {
+            name: "odd?".to_string(),
+            func: builtins::numeric::is_odd,
+            arity: Arity::Exact(1),
+        }),
+
+        ("even?".to_string(), Value::BuiltinFunction {
+            name: "even?".to_string(),
+            func: builtins::numeric::is_even,
+            arity: Arity::Exact(1),
+        }),
+
+        ("symbol?".to_string(), Value::BuiltinFunction {
+            name: "symbol?".to_string(),
+            func: builtins::is_symbol,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("eq?".to_string(), Value::BuiltinFunction {
+            name: "eq?".to_string(),
+            func: builtins::eq,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("eqv?".to_string(), Value::BuiltinFunction {
+            name: "eqv?".to_string(),
+            func: builtins::eqv,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("equal?".to_string(), Value::BuiltinFunction {
+            name: "equal?".to_string(),
+            func: builtins::deep_equal,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("symbol=?".to_string(), Value::BuiltinFunction {
+            name: "symbol=?".to_string(),
+            func: builtins::symbol_equal,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("string?".to_string(), Value::BuiltinFunction {
+            name: "string?".to_string(),
+            func: builtins::is_string,
+            arity: Arity::Exact(1),
+        }),
+
+        // 字符串操作
+        ("string-pad".to_string(), Value::BuiltinFunction {
+            name: "string-pad".to_string(),
+            func: builtins::string::string_pad,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("string-pad-right".to_string(), Value::BuiltinFunction {
+            name: "string-pad-right".to_string(),
+            func: builtins::string::string_pad_right,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("string-trim".to_string(), Value::BuiltinFunction {
+            name: "string-trim".to_string(),
+            func: builtins::string::string_trim,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("string-trim-left".to_string(), Value::BuiltinFunction {
+            name: "string-trim-left".to_string(),
+            func: builtins::string::string_trim_left,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("string-trim-right".to_string(), Value::BuiltinFunction {
+            name: "string-trim-right".to_string(),
+            func: builtins::string::string_trim_right,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("string-foldcase".to_string(), Value::BuiltinFunction {
+            name: "string-foldcase".to_string(),
+            func: builtins::string::string_foldcase,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("char-foldcase".to_string(), Value::BuiltinFunction {
+            name: "char-foldcase".to_string(),
+            func: builtins::string::char_foldcase,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("string-ci=?".to_string(), Value::BuiltinFunction {
+            name: "string-ci=?".to_string(),
+            func: builtins::string::string_ci_equal,
+            arity: Arity::AtLeast(1),
+        }),
+    
+        ("string-ci<?".to_string(), Value::BuiltinFunction {
+            name: "string-ci<?".to_string(),
+            func: builtins::string::string_ci_less,
+            arity: Arity::AtLeast(1),
+        }),
+    
+        ("char-ci=?".to_string(), Value::BuiltinFunction {
+            name: "char-ci=?".to_string(),
+            func: builtins::string::char_ci_equal,
+            arity: Arity::AtLeast(1),
+        }),
+    
+        ("char->digit".to_string(), Value::BuiltinFunction {
+            name: "char->digit".to_string(),
+            func: builtins::string::char_to_digit,
+            arity: Arity::Range(1, 2),
+        }),
+    
+        ("digit->char".to_string(), Value::BuiltinFunction {
+            name: "digit->char".to_string(),
+            func: builtins::string::digit_to_char,
+            arity: Arity::Range(1, 2),
+        }),
+    
+        ("string".to_string(), Value::BuiltinFunction {
+            name: "string".to_string(),
+            func: builtins::string::string,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("list->string".to_string(), Value::BuiltinFunction {
+            name: "list->string".to_string(),
+            func: builtins::string::list_to_string,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("make-string".to_string(), Value::BuiltinFunction {
+            name: "make-string".to_string(),
+            func: builtins::string::make_string,
+            arity: Arity::Range(1, 2),
+        }),
+    
+        ("string-copy".to_string(), Value::BuiltinFunction {
+            name: "string-copy".to_string(),
+            func: builtins::string::string_copy,
+            arity: Arity::Range(1, 3),
+        }),
+    
+        ("string-ref".to_string(), Value::BuiltinFunction {
+            name: "string-ref".to_string(),
+            func: builtins::string::string_ref,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("string-set!".to_string(), Value::BuiltinFunction {
+            name: "string-set!".to_string(),
+            func: builtins::string::string_set,
+            arity: Arity::Exact(3),
+        }),
+    
+        ("string-fill!".to_string(), Value::BuiltinFunction {
+            name: "string-fill!".to_string(),
+            func: builtins::string::string_fill,
+            arity: Arity::Range(2, 4),
+        }),
+    
+        // 端口操作
+        ("open-input-string".to_string(), Value::BuiltinFunction {
+            name: "open-input-string".to_string(),
+            func: builtins::port::open_input_string,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("open-output-string".to_string(), Value::BuiltinFunction {
+            name: "open-output-string".to_string(),
+            func: builtins::port::open_output_string,
+            arity: Arity::Exact(0),
+        }),
+    
+        ("get-output-string".to_string(), Value::BuiltinFunction {
+            name: "get-output-string".to_string(),
+            func: builtins::port::get_output_string,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("close-port".to_string(), Value::BuiltinFunction {
+            name: "close-port".to_string(),
+            func: builtins::port::close_port,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("open-input-file".to_string(), Value::BuiltinFunction {
+            name: "open-input-file".to_string(),
+            func: builtins::port::open_input_file,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("open-output-file".to_string(), Value::BuiltinFunction {
+            name: "open-output-file".to_string(),
+            func: builtins::port::open_output_file,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("call-with-input-file".to_string(), Value::EvaluatorFunction {
+            name: "call-with-input-file".to_string(),
+            func: builtins::port::call_with_input_file,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("call-with-output-file".to_string(), Value::EvaluatorFunction {
+            name: "call-with-output-file".to_string(),
+            func: builtins::port::call_with_output_file,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("peek-char".to_string(), Value::BuiltinFunction {
+            name: "peek-char".to_string(),
+            func: builtins::port::peek_char,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("read-char".to_string(), Value::BuiltinFunction {
+            name: "read-char".to_string(),
+            func: builtins::port::read_char,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("char-ready?".to_string(), Value::BuiltinFunction {
+            name: "char-ready?".to_string(),
+            func: builtins::port::is_char_ready,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("read".to_string(), Value::EvaluatorFunction {
+            name: "read".to_string(),
+            func: builtins::port::read,
+            arity: Arity::Range(0, 1),
+        }),
+    
+        ("read-line".to_string(), Value::EvaluatorFunction {
+            name: "read-line".to_string(),
+            func: builtins::port::read_line,
+            arity: Arity::Range(0, 1),
+        }),
+    
+        ("with-input-from-string".to_string(), Value::EvaluatorFunction {
+            name: "with-input-from-string".to_string(),
+            func: builtins::port::with_input_from_string,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("eof-object?".to_string(), Value::BuiltinFunction {
+            name: "eof-object?".to_string(),
+            func: builtins::port::is_eof_object,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("format".to_string(), Value::BuiltinFunction {
+            name: "format".to_string(),
+            func: builtins::port::format,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("display".to_string(), Value::BuiltinFunction {
+            name: "display".to_string(),
+            func: builtins::port::display,
+            arity: Arity::Range(1, 2),
+        }),
+    
+        ("write".to_string(), Value::BuiltinFunction {
+            name: "write".to_string(),
+            func: builtins::port::write,
+            arity: Arity::Range(1, 2),
+        }),
+    
+        ("write-shared".to_string(), Value::BuiltinFunction {
+            name: "write-shared".to_string(),
+            func: builtins::port::write_shared,
+            arity: Arity::Range(1, 2),
+        }),
+    
+        ("write-simple".to_string(), Value::BuiltinFunction {
+            name: "write-simple".to_string(),
+            func: builtins::port::write_simple,
+            arity: Arity::Range(1, 2),
+        }),
+    
+        ("write-char".to_string(), Value::BuiltinFunction {
+            name: "write-char".to_string(),
+            func: builtins::port::write_char,
+            arity: Arity::Range(1, 2),
+        }),
+    
+        ("newline".to_string(), Value::BuiltinFunction {
+            name: "newline".to_string(),
+            func: builtins::port::newline,
+            arity: Arity::Range(0, 1),
+        }),
+    
+        ("write-string".to_string(), Value::BuiltinFunction {
+            name: "write-string".to_string(),
+            func: builtins::port::write_string,
+            arity: Arity::Range(1, 2),
+        }),
+    
+        ("pp".to_string(), Value::BuiltinFunction {
+            name: "pp".to_string(),
+            func: builtins::port::pp,
+            arity: Arity::Exact(1),
+        }),
+
+        // 哈希
+        ("equal-hash".to_string(), Value::BuiltinFunction {
+            name: "equal-hash".to_string(),
+            func: builtins::hash::equal_hash,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("hash".to_string(), Value::BuiltinFunction {
+            name: "hash".to_string(),
+            func: builtins::hash::equal_hash,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("make-hash-table".to_string(), Value::BuiltinFunction {
+            name: "make-hash-table".to_string(),
+            func: builtins::hash::make_hash_table,
+            arity: Arity::Exact(0),
+        }),
+    
+        ("hash-table?".to_string(), Value::BuiltinFunction {
+            name: "hash-table?".to_string(),
+            func: builtins::hash::is_hash_table,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("hash-table-set!".to_string(), Value::BuiltinFunction {
+            name: "hash-table-set!".to_string(),
+            func: builtins::hash::hash_table_set,
+            arity: Arity::Exact(3),
+        }),
+    
+        ("hash-table-ref/default".to_string(), Value::BuiltinFunction {
+            name: "hash-table-ref/default".to_string(),
+            func: builtins::hash::hash_table_ref_default,
+            arity: Arity::Exact(3),
+        }),
+    
+        ("hash-table-contains?".to_string(), Value::BuiltinFunction {
+            name: "hash-table-contains?".to_string(),
+            func: builtins::hash::hash_table_contains,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("hash-table-delete!".to_string(), Value::BuiltinFunction {
+            name: "hash-table-delete!".to_string(),
+            func: builtins::hash::hash_table_delete,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("hash-table-update!".to_string(), Value::EvaluatorFunction {
+            name: "hash-table-update!".to_string(),
+            func: builtins::hash::hash_table_update,
+            arity: Arity::Range(3, 4),
+        }),
+    
+        ("hash-table-update!/default".to_string(), Value::EvaluatorFunction {
+            name: "hash-table-update!/default".to_string(),
+            func: builtins::hash::hash_table_update_default,
+            arity: Arity::Exact(4),
+        }),
+    
+        ("hash-table-count".to_string(), Value::BuiltinFunction {
+            name: "hash-table-count".to_string(),
+            func: builtins::hash::hash_table_count,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("alist->hash-table".to_string(), Value::BuiltinFunction {
+            name: "alist->hash-table".to_string(),
+            func: builtins::hash::alist_to_hash_table,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("hash-table->alist".to_string(), Value::BuiltinFunction {
+            name: "hash-table->alist".to_string(),
+            func: builtins::hash::hash_table_to_alist,
+            arity: Arity::Exact(1),
+        }),
+
+        // 时间
+        ("current-time".to_string(), Value::BuiltinFunction {
+            name: "current-time".to_string(),
+            func: builtins::time::current_time,
+            arity: Arity::Exact(0),
+        }),
+    
+        ("current-jiffy".to_string(), Value::BuiltinFunction {
+            name: "current-jiffy".to_string(),
+            func: builtins::time::current_jiffy,
+            arity: Arity::Exact(0),
+        }),
+    
+        ("jiffies-per-second".to_string(), Value::BuiltinFunction {
+            name: "jiffies-per-second".to_string(),
+            func: builtins::time::jiffies_per_second,
+            arity: Arity::Exact(0),
+        }),
+
+        // 随机数
+        ("random".to_string(), Value::EvaluatorFunction {
+            name: "random".to_string(),
+            func: builtins::random::random,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("set-random-seed!".to_string(), Value::EvaluatorFunction {
+            name: "set-random-seed!".to_string(),
+            func: builtins::random::set_random_seed,
+            arity: Arity::Exact(1),
+        }),
+
+        // 向量操作
+        ("vector".to_string(), Value::BuiltinFunction {
+            name: "vector".to_string(),
+            func: builtins::vector::vector,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("make-vector".to_string(), Value::BuiltinFunction {
+            name: "make-vector".to_string(),
+            func: builtins::vector::make_vector,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("vector?".to_string(), Value::BuiltinFunction {
+            name: "vector?".to_string(),
+            func: builtins::vector::is_vector,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("vector-length".to_string(), Value::BuiltinFunction {
+            name: "vector-length".to_string(),
+            func: builtins::vector::vector_length,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("vector-ref".to_string(), Value::BuiltinFunction {
+            name: "vector-ref".to_string(),
+            func: builtins::vector::vector_ref,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("vector-set!".to_string(), Value::BuiltinFunction {
+            name: "vector-set!".to_string(),
+            func: builtins::vector::vector_set,
+            arity: Arity::Exact(3),
+        }),
+    
+        ("vector->list".to_string(), Value::BuiltinFunction {
+            name: "vector->list".to_string(),
+            func: builtins::vector::vector_to_list,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("list->vector".to_string(), Value::BuiltinFunction {
+            name: "list->vector".to_string(),
+            func: builtins::vector::list_to_vector,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("vector-copy".to_string(), Value::BuiltinFunction {
+            name: "vector-copy".to_string(),
+            func: builtins::vector::vector_copy,
+            arity: Arity::Range(1, 3),
+        }),
+    
+        ("vector-copy!".to_string(), Value::BuiltinFunction {
+            name: "vector-copy!".to_string(),
+            func: builtins::vector::vector_copy_bang,
+            arity: Arity::Range(3, 5),
+        }),
+    
+        ("vector-fill!".to_string(), Value::BuiltinFunction {
+            name: "vector-fill!".to_string(),
+            func: builtins::vector::vector_fill,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("vector-map".to_string(), Value::EvaluatorFunction {
+            name: "vector-map".to_string(),
+            func: builtins::vector::vector_map,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("vector-for-each".to_string(), Value::EvaluatorFunction {
+            name: "vector-for-each".to_string(),
+            func: builtins::vector::vector_for_each,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        // 排序
+        ("list-sort".to_string(), Value::EvaluatorFunction {
+            name: "list-sort".to_string(),
+            func: builtins::sort::list_sort,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("vector-sort!".to_string(), Value::EvaluatorFunction {
+            name: "vector-sort!".to_string(),
+            func: builtins::sort::vector_sort,
+            arity: Arity::Exact(2),
+        }),
+
+        // 整数除法
+        ("floor".to_string(), Value::BuiltinFunction {
+            name: "floor".to_string(),
+            func: builtins::numeric::floor,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("ceiling".to_string(), Value::BuiltinFunction {
+            name: "ceiling".to_string(),
+            func: builtins::numeric::ceiling,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("truncate".to_string(), Value::BuiltinFunction {
+            name: "truncate".to_string(),
+            func: builtins::numeric::truncate,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("round".to_string(), Value::BuiltinFunction {
+            name: "round".to_string(),
+            func: builtins::numeric::round,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("square".to_string(), Value::BuiltinFunction {
+            name: "square".to_string(),
+            func: builtins::numeric::square,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("exact-integer-sqrt".to_string(), Value::BuiltinFunction {
+            name: "exact-integer-sqrt".to_string(),
+            func: builtins::numeric::exact_integer_sqrt,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("number->string".to_string(), Value::BuiltinFunction {
+            name: "number->string".to_string(),
+            func: builtins::numeric::number_to_string,
+            arity: Arity::Range(1, 2),
+        }),
+    
+        ("string->number".to_string(), Value::BuiltinFunction {
+            name: "string->number".to_string(),
+            func: builtins::numeric::string_to_number,
+            arity: Arity::Range(1, 2),
+        }),
+    
+        ("floor/".to_string(), Value::BuiltinFunction {
+            name: "floor/".to_string(),
+            func: builtins::numeric::floor_slash,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("floor-quotient".to_string(), Value::BuiltinFunction {
+            name: "floor-quotient".to_string(),
+            func: builtins::numeric::floor_quotient,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("floor-remainder".to_string(), Value::BuiltinFunction {
+            name: "floor-remainder".to_string(),
+            func: builtins::numeric::floor_remainder,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("truncate/".to_string(), Value::BuiltinFunction {
+            name: "truncate/".to_string(),
+            func: builtins::numeric::truncate_slash,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("truncate-quotient".to_string(), Value::BuiltinFunction {
+            name: "truncate-quotient".to_string(),
+            func: builtins::numeric::truncate_quotient,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("truncate-remainder".to_string(), Value::BuiltinFunction {
+            name: "truncate-remainder".to_string(),
+            func: builtins::numeric::truncate_remainder,
+            arity: Arity::Exact(2),
+        }),
+    
+
+        // 多值
+        ("values".to_string(), Value::BuiltinFunction {
+            name: "values".to_string(),
+            func: builtins::control::values,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("call-with-values".to_string(), Value::EvaluatorFunction {
+            name: "call-with-values".to_string(),
+            func: builtins::control::call_with_values,
+            arity: Arity::Exact(2),
+        }),
+    
+        // 异常
+        ("raise".to_string(), Value::EvaluatorFunction {
+            name: "raise".to_string(),
+            func: builtins::control::raise,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("raise-continuable".to_string(), Value::EvaluatorFunction {
+            name: "raise-continuable".to_string(),
+            func: builtins::control::raise_continuable,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("with-exception-handler".to_string(), Value::EvaluatorFunction {
+            name: "with-exception-handler".to_string(),
+            func: builtins::control::with_exception_handler,
+            arity: Arity::Exact(2),
+        }),
+    
+        ("error".to_string(), Value::EvaluatorFunction {
+            name: "error".to_string(),
+            func: builtins::control::error,
+            arity: Arity::AtLeast(1),
+        }),
+    
+        ("error?".to_string(), Value::BuiltinFunction {
+            name: "error?".to_string(),
+            func: builtins::control::is_error_object,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("error-object?".to_string(), Value::BuiltinFunction {
+            name: "error-object?".to_string(),
+            func: builtins::control::is_error_object,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("error-object-message".to_string(), Value::BuiltinFunction {
+            name: "error-object-message".to_string(),
+            func: builtins::control::error_object_message,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("error-object-irritants".to_string(), Value::BuiltinFunction {
+            name: "error-object-irritants".to_string(),
+            func: builtins::control::error_object_irritants,
+            arity: Arity::Exact(1),
+        }),
+    
+        // 实现信息
+        ("features".to_string(), Value::BuiltinFunction {
+            name: "features".to_string(),
+            func: builtins::control::features,
+            arity: Arity::Exact(0),
+        }),
+    
+        ("*version*".to_string(), Value::string(builtins::control::VERSION.to_string())),
+    
+        // 退出
+        ("exit".to_string(), Value::BuiltinFunction {
+            name: "exit".to_string(),
+            func: builtins::control::exit,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        ("emergency-exit".to_string(), Value::BuiltinFunction {
+            name: "emergency-exit".to_string(),
+            func: builtins::control::emergency_exit,
+            arity: Arity::AtLeast(0),
+        }),
+    
+        // 承诺
+        ("force".to_string(), Value::EvaluatorFunction {
+            name: "force".to_string(),
+            func: builtins::promise::force,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("make-promise".to_string(), Value::BuiltinFunction {
+            name: "make-promise".to_string(),
+            func: builtins::promise::make_promise,
+            arity: Arity::Exact(1),
+        }),
+    
+        ("promise?".to_string(), Value::BuiltinFunction {
+            name: "promise?".to_string(),
+            func: builtins::promise::is_promise,
+            arity: Arity::Exact(1),
+        }),
+    ];
+
+    env.define_many(bindings).unwrap();
+}
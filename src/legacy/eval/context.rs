@@ -1,4 +1,5 @@
-use crate::legacy::types::Position;
+use std::rc::Rc;
+use crate::legacy::types::{Position, Parameter, Value};
 
 /// 调用栈帧
 #[derive(Debug, Clone)]
@@ -8,12 +9,21 @@ pub struct CallFrame {
     pub expression: String,
 }
 
+/// parameterize 安装的动态绑定（不可变链表，内层绑定在前）
+#[derive(Debug)]
+pub struct DynamicBinding {
+    pub parameter: Rc<Parameter>,
+    pub value: Value,
+    pub next: Option<Rc<DynamicBinding>>,
+}
+
 /// 链式不可变执行上下文
 #[derive(Debug, Clone)]
 pub struct EvaluationContext {
     pub current_position: Option<Position>,
     pub function_name: Option<String>,
     pub parent: Option<Box<EvaluationContext>>, // 链式结构
+    pub dynamic_bindings: Option<Rc<DynamicBinding>>,
 }
 
 impl EvaluationContext {
@@ -23,6 +33,7 @@ impl EvaluationContext {
             current_position: None,
             function_name: None,
             parent: None,
+            dynamic_bindings: None,
         }
     }
     
@@ -32,7 +43,31 @@ impl EvaluationContext {
             current_position: position,
             function_name,
             parent: Some(Box::new(self.clone())),
+            dynamic_bindings: self.dynamic_bindings.clone(),
+        }
+    }
+    
+    /// 安装一个参数的动态绑定，返回新的上下文
+    pub fn with_dynamic_binding(&self, parameter: Rc<Parameter>, value: Value) -> Self {
+        let mut ctx = self.clone();
+        ctx.dynamic_bindings = Some(Rc::new(DynamicBinding {
+            parameter,
+            value,
+            next: self.dynamic_bindings.clone(),
+        }));
+        ctx
+    }
+    
+    /// 查找参数的当前动态绑定值（最内层优先）
+    pub fn lookup_parameter(&self, parameter: &Rc<Parameter>) -> Option<Value> {
+        let mut current = self.dynamic_bindings.as_ref();
+        while let Some(binding) = current {
+            if Rc::ptr_eq(&binding.parameter, parameter) {
+                return Some(binding.value.clone());
+            }
+            current = binding.next.as_ref();
         }
+        None
     }
     
     /// 获取完整调用栈
@@ -1,9 +1,20 @@
 use std::rc::Rc;
-use std::cell::RefCell;
-use crate::legacy::types::{Value, SchemeError, Result, LocatedValue};
-use crate::legacy::env::{Environment, EnvironmentManager, EnvironmentId};
-use crate::legacy::eval::special_forms::SpecialFormsEvaluator;
+use std::cell::{Cell, RefCell};
+use crate::legacy::types::{Value, SchemeError, Result, LocatedValue, Symbol, Parameter, Port, InputPort};
+use crate::legacy::env::{Environment, EnvironmentManager, EnvironmentId, EnvSnapshot};
+use crate::legacy::eval::special_forms::{SpecialFormsEvaluator, Tail};
 use crate::legacy::eval::context::EvaluationContext;
+use crate::legacy::eval::macros::{MacroScope, SyntaxRules};
+use crate::legacy::eval::profile::{Profiler, ProfileEntry};
+use crate::legacy::eval::optimize::{self, Operator};
+
+/// 异常处理器栈中的一项
+enum ExceptionHandler {
+    /// with-exception-handler 安装的处理过程，在 raise 的动态环境中被调用
+    Procedure(Value),
+    /// guard 的标记：条件沿 Err 传播到该 guard，由其子句处理
+    Guard,
+}
 
 /// 核心求值器
 pub struct CoreEvaluator {
@@ -11,8 +22,31 @@ pub struct CoreEvaluator {
     env_manager: Rc<RefCell<EnvironmentManager>>,
     /// 全局环境 ID
     global_env_id: EnvironmentId,
+    /// eval-string 等运行时求值的当前嵌套深度
+    nesting_depth: Cell<usize>,
+    /// eval 的当前递归深度
+    eval_depth: Cell<usize>,
+    /// 最近一次顶层求值期间 eval 达到的最大递归深度
+    max_eval_depth: Cell<usize>,
+    /// 伪随机数生成器状态（splitmix64）
+    rng_state: Cell<u64>,
+    /// 宏作用域栈：第一个为全局宏表，let-syntax 展开期间压入局部作用域
+    macro_scopes: RefCell<Vec<MacroScope>>,
+    /// 当前输入端口参数（默认为标准输入，可被 with-input-from-string 等动态重绑定）
+    current_input_port: Rc<Parameter>,
+    /// 当前安装的异常处理器（栈顶为最近安装者）
+    exception_handlers: RefCell<Vec<ExceptionHandler>>,
+    /// 按过程名的调用统计（默认关闭）
+    profiler: RefCell<Profiler>,
+    /// 是否在顶层求值前进行常量折叠（默认关闭）
+    optimize: Cell<bool>,
+    /// 创建以来执行的求值步数
+    eval_steps: Cell<u64>,
 }
 
+/// 运行时求值（如 eval-string）允许的最大嵌套深度
+pub const MAX_NESTING_DEPTH: usize = 64;
+
 impl CoreEvaluator {
     /// 创建新的核心求值器
     pub fn new() -> Self {
@@ -23,6 +57,19 @@ impl CoreEvaluator {
         CoreEvaluator {
             env_manager,
             global_env_id,
+            nesting_depth: Cell::new(0),
+            eval_depth: Cell::new(0),
+            max_eval_depth: Cell::new(0),
+            rng_state: Cell::new(crate::legacy::storage::since_unix_epoch().as_nanos() as u64),
+            macro_scopes: RefCell::new(vec![MacroScope::new()]),
+            current_input_port: Rc::new(Parameter {
+                value: Value::Port(Rc::new(RefCell::new(Port::Input(InputPort::stdin())))),
+                converter: None,
+            }),
+            exception_handlers: RefCell::new(Vec::new()),
+            profiler: RefCell::new(Profiler::default()),
+            optimize: Cell::new(false),
+            eval_steps: Cell::new(0),
         }
     }
 
@@ -36,6 +83,134 @@ impl CoreEvaluator {
 
     /// 核心求值方法
     pub fn eval(&self, expr: &Value, env: &Environment, context: Option<&EvaluationContext>) -> Result<Value> {
+        // 从深度 0 进入即为一次新的顶层求值，重新统计最大深度
+        let depth = self.eval_depth.get() + 1;
+        if depth == 1 {
+            self.max_eval_depth.set(0);
+        }
+        self.eval_depth.set(depth);
+        self.max_eval_depth.set(self.max_eval_depth.get().max(depth));
+
+        let result = if depth == 1 && self.optimize.get() {
+            let folded = optimize::fold_constants(expr, &|name| self.resolve_operator(name, env));
+            self.eval_expr(&folded, env, context)
+        } else {
+            self.eval_expr(expr, env, context)
+        };
+        self.eval_depth.set(depth - 1);
+        result
+    }
+
+    /// 开启或关闭按过程名的调用统计
+    pub fn set_profiling(&self, enabled: bool) {
+        self.profiler.borrow_mut().set_enabled(enabled);
+    }
+
+    /// 清空调用统计
+    pub fn reset_profile(&self) {
+        self.profiler.borrow_mut().reset();
+    }
+
+    /// 按过程名排序的调用统计
+    pub fn profile_report(&self) -> Vec<(String, ProfileEntry)> {
+        self.profiler.borrow().report()
+    }
+
+    /// 开启或关闭顶层表达式求值前的常量折叠（见 [`optimize::fold_constants`]）
+    pub fn set_optimize(&self, enabled: bool) {
+        self.optimize.set(enabled);
+    }
+
+    /// 求值器创建以来执行的求值步数（每次对表达式求值的一步计一次），可用于比较优化前后的工作量
+    pub fn eval_steps(&self) -> u64 {
+        self.eval_steps.get()
+    }
+
+    /// 常量折叠时运算符符号的含义：宏优先，其次为 env 中的绑定
+    fn resolve_operator(&self, name: Symbol, env: &Environment) -> Operator {
+        if self.lookup_macro(name).is_some() {
+            return Operator::Opaque;
+        }
+        match &env.lookup_symbol(name) {
+            Ok(Value::BuiltinFunction { name: builtin, func, arity })
+                if builtin == name.as_str() && optimize::PURE_BUILTINS.contains(&builtin.as_str()) => Operator::Pure(*func, *arity),
+            Ok(value) if value.is_procedure() => Operator::Procedure,
+            _ => Operator::Opaque,
+        }
+    }
+
+    /// 最近一次顶层求值（对 eval-string 等多表达式求值而言为最后一个表达式）期间
+    /// eval 达到的最大递归深度，可用于确认尾递归循环的深度不随迭代次数增长
+    pub fn last_eval_max_depth(&self) -> usize {
+        self.max_eval_depth.get()
+    }
+
+    fn eval_expr(&self, expr: &Value, env: &Environment, context: Option<&EvaluationContext>) -> Result<Value> {
+        let mut profiled = Vec::new();
+        let result = self.eval_tail_loop(expr, env, context, &mut profiled);
+        // 本循环中发起的调用（包括尾调用）在循环结束时一并结束
+        if !profiled.is_empty() {
+            let mut profiler = self.profiler.borrow_mut();
+            for name in profiled.iter().rev() {
+                profiler.leave(name);
+            }
+        }
+        result
+    }
+
+    /// 开启统计时记录一次具名调用的开始
+    fn profile_call(&self, name: &Option<String>, profiled: &mut Vec<String>) {
+        if let Some(name) = name {
+            let mut profiler = self.profiler.borrow_mut();
+            if profiler.is_enabled() {
+                profiler.enter(name);
+                profiled.push(name.clone());
+            }
+        }
+    }
+
+    fn eval_tail_loop(&self, expr: &Value, env: &Environment, context: Option<&EvaluationContext>, profiled: &mut Vec<String>) -> Result<Value> {
+        // 尾位置上的表达式和过程调用在此循环中就地继续，而不是递归调用 eval。
+        // 尾调用 lambda 时以新的调用帧替换本循环先前压入的帧，调用链长度保持不变
+        let mut tail = self.eval_form(expr, env, context)?;
+        let mut tail_context: Option<EvaluationContext> = None;
+        loop {
+            let context = tail_context.as_ref().or(context);
+            tail = match tail {
+                Tail::Value(value) => return Ok(value),
+                Tail::Eval(expr, env) => self.eval_form(&expr, &env, context)?,
+                Tail::Apply { mut func, args, name } if matches!(&func, Value::Lambda { params, .. } if args.len() == params.len()) => {
+                    let Value::Lambda { params, body, env_id } = &mut func else {
+                        unreachable!()
+                    };
+                    self.profile_call(&name, profiled);
+                    let closure_env = Environment::from_id(*env_id, self.env_manager.clone());
+                    let new_env = closure_env.extend(std::mem::take(params), args)?;
+
+                    let frame = context.map(|ctx| {
+                        let mut frame = match &tail_context {
+                            Some(current) => current.clone(),
+                            None => ctx.enter_call(ctx.current_position, None),
+                        };
+                        frame.function_name = name.or(Some("<lambda>".to_string()));
+                        frame
+                    });
+                    tail_context = frame;
+                    Tail::Eval((**body).clone(), new_env)
+                },
+                // 其余过程（以及参数个数不符、由 apply_procedure 报错的 lambda）直接调用
+                Tail::Apply { func, args, name } => {
+                    self.profile_call(&name, profiled);
+                    let call_context = context.map(|ctx| ctx.enter_call(ctx.current_position, None));
+                    Tail::Value(self.apply_procedure(func, args, name, call_context.as_ref())?)
+                },
+            };
+        }
+    }
+
+    /// 求值一个表达式的一步：直接得到值，或给出尾位置上待继续求值的表达式或过程调用
+    fn eval_form(&self, expr: &Value, env: &Environment, context: Option<&EvaluationContext>) -> Result<Tail> {
+        self.eval_steps.set(self.eval_steps.get() + 1);
         // 辅助函数：为错误添加位置信息和调用栈
         let enrich_error = |error: SchemeError| -> SchemeError {
             if let Some(ctx) = context {
@@ -52,6 +227,10 @@ impl CoreEvaluator {
                             SchemeError::ArityError(msg, Some(pos)),
                         SchemeError::DivisionByZero(None) => 
                             SchemeError::DivisionByZero(Some(pos)),
+                        SchemeError::AssertionError(msg, None) => 
+                            SchemeError::AssertionError(msg, Some(pos)),
+                        SchemeError::SyntaxError(msg, None) => 
+                            SchemeError::SyntaxError(msg, Some(pos)),
                         other => other, // 已经有位置信息的错误保持不变
                     }
                 } else {
@@ -72,36 +251,62 @@ impl CoreEvaluator {
 
         match expr {
             // 自求值表达式
-            Value::Integer(_) | Value::Float(_) | Value::String(_) | Value::Bool(_) => {
-                Ok(expr.clone())
+            Value::Integer(_) | Value::Float(_) | Value::String(_) | Value::MutableString(_) | Value::Bool(_) | Value::Char(_)
+            | Value::Vector(_, _) | Value::Eof | Value::Default | Value::Unspecified => {
+                Ok(Tail::Value(expr.clone()))
             },
             
             // 空列表
-            Value::Nil => Ok(Value::Nil),
+            Value::Nil => Ok(Tail::Value(Value::Nil)),
             
             // 符号（变量查找）
-            Value::Symbol(name) => env.lookup(name).map_err(enrich_error),
+            Value::Symbol(name) => env.lookup_symbol(*name).map(Tail::Value).map_err(enrich_error),
             
             // 列表（函数调用或特殊形式）
-            Value::Cons(_, _) => {
+            Value::Cons(_, _, _) => {
                 if let Some(list) = expr.to_vec() {
                     if list.is_empty() {
-                        return Ok(Value::Nil);
+                        return Ok(Tail::Value(Value::Nil));
                     }
                     
-                    // 检查是否为特殊形式
+                    // 检查是否为宏调用或特殊形式
                     if let Value::Symbol(op) = &list[0] {
+                        if let Some(transformer) = self.lookup_macro(*op) {
+                            let expanded = transformer.expand(expr).map_err(enrich_error)?;
+                            return Ok(Tail::Eval(expanded, env.clone()));
+                        }
                         match op.as_str() {
-                            "quote" => SpecialFormsEvaluator::eval_quote(&list[1..], env),
+                            "quote" => SpecialFormsEvaluator::eval_quote(&list[1..], env).map(Tail::Value),
                             "if" => SpecialFormsEvaluator::eval_if(&list[1..], env, &|e, env| self.eval(e, env, context)),
-                            "define" => SpecialFormsEvaluator::eval_define(&list[1..], env, &|e, env| self.eval(e, env, context)),
-                            "set!" => SpecialFormsEvaluator::eval_set(&list[1..], env, &|e, env| self.eval(e, env, context)),
-                            "lambda" => SpecialFormsEvaluator::eval_lambda(&list[1..], env),
-                            "let" => SpecialFormsEvaluator::eval_let(&list[1..], env, &|e, env| self.eval(e, env, context)),
-                            "begin" => SpecialFormsEvaluator::eval_begin(&list[1..], env, &|e, env| self.eval(e, env, context)),
+                            "when" => SpecialFormsEvaluator::eval_when(&list[1..], env, &|e, env| self.eval(e, env, context)),
+                            "unless" => SpecialFormsEvaluator::eval_unless(&list[1..], env, &|e, env| self.eval(e, env, context)),
+                            "define" => SpecialFormsEvaluator::eval_define(&list[1..], env, &|e, env| self.eval(e, env, context))
+                                .map(Tail::Value).map_err(enrich_error),
+                            "define-values" => SpecialFormsEvaluator::eval_define_values(&list[1..], env, &|e, env| self.eval(e, env, context))
+                                .map(Tail::Value).map_err(enrich_error),
+                            "set!" => SpecialFormsEvaluator::eval_set(&list[1..], env, &|e, env| self.eval(e, env, context)).map(Tail::Value),
+                            "lambda" => SpecialFormsEvaluator::eval_lambda(&list[1..], env).map(Tail::Value).map_err(enrich_error),
+                            "let" => SpecialFormsEvaluator::eval_let(&list[1..], env, &|e, env| self.eval(e, env, context)).map_err(enrich_error),
+                            "do" => SpecialFormsEvaluator::eval_do(&list[1..], env, &|e, env| self.eval(e, env, context)).map_err(enrich_error),
+                            "begin" => SpecialFormsEvaluator::eval_sequence(&list[1..], env, &|e, env| self.eval(e, env, context)),
                             "and" => SpecialFormsEvaluator::eval_and(&list[1..], env, &|e, env| self.eval(e, env, context)),
                             "or" => SpecialFormsEvaluator::eval_or(&list[1..], env, &|e, env| self.eval(e, env, context)),
                             "cond" => SpecialFormsEvaluator::eval_cond(&list[1..], env, &|e, env| self.eval(e, env, context)),
+                            "case" => SpecialFormsEvaluator::eval_case(&list[1..], env, &|e, env| self.eval(e, env, context)),
+                            "cond-expand" => SpecialFormsEvaluator::eval_cond_expand(&list[1..], env, &|e, env| self.eval(e, env, context))
+                                .map_err(enrich_error),
+                            "parameterize" => self.eval_parameterize(&list[1..], env, context).map(Tail::Value),
+                            "guard" => self.eval_guard(&list[1..], env, context).map_err(enrich_error),
+                            "delay" => SpecialFormsEvaluator::eval_delay(&list[1..], env).map(Tail::Value),
+                            "delay-force" | "lazy" => SpecialFormsEvaluator::eval_delay_force(&list[1..], env).map(Tail::Value),
+                            "the-environment" => SpecialFormsEvaluator::eval_the_environment(&list[1..], env).map(Tail::Value),
+                            "define-syntax" => self.eval_define_syntax(&list[1..]).map(Tail::Value).map_err(enrich_error),
+                            "let-syntax" | "letrec-syntax" => self.eval_let_syntax(&list[1..], env, context).map_err(enrich_error),
+                            "syntax-error" => SpecialFormsEvaluator::eval_syntax_error(&list[1..]).map(Tail::Value).map_err(enrich_error),
+                            "define-record-type" => SpecialFormsEvaluator::eval_define_record_type(&list[1..], env).map(Tail::Value),
+                            "make-object" => SpecialFormsEvaluator::eval_make_object(&list[1..], env).map(Tail::Value).map_err(enrich_error),
+                            "assert" => SpecialFormsEvaluator::eval_assert(&list[1..], env, &|e, env| self.eval(e, env, context))
+                                .map(Tail::Value).map_err(enrich_error),
                             _ => {
                                 // 对于函数调用，创建一个包含调用位置的新上下文
                                 // TODO: 改进位置信息传递
@@ -129,8 +334,38 @@ impl CoreEvaluator {
         }
     }
 
-    /// 求值函数应用
-    fn eval_application(&self, exprs: &[Value], env: &Environment, context: Option<&EvaluationContext>) -> Result<Value> {
+    /// 求值函数应用的运算符和参数；调用本身处于尾位置，交给求值循环完成
+    fn eval_application(&self, exprs: &[Value], env: &Environment, context: Option<&EvaluationContext>) -> Result<Tail> {
+        if exprs.is_empty() {
+            return Ok(Tail::Value(Value::Nil));
+        }
+
+        // 在求值前保存函数名（如果是符号的话）
+        let func_name = if let Value::Symbol(name) = &exprs[0] {
+            Some(name.to_string())
+        } else {
+            None
+        };
+
+        // 求值函数
+        let func = self.eval(&exprs[0], env, context)?;
+        
+        // 求值参数
+        let mut args = Vec::new();
+        for arg_expr in &exprs[1..] {
+            args.push(self.eval(arg_expr, env, context)?);
+        }
+
+        Ok(Tail::Apply { func, args, name: func_name })
+    }
+
+    /// 以已求值的参数调用过程（供高阶内置函数回调使用）
+    pub fn apply(&self, func: &Value, args: Vec<Value>, context: Option<&EvaluationContext>) -> Result<Value> {
+        self.apply_procedure(func.clone(), args, None, context)
+    }
+
+    /// 应用函数
+    fn apply_procedure(&self, mut func: Value, args: Vec<Value>, func_name: Option<String>, context: Option<&EvaluationContext>) -> Result<Value> {
         // 辅助函数：为错误添加位置信信息
         let enrich_error = |error: SchemeError| -> SchemeError {
             if let Some(ctx) = context {
@@ -147,6 +382,8 @@ impl CoreEvaluator {
                             SchemeError::ArityError(msg, Some(pos)),
                         SchemeError::DivisionByZero(None) => 
                             SchemeError::DivisionByZero(Some(pos)),
+                        SchemeError::AssertionError(msg, None) => 
+                            SchemeError::AssertionError(msg, Some(pos)),
                         other => other, // 已经有位置信息的错误保持不变
                     }
                 } else {
@@ -165,38 +402,45 @@ impl CoreEvaluator {
             }
         };
 
-        if exprs.is_empty() {
-            return Ok(Value::Nil);
-        }
-
-        // 在求值前保存函数名（如果是符号的话）
-        let func_name = if let Value::Symbol(name) = &exprs[0] {
-            Some(name.clone())
-        } else {
-            None
-        };
-
-        // 求值函数
-        let func = self.eval(&exprs[0], env, context)?;
-        
-        // 求值参数
-        let mut args = Vec::new();
-        for arg_expr in &exprs[1..] {
-            args.push(self.eval(arg_expr, env, context)?);
-        }
-
-        // 应用函数
-        match func {
+        match &mut func {
             Value::BuiltinFunction { func, arity, .. } => {
                 // 检查参数个数
-                if let Some(expected_arity) = arity {
-                    if args.len() != expected_arity {
-                        return Err(enrich_error(SchemeError::ArityError(
-                            format!("Expected {} arguments, got {}", expected_arity, args.len()), None
-                        )));
-                    }
+                let result = if arity.accepts(args.len()) {
+                    func(&args)
+                } else {
+                    Err(SchemeError::ArityError(format!("Expected {} arguments, got {}", arity, args.len()), None))
+                };
+                result.or_else(|error| self.signal_error(error, context)).map_err(enrich_error)
+            },
+            
+            Value::EvaluatorFunction { func, arity, .. } => {
+                if !arity.accepts(args.len()) {
+                    return Err(enrich_error(SchemeError::ArityError(
+                        format!("Expected {} arguments, got {}", arity, args.len()), None
+                    )));
                 }
-                func(&args).map_err(enrich_error)
+                func(&args, self, context).map_err(enrich_error)
+            },
+            
+            Value::RecordProcedure(procedure) => procedure.apply(&args)
+                .or_else(|error| self.signal_error(error, context))
+                .map_err(enrich_error),
+            
+            // 对象按消息名找到方法闭包，以其余参数调用
+            Value::Object(object) => match object.dispatch(args) {
+                Ok((method, args)) => self.apply_procedure(method, args, func_name, context),
+                Err(error) => self.signal_error(error, context).map_err(enrich_error),
+            },
+            
+            Value::Parameter(parameter) => {
+                if !args.is_empty() {
+                    return Err(enrich_error(SchemeError::ArityError(
+                        format!("Expected 0 arguments, got {}", args.len()), None
+                    )));
+                }
+                Ok(context
+                    .and_then(|ctx| ctx.lookup_parameter(parameter))
+                    .unwrap_or_else(|| parameter.value.clone()))
             },
             
             Value::Lambda { params, body, env_id } => {
@@ -207,8 +451,8 @@ impl CoreEvaluator {
                 }
                 
                 // 从环境ID创建新环境绑定参数
-                let closure_env = Environment::from_id(env_id, self.env_manager.clone());
-                let new_env = closure_env.extend(params, args)?;
+                let closure_env = Environment::from_id(*env_id, self.env_manager.clone());
+                let new_env = closure_env.extend(std::mem::take(params), args)?;
                 
                 // 创建子上下文，使用函数名更新上下文
                 let child_context = context.map(|ctx| {
@@ -220,25 +464,370 @@ impl CoreEvaluator {
                     new_ctx
                 });
                 
-                self.eval(&body, &new_env, child_context.as_ref())
+                self.eval(body, &new_env, child_context.as_ref())
             },
             
             _ => Err(enrich_error(SchemeError::TypeError(format!("Cannot apply non-function: {func}"), None))),
         }
     }
 
-    /// 便利方法：求值字符串
+    /// 求值 parameterize 特殊形式：在新的上下文中安装动态绑定并求值 body，
+    /// 离开时（包括出错时）外层上下文不受影响，绑定自然恢复
+    fn eval_parameterize(&self, args: &[Value], env: &Environment, context: Option<&EvaluationContext>) -> Result<Value> {
+        if args.len() < 2 {
+            return Err(SchemeError::ArityError("parameterize requires bindings and a body".to_string(), None));
+        }
+
+        let bindings = args[0].to_vec().ok_or_else(|| {
+            SchemeError::SyntaxError("parameterize bindings must be a list".to_string(), None)
+        })?;
+
+        let mut new_context = context.cloned().unwrap_or_default();
+        for binding in bindings {
+            let pair = binding.to_vec().filter(|pair| pair.len() == 2).ok_or_else(|| {
+                SchemeError::SyntaxError(format!("Invalid parameterize binding: {binding}"), None)
+            })?;
+            let parameter = match &self.eval(&pair[0], env, context)? {
+                Value::Parameter(parameter) => parameter.clone(),
+                other => return Err(SchemeError::TypeError(format!("parameterize expects a parameter, got {other}"), None)),
+            };
+            let mut value = self.eval(&pair[1], env, context)?;
+            if let Some(converter) = &parameter.converter {
+                value = self.apply(converter, vec![value], context)?;
+            }
+            new_context = new_context.with_dynamic_binding(parameter, value);
+        }
+
+        SpecialFormsEvaluator::eval_begin(&args[1..], env, &|e, env| self.eval(e, env, Some(&new_context)))
+    }
+
+    /// 将条件对象交给最近安装的异常处理器。
+    /// 最近的是 guard（或没有处理器）时以 Err 传播到该 guard（或顶层）；
+    /// 是处理过程时在移除该处理器的动态环境中调用它：raise-continuable 以其返回值作为结果，
+    /// raise 则在处理过程返回时报错
+    pub fn raise(&self, payload: Value, continuable: bool, context: Option<&EvaluationContext>) -> Result<Value> {
+        let innermost = self.exception_handlers.borrow_mut().pop();
+        let handler = match innermost {
+            Some(ExceptionHandler::Procedure(handler)) => handler,
+            Some(ExceptionHandler::Guard) => {
+                self.exception_handlers.borrow_mut().push(ExceptionHandler::Guard);
+                return Err(SchemeError::Raised { payload, continuable });
+            },
+            None => return Err(SchemeError::Raised { payload, continuable }),
+        };
+
+        let result = self.apply(&handler, vec![payload.clone()], context);
+        self.exception_handlers.borrow_mut().push(ExceptionHandler::Procedure(handler));
+        let value = result?;
+        if continuable {
+            Ok(value)
+        } else {
+            Err(SchemeError::RuntimeError(
+                format!("exception handler returned from non-continuable raise of {payload}"), None
+            ))
+        }
+    }
+
+    /// 内置过程产生的错误：最近安装的是处理过程时，以对应的错误对象（不可继续地）交给它；
+    /// 否则原样传播，由 guard 或顶层处理，保留原始错误的类型与位置
+    fn signal_error(&self, error: SchemeError, context: Option<&EvaluationContext>) -> Result<Value> {
+        let handled = !matches!(error, SchemeError::Raised { .. } | SchemeError::Exit(_))
+            && matches!(self.exception_handlers.borrow().last(), Some(ExceptionHandler::Procedure(_)));
+        if handled {
+            self.raise(error.to_condition(), false, context)
+        } else {
+            Err(error)
+        }
+    }
+
+    /// 安装 handler 后调用无参过程 thunk，返回时（包括出错时）卸载 handler
+    pub fn with_exception_handler(&self, handler: &Value, thunk: &Value, context: Option<&EvaluationContext>) -> Result<Value> {
+        self.exception_handlers.borrow_mut().push(ExceptionHandler::Procedure(handler.clone()));
+        let result = self.apply(thunk, vec![], context);
+        self.exception_handlers.borrow_mut().pop();
+        result
+    }
+
+    /// 求值 guard 特殊形式：(guard (var clause ...) body ...)。
+    /// 主体中 raise 的条件（内置错误则为对应的错误对象）绑定到 var 后按 cond 子句处理；
+    /// 没有子句匹配时向外层处理器重新抛出
+    fn eval_guard(&self, args: &[Value], env: &Environment, context: Option<&EvaluationContext>) -> Result<Tail> {
+        let (var, clauses) = match args.first().and_then(Value::to_vec).as_deref() {
+            Some([Value::Symbol(var), clauses @ ..]) => (*var, clauses.to_vec()),
+            _ => return Err(SchemeError::SyntaxError(
+                "guard requires a (variable clause ...) specification".to_string(), None
+            )),
+        };
+        if args.len() < 2 {
+            return Err(SchemeError::SyntaxError("guard requires a body".to_string(), None));
+        }
+
+        self.exception_handlers.borrow_mut().push(ExceptionHandler::Guard);
+        let result = SpecialFormsEvaluator::eval_begin(&args[1..], env, &|e, env| self.eval(e, env, context));
+        self.exception_handlers.borrow_mut().pop();
+
+        // raise 的条件对象与内置错误（转为错误对象）都可被捕获；exit 不可捕获
+        let error = match result {
+            Err(SchemeError::Exit(code)) => return Err(SchemeError::Exit(code)),
+            Err(error) => error,
+            Ok(value) => return Ok(Tail::Value(value)),
+        };
+        let payload = error.to_condition();
+        // guard 已卸载，匹配子句的主体处于尾位置
+        let clause_env = env.extend(vec![var.to_string()], vec![payload])?;
+        let handled = SpecialFormsEvaluator::eval_cond_clauses("guard", &clauses, &clause_env, &|e, env| self.eval(e, env, context))?;
+        match (handled, error) {
+            (Some(tail), _) => Ok(tail),
+            (None, SchemeError::Raised { payload, continuable }) => self.raise(payload, continuable, context).map(Tail::Value),
+            (None, error) => self.signal_error(error, context).map(Tail::Value),
+        }
+    }
+
+    /// 按从内到外的顺序查找宏
+    fn lookup_macro(&self, name: Symbol) -> Option<Rc<SyntaxRules>> {
+        self.macro_scopes.borrow().iter().rev()
+            .find_map(|scope| scope.get(&name).cloned())
+    }
+
+    /// 解析 ((name transformer) ...) 形式的宏绑定列表
+    fn parse_syntax_bindings(form: &str, bindings: &Value) -> Result<MacroScope> {
+        let bindings = bindings.to_vec().ok_or_else(|| {
+            SchemeError::SyntaxError(format!("{form} bindings must be a list"), None)
+        })?;
+        let mut scope = MacroScope::new();
+        for binding in bindings {
+            match binding.to_vec().as_deref() {
+                Some([Value::Symbol(name), spec]) => {
+                    scope.insert(*name, Rc::new(SyntaxRules::parse(spec)?));
+                },
+                _ => return Err(SchemeError::SyntaxError(format!("Invalid {form} binding: {binding}"), None)),
+            }
+        }
+        Ok(scope)
+    }
+
+    /// 求值 define-syntax 特殊形式：在全局宏表中定义宏
+    fn eval_define_syntax(&self, args: &[Value]) -> Result<Value> {
+        match args {
+            [Value::Symbol(name), spec] => {
+                let transformer = Rc::new(SyntaxRules::parse(spec)?);
+                self.macro_scopes.borrow_mut()[0].insert(*name, transformer);
+                Ok(Value::Unspecified)
+            },
+            _ => Err(SchemeError::SyntaxError("define-syntax requires a name and a transformer".to_string(), None)),
+        }
+    }
+
+    /// 求值 let-syntax / letrec-syntax 特殊形式：在局部宏作用域中展开 body，
+    /// 弹出作用域后再求值，因此局部宏不会泄漏到外部。
+    /// 由于宏是非卫生的，两者目前行为相同（绑定的宏都能看到彼此）
+    fn eval_let_syntax(&self, args: &[Value], env: &Environment, context: Option<&EvaluationContext>) -> Result<Tail> {
+        let body = self.expand_let_syntax(args, &[])?;
+        SpecialFormsEvaluator::eval_sequence(&body, env, &|e, env| self.eval(e, env, context))
+    }
+
+    /// 压入 let-syntax 的宏作用域并展开 body 中的所有宏调用，返回展开后的 body；
+    /// bound 为外层绑定形式中遮蔽宏的局部名字
+    fn expand_let_syntax(&self, args: &[Value], bound: &[Symbol]) -> Result<Vec<Value>> {
+        let Some(bindings) = args.first() else {
+            return Err(SchemeError::SyntaxError("let-syntax requires bindings and a body".to_string(), None));
+        };
+        let scope = Self::parse_syntax_bindings("let-syntax", bindings)?;
+
+        // 新绑定的宏不再被外层同名的局部变量遮蔽
+        let bound: Vec<Symbol> = bound.iter().filter(|name| !scope.contains_key(*name)).copied().collect();
+        self.macro_scopes.borrow_mut().push(scope);
+        let body = self.expand_body(&args[1..], &bound);
+        self.macro_scopes.borrow_mut().pop();
+        body
+    }
+
+    /// 递归展开表达式中所有可见的宏调用（不进入 quote）。
+    /// bound 中的名字在当前位置被局部绑定遮蔽，不作为宏展开
+    fn expand_macros(&self, expr: &Value, bound: &[Symbol]) -> Result<Value> {
+        let Value::Cons(head, rest, _) = expr else {
+            return Ok(expr.clone());
+        };
+        let head = head.borrow().clone();
+        if let Value::Symbol(op) = &head {
+            if !bound.contains(op) {
+                if let Some(transformer) = self.lookup_macro(*op) {
+                    return self.expand_macros(&transformer.expand(expr)?, bound);
+                }
+                match op.as_str() {
+                    "quote" => return Ok(expr.clone()),
+                    "let-syntax" | "letrec-syntax" => {
+                        let args = rest.borrow().to_vec().ok_or_else(|| {
+                            SchemeError::SyntaxError(format!("Invalid {op} form"), None)
+                        })?;
+                        let mut body = self.expand_let_syntax(&args, bound)?;
+                        body.insert(0, Value::symbol("begin"));
+                        return Ok(Value::from_vec(body));
+                    },
+                    _ => {
+                        if let Some(expanded) = self.expand_binding_form(op.as_str(), expr, bound)? {
+                            return Ok(expanded);
+                        }
+                    },
+                }
+            }
+        }
+        self.expand_macros_in_list(expr, bound)
+    }
+
+    /// 展开绑定形式（lambda、define、define-values、let、do）：形参和绑定名原样保留，
+    /// 它们在各自的作用域中遮蔽同名的宏。不是这些形式时返回 None；
+    /// 格式不对的部分原样保留，交由求值时报错
+    fn expand_binding_form(&self, op: &str, expr: &Value, bound: &[Symbol]) -> Result<Option<Value>> {
+        let Some(items) = expr.to_vec() else {
+            return Ok(None);
+        };
+        let scoped = |names: Vec<Symbol>| -> Vec<Symbol> { bound.iter().copied().chain(names).collect() };
+
+        let (mut expanded, body_start, body_bound) = match (op, items.as_slice()) {
+            ("lambda", [_, formals, ..]) => (items[..2].to_vec(), 2, scoped(formal_names(formals))),
+            // (define (name param ...) body ...)：名字与参数都在主体中可见
+            ("define", [_, target @ Value::Cons(..), ..]) => (items[..2].to_vec(), 2, scoped(formal_names(target))),
+            ("define" | "define-values", [_, _, ..]) => (items[..2].to_vec(), 2, bound.to_vec()),
+            ("let", [_, rest @ ..]) => {
+                let (name, bindings_index) = match rest.first() {
+                    Some(Value::Symbol(name)) => (vec![*name], 2),
+                    _ => (Vec::new(), 1),
+                };
+                let Some(bindings) = items.get(bindings_index).and_then(Value::to_vec) else {
+                    return Ok(Some(expr.clone()));
+                };
+                let mut names = name;
+                let mut expanded_bindings = Vec::new();
+                for binding in bindings {
+                    match binding.to_vec().as_deref() {
+                        Some([var @ Value::Symbol(name), init]) => {
+                            names.push(*name);
+                            expanded_bindings.push(Value::from_vec(vec![var.clone(), self.expand_macros(init, bound)?]));
+                        },
+                        _ => expanded_bindings.push(binding.clone()),
+                    }
+                }
+                let mut head = items[..bindings_index].to_vec();
+                head.push(Value::from_vec(expanded_bindings));
+                (head, bindings_index + 1, scoped(names))
+            },
+            // (do ((var init [step]) ...) (test expr ...) command ...)：init 在外层作用域中展开
+            ("do", [_, specs, _, ..]) => {
+                let Some(specs) = specs.to_vec() else {
+                    return Ok(Some(expr.clone()));
+                };
+                let names = specs.iter()
+                    .filter_map(|spec| match spec.car() {
+                        Some(Value::Symbol(name)) => Some(name),
+                        _ => None,
+                    })
+                    .collect();
+                let inner = scoped(names);
+                let mut expanded_specs = Vec::new();
+                for spec in specs {
+                    match spec.to_vec().as_deref() {
+                        Some([var, init, step @ ..]) => {
+                            let mut parts = vec![var.clone(), self.expand_macros(init, bound)?];
+                            parts.extend(self.expand_body(step, &inner)?);
+                            expanded_specs.push(Value::from_vec(parts));
+                        },
+                        _ => expanded_specs.push(spec.clone()),
+                    }
+                }
+                (vec![items[0].clone(), Value::from_vec(expanded_specs)], 2, inner)
+            },
+            _ => return Ok(None),
+        };
+        expanded.extend(self.expand_body(&items[body_start..], &body_bound)?);
+        Ok(Some(Value::from_vec(expanded)))
+    }
+
+    /// 依次展开一组形式
+    fn expand_body(&self, forms: &[Value], bound: &[Symbol]) -> Result<Vec<Value>> {
+        forms.iter().map(|form| self.expand_macros(form, bound)).collect()
+    }
+
+    /// 展开列表中每个元素的宏调用，保留非正常列表的尾部
+    fn expand_macros_in_list(&self, list: &Value, bound: &[Symbol]) -> Result<Value> {
+        match list {
+            Value::Cons(car, cdr, _) => Ok(Value::cons(
+                self.expand_macros(&car.borrow(), bound)?,
+                self.expand_macros_in_list(&cdr.borrow(), bound)?,
+            )),
+            other => Ok(other.clone()),
+        }
+    }
+
+    /// 全局宏表的副本
+    pub fn global_macros(&self) -> MacroScope {
+        self.macro_scopes.borrow()[0].clone()
+    }
+
+    /// 以给定宏表替换全局宏表
+    pub fn set_global_macros(&self, macros: MacroScope) {
+        self.macro_scopes.borrow_mut()[0] = macros;
+    }
+
+    /// 当前输入端口参数对象
+    pub fn current_input_port_parameter(&self) -> Rc<Parameter> {
+        Rc::clone(&self.current_input_port)
+    }
+
+    /// 在给定上下文中的当前输入端口
+    pub fn current_input_port(&self, context: Option<&EvaluationContext>) -> Value {
+        context
+            .and_then(|ctx| ctx.lookup_parameter(&self.current_input_port))
+            .unwrap_or_else(|| self.current_input_port.value.clone())
+    }
+
+    /// 设置随机数种子，相同种子产生相同的随机序列
+    pub fn set_random_seed(&self, seed: u64) {
+        self.rng_state.set(seed);
+    }
+
+    /// 生成下一个 64 位伪随机数（splitmix64）
+    pub fn next_random(&self) -> u64 {
+        let state = self.rng_state.get().wrapping_add(0x9E37_79B9_7F4A_7C15);
+        self.rng_state.set(state);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// 在嵌套深度保护下执行运行时求值，防止自我求值导致的无限递归
+    pub fn with_nesting_guard<T>(&self, name: &str, f: impl FnOnce() -> Result<T>) -> Result<T> {
+        let depth = self.nesting_depth.get();
+        if depth >= MAX_NESTING_DEPTH {
+            return Err(SchemeError::RuntimeError(
+                format!("{name}: maximum nesting depth ({MAX_NESTING_DEPTH}) exceeded"), None
+            ));
+        }
+        self.nesting_depth.set(depth + 1);
+        let result = f();
+        self.nesting_depth.set(depth);
+        result
+    }
+
+    /// 便利方法：求值字符串。字符串可以包含多个顶层表达式，依次在全局环境中求值
+    /// （后面的表达式能看到前面的 define），返回最后一个的值
     pub fn eval_string(&self, input: &str, context: Option<&EvaluationContext>) -> Result<Value> {
-        let expr = crate::legacy::parser::Parser::parse(input)?;
+        let exprs = crate::legacy::parser::Parser::parse_multiple(input)?;
+        if exprs.is_empty() {
+            // 没有任何表达式：沿用单个表达式解析时的错误
+            return crate::legacy::parser::Parser::parse(input);
+        }
         let global_env = Environment::from_id(self.global_env_id, self.env_manager.clone());
         
         // 如果没有提供上下文，创建一个根上下文以支持 callstack 追踪
-        if let Some(ctx) = context {
-            self.eval(&expr, &global_env, Some(ctx))
-        } else {
-            let default_context = EvaluationContext::new();
-            self.eval(&expr, &global_env, Some(&default_context))
+        let default_context = EvaluationContext::new();
+        let context = context.or(Some(&default_context));
+        let mut result = Value::Unspecified;
+        for expr in &exprs {
+            result = self.eval(expr, &global_env, context)?;
         }
+        Ok(result)
     }
 
     /// 获取全局环境
@@ -246,6 +835,21 @@ impl CoreEvaluator {
         Environment::from_id(self.global_env_id, self.env_manager.clone())
     }
 
+    /// 保存全局环境及从它可达的环境的绑定（见 [`EnvironmentManager::snapshot`]）
+    pub fn snapshot(&self) -> EnvSnapshot {
+        self.env_manager.borrow().snapshot(self.global_env_id)
+    }
+
+    /// 将所有环境恢复为快照时的绑定
+    pub fn restore(&self, snapshot: &EnvSnapshot) {
+        self.env_manager.borrow_mut().restore(snapshot);
+    }
+
+    /// 根据环境 ID 获取环境引用
+    pub fn environment(&self, env_id: EnvironmentId) -> Environment {
+        Environment::from_id(env_id, self.env_manager.clone())
+    }
+
     /// 求值带位置信息的表达式
     pub fn eval_located(&self, located_expr: &LocatedValue, env: &Environment, context: Option<&EvaluationContext>) -> Result<Value> {
         // 如果表达式有位置信息，创建一个新的上下文使用这个位置
@@ -260,6 +864,7 @@ impl CoreEvaluator {
                     current_position: Some(pos),
                     function_name: None,
                     parent: None,
+                    dynamic_bindings: None,
                 })
             })
         } else {
@@ -269,11 +874,30 @@ impl CoreEvaluator {
         self.eval(&located_expr.value, env, enhanced_context.as_ref())
     }
     
-    /// 求值带位置信息的字符串
+    /// 求值带位置信息的字符串；与 eval_string 一样依次求值所有顶层表达式并返回最后一个的值
     pub fn eval_string_located(&self, input: &str, context: Option<&EvaluationContext>) -> Result<Value> {
-        let located_expr = crate::legacy::parser::Parser::parse_located(input)?;
+        let located_exprs = crate::legacy::parser::Parser::parse_multiple_located(input)?;
+        if located_exprs.is_empty() {
+            return crate::legacy::parser::Parser::parse_located(input).map(|located| located.value);
+        }
+        let global_env = Environment::from_id(self.global_env_id, self.env_manager.clone());
+        let mut result = Value::Unspecified;
+        for located_expr in &located_exprs {
+            result = self.eval_located(located_expr, &global_env, context)?;
+        }
+        Ok(result)
+    }
+
+    /// 在全局环境中依次求值程序中的所有顶层表达式，返回每个表达式的结果
+    pub fn eval_all(&self, input: &str) -> Result<Vec<Value>> {
+        // 整个程序一次解析，重复出现的常量数据共享结构
+        let located_exprs = crate::legacy::parser::Parser::new(input)?.parse_program_located()?;
         let global_env = Environment::from_id(self.global_env_id, self.env_manager.clone());
-        self.eval_located(&located_expr, &global_env, context)
+        let context = EvaluationContext::new();
+        
+        located_exprs.iter()
+            .map(|expr| self.eval_located(expr, &global_env, Some(&context)))
+            .collect()
     }
 }
 
@@ -282,3 +906,18 @@ impl Default for CoreEvaluator {
         Self::new()
     }
 }
+
+/// 形参表中绑定的名字：单个符号、符号列表及其点号尾部；
+/// 对 define 的 (name param ...) 还包括过程名（柯里化写法中嵌套的头部同样计入）
+fn formal_names(formals: &Value) -> Vec<Symbol> {
+    let mut names = Vec::new();
+    let mut rest = formals.clone();
+    while let Some(formal) = rest.car() {
+        names.extend(formal_names(&formal));
+        rest = rest.cdr().unwrap_or(Value::Nil);
+    }
+    if let Value::Symbol(name) = rest {
+        names.push(name);
+    }
+    names
+}
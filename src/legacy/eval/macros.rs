@@ -0,0 +1,334 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use crate::legacy::types::{Value, SchemeError, Result, Symbol};
+
+/// 宏作用域：宏名到变换器的映射
+pub type MacroScope = HashMap<Symbol, Rc<SyntaxRules>>;
+
+/// syntax-rules 变换器（非卫生宏）
+#[derive(Debug)]
+pub struct SyntaxRules {
+    /// 省略号符号（默认为 `...`）
+    ellipsis: Symbol,
+    /// 字面量标识符
+    literals: Vec<Symbol>,
+    /// 规则列表：(模式, 模板)
+    rules: Vec<(Value, Value)>,
+}
+
+/// 模式变量的绑定
+#[derive(Debug, Clone)]
+enum Binding {
+    /// 普通模式变量
+    One(Value),
+    /// 省略号下的模式变量，每次重复对应一个绑定
+    Many(Vec<Binding>),
+}
+
+type Bindings = HashMap<Symbol, Binding>;
+
+impl SyntaxRules {
+    /// 解析 (syntax-rules (literal ...) (pattern template) ...)，
+    /// 也支持 R7RS 的自定义省略号形式 (syntax-rules ellipsis (literal ...) rule ...)
+    pub fn parse(spec: &Value) -> Result<Self> {
+        let parts = spec.to_vec().ok_or_else(|| {
+            SchemeError::SyntaxError(format!("Invalid macro transformer: {spec}"), None)
+        })?;
+        match parts.first() {
+            Some(Value::Symbol(head)) if *head == "syntax-rules" => {},
+            _ => return Err(SchemeError::SyntaxError(
+                format!("Unsupported macro transformer: {spec}"), None
+            )),
+        }
+
+        let (ellipsis, rest) = match parts.get(1) {
+            Some(Value::Symbol(ellipsis)) => (*ellipsis, &parts[2..]),
+            _ => (Symbol::intern("..."), &parts[1..]),
+        };
+
+        let literal_list = rest.first().and_then(|literals| literals.to_vec()).ok_or_else(|| {
+            SchemeError::SyntaxError("syntax-rules requires a literal list".to_string(), None)
+        })?;
+        let mut literals = Vec::new();
+        for literal in literal_list {
+            match literal {
+                Value::Symbol(name) => literals.push(name),
+                other => return Err(SchemeError::SyntaxError(
+                    format!("syntax-rules literal must be a symbol, got {other}"), None
+                )),
+            }
+        }
+
+        let mut rules = Vec::new();
+        for rule in &rest[1..] {
+            match rule.to_vec().as_deref() {
+                Some([pattern @ Value::Cons(_, _, _), template]) => rules.push((pattern.clone(), template.clone())),
+                _ => return Err(SchemeError::SyntaxError(format!("Invalid syntax-rules rule: {rule}"), None)),
+            }
+        }
+
+        Ok(SyntaxRules { ellipsis, literals, rules })
+    }
+
+    /// 用第一条匹配的规则展开宏调用（展开一层）
+    pub fn expand(&self, form: &Value) -> Result<Value> {
+        let args = match form {
+            Value::Cons(_, args, _) => args.borrow().clone(),
+            _ => return Err(SchemeError::SyntaxError(format!("Invalid macro use: {form}"), None)),
+        };
+        for (pattern, template) in &self.rules {
+            // 模式的第一个元素对应宏关键字本身，不参与匹配
+            let pattern_args = match pattern {
+                Value::Cons(_, rest, _) => rest.borrow().clone(),
+                _ => continue,
+            };
+            let mut bindings = Bindings::new();
+            if self.match_pattern(&pattern_args, &args, &mut bindings) {
+                return self.expand_template(template, &bindings);
+            }
+        }
+        let keyword = match form {
+            Value::Cons(keyword, _, _) => keyword.borrow().to_string(),
+            _ => form.to_string(),
+        };
+        Err(SchemeError::SyntaxError(format!("{keyword}: no syntax-rules pattern matches {form}"), None))
+    }
+
+    fn is_ellipsis(&self, value: &Value) -> bool {
+        matches!(value, Value::Symbol(name) if *name == self.ellipsis)
+    }
+
+    /// 匹配模式，成功时将模式变量写入 bindings
+    fn match_pattern(&self, pattern: &Value, form: &Value, bindings: &mut Bindings) -> bool {
+        match pattern {
+            Value::Symbol(name) if self.literals.contains(name) => {
+                matches!(form, Value::Symbol(other) if other == name)
+            },
+            Value::Symbol(name) if *name == "_" => true,
+            Value::Symbol(name) => {
+                bindings.insert(*name, Binding::One(form.clone()));
+                true
+            },
+            Value::Cons(_, _, _) => {
+                let (items, tail) = split_list(pattern);
+                match items.iter().position(|item| self.is_ellipsis(item)) {
+                    Some(index) if index > 0 => self.match_ellipsis(&items, index - 1, &tail, form, bindings),
+                    _ => self.match_sequence(&items, &tail, form, bindings),
+                }
+            },
+            Value::Nil => form.is_nil(),
+            _ => pattern == form,
+        }
+    }
+
+    /// 匹配不含省略号的（可能带点尾部的）列表模式
+    fn match_sequence(&self, items: &[Value], tail: &Value, form: &Value, bindings: &mut Bindings) -> bool {
+        let mut current = form.clone();
+        for item in items {
+            let next = match &current {
+                Value::Cons(car, cdr, _) => {
+                    if !self.match_pattern(item, &car.borrow(), bindings) {
+                        return false;
+                    }
+                    cdr.borrow().clone()
+                },
+                _ => return false,
+            };
+            current = next;
+        }
+        self.match_pattern(tail, &current, bindings)
+    }
+
+    /// 匹配形如 (before ... repeated <ellipsis> after ... . tail) 的列表模式
+    fn match_ellipsis(&self, items: &[Value], repeated_index: usize, tail: &Value, form: &Value, bindings: &mut Bindings) -> bool {
+        let before = &items[..repeated_index];
+        let repeated = &items[repeated_index];
+        let after = &items[repeated_index + 2..];
+
+        let (elements, form_tail) = split_list(form);
+        if elements.len() < before.len() + after.len() {
+            return false;
+        }
+        let repeat_count = elements.len() - before.len() - after.len();
+
+        for (item, element) in before.iter().zip(&elements) {
+            if !self.match_pattern(item, element, bindings) {
+                return false;
+            }
+        }
+
+        let mut iterations = Vec::with_capacity(repeat_count);
+        for element in &elements[before.len()..before.len() + repeat_count] {
+            let mut iteration = Bindings::new();
+            if !self.match_pattern(repeated, element, &mut iteration) {
+                return false;
+            }
+            iterations.push(iteration);
+        }
+        for var in self.pattern_vars(repeated) {
+            let values = iterations.iter_mut()
+                .filter_map(|iteration| iteration.remove(&var))
+                .collect();
+            bindings.insert(var, Binding::Many(values));
+        }
+
+        for (item, element) in after.iter().zip(&elements[before.len() + repeat_count..]) {
+            if !self.match_pattern(item, element, bindings) {
+                return false;
+            }
+        }
+        self.match_pattern(tail, &form_tail, bindings)
+    }
+
+    /// 收集模式中的所有模式变量
+    fn pattern_vars(&self, pattern: &Value) -> Vec<Symbol> {
+        let mut vars = Vec::new();
+        self.collect_pattern_vars(pattern, &mut vars);
+        vars
+    }
+
+    fn collect_pattern_vars(&self, pattern: &Value, vars: &mut Vec<Symbol>) {
+        match pattern {
+            Value::Symbol(name)
+                if !self.literals.contains(name) && *name != "_" && *name != self.ellipsis => {
+                vars.push(*name);
+            },
+            Value::Cons(car, cdr, _) => {
+                self.collect_pattern_vars(&car.borrow(), vars);
+                self.collect_pattern_vars(&cdr.borrow(), vars);
+            },
+            _ => {},
+        }
+    }
+
+    /// 用模式变量绑定实例化模板
+    fn expand_template(&self, template: &Value, bindings: &Bindings) -> Result<Value> {
+        match template {
+            Value::Symbol(name) => match bindings.get(name) {
+                Some(Binding::One(value)) => Ok(value.clone()),
+                Some(Binding::Many(_)) => Err(SchemeError::SyntaxError(
+                    format!("Pattern variable {name} used without ellipsis"), None
+                )),
+                None => Ok(template.clone()),
+            },
+            Value::Cons(car, cdr, _) => {
+                // (... template) 转义：模板中的省略号按字面处理
+                if self.is_ellipsis(&car.borrow()) {
+                    if let Value::Cons(escaped, rest, _) = &*cdr.borrow() {
+                        if rest.borrow().is_nil() {
+                            return Ok(escaped.borrow().clone());
+                        }
+                    }
+                }
+
+                let (items, tail) = split_list(template);
+                let mut result = Vec::new();
+                let mut index = 0;
+                while index < items.len() {
+                    let item = &items[index];
+                    let mut depth = 0;
+                    while items.get(index + 1 + depth).is_some_and(|next| self.is_ellipsis(next)) {
+                        depth += 1;
+                    }
+                    if depth == 0 {
+                        result.push(self.expand_template(item, bindings)?);
+                    } else {
+                        result.extend(self.expand_repeated(item, bindings, depth)?);
+                    }
+                    index += 1 + depth;
+                }
+
+                let tail = self.expand_template(&tail, bindings)?;
+                Ok(result.into_iter().rev().fold(tail, |acc, value| Value::cons(value, acc)))
+            },
+            _ => Ok(template.clone()),
+        }
+    }
+
+    /// 展开后跟 depth 个省略号的子模板
+    fn expand_repeated(&self, template: &Value, bindings: &Bindings, depth: usize) -> Result<Vec<Value>> {
+        let mut vars = Vec::new();
+        self.collect_pattern_vars(template, &mut vars);
+        let repeated: Vec<(Symbol, &Vec<Binding>)> = vars.into_iter()
+            .filter_map(|var| match bindings.get(&var) {
+                Some(Binding::Many(values)) => Some((var, values)),
+                _ => None,
+            })
+            .collect();
+
+        let count = match repeated.first() {
+            Some((_, values)) => values.len(),
+            None => return Err(SchemeError::SyntaxError(
+                format!("No pattern variable to repeat in {template}"), None
+            )),
+        };
+        if repeated.iter().any(|(_, values)| values.len() != count) {
+            return Err(SchemeError::SyntaxError(
+                format!("Mismatched ellipsis lengths in {template}"), None
+            ));
+        }
+
+        let mut result = Vec::new();
+        for index in 0..count {
+            let mut iteration = bindings.clone();
+            for (var, values) in &repeated {
+                iteration.insert(*var, values[index].clone());
+            }
+            if depth == 1 {
+                result.push(self.expand_template(template, &iteration)?);
+            } else {
+                result.extend(self.expand_repeated(template, &iteration, depth - 1)?);
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// 拆分 cons 链为元素列表和最终的尾部（正常列表的尾部为空表）
+fn split_list(list: &Value) -> (Vec<Value>, Value) {
+    let mut items = Vec::new();
+    let mut current = list.clone();
+    while let Value::Cons(car, cdr, _) = &current {
+        items.push(car.borrow().clone());
+        let next = cdr.borrow().clone();
+        current = next;
+    }
+    (items, current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::legacy::parser::Parser;
+
+    fn rules(source: &str) -> SyntaxRules {
+        SyntaxRules::parse(&Parser::parse(source).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_expand_with_ellipsis() {
+        let my_let = rules("(syntax-rules () ((_ ((name val) ...) body ...) ((lambda (name ...) body ...) val ...)))");
+        let expanded = my_let.expand(&Parser::parse("(my-let ((a 1) (b 2)) (+ a b))").unwrap()).unwrap();
+        assert_eq!(expanded, Parser::parse("((lambda (a b) (+ a b)) 1 2)").unwrap());
+
+        // 字面量必须按名字匹配
+        let arrow = rules("(syntax-rules (=>) ((_ a => b) (list a b)))");
+        assert!(arrow.expand(&Parser::parse("(m 1 => 2)").unwrap()).is_ok());
+        assert!(arrow.expand(&Parser::parse("(m 1 -> 2)").unwrap()).is_err());
+
+        // 无匹配规则时的错误信息包含宏名和出错的形式
+        match arrow.expand(&Parser::parse("(m 1 -> 2)").unwrap()) {
+            Err(SchemeError::SyntaxError(message, _)) => assert_eq!(message, "m: no syntax-rules pattern matches (m 1 -> 2)"),
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        // 点尾部模式与零次重复
+        let tail = rules("(syntax-rules () ((_ x . rest) (quote rest)))");
+        assert_eq!(
+            tail.expand(&Parser::parse("(m 1 2 3)").unwrap()).unwrap(),
+            Parser::parse("(quote (2 3))").unwrap()
+        );
+        let empty = my_let.expand(&Parser::parse("(my-let () 0)").unwrap()).unwrap();
+        assert_eq!(empty, Parser::parse("((lambda () 0))").unwrap());
+    }
+}
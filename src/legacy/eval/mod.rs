@@ -3,33 +3,72 @@ pub mod builtins;
 pub mod special_forms;
 pub mod core;
 pub mod context;
+pub mod macros;
+pub mod optimize;
+pub mod profile;
 
 // 重新导出主要类型
 pub use core::CoreEvaluator;
 pub use builtins::register_builtins;
-pub use context::{EvaluationContext, CallFrame};
+pub use context::{EvaluationContext, CallFrame, DynamicBinding};
 
+use std::cell::RefCell;
 use crate::legacy::types::{Value, Result};
-use crate::legacy::env::Environment;
+use crate::legacy::env::{Environment, EnvSnapshot};
+use macros::MacroScope;
 
 /// 求值器 - 重构后的主求值器
 pub struct Evaluator {
     /// 核心求值器
     core: CoreEvaluator,
+    /// 创建时注册的内置绑定与宿主经 register_native 注册的绑定；重置时只保留这些名字并恢复其原值
+    builtin_bindings: RefCell<Vec<(String, Value)>>,
+    /// 创建时的全局宏表；重置时恢复为它，清除用户以 define-syntax 定义的宏
+    builtin_macros: MacroScope,
 }
 
 impl Evaluator {
     /// 创建新的求值器
     pub fn new() -> Self {
-        let evaluator = Evaluator {
-            core: CoreEvaluator::new(),
-        };
+        let core = CoreEvaluator::new();
         
         // 注册内置函数
-        let global_env = evaluator.core.global_env();
+        let global_env = core.global_env();
         register_builtins(&global_env);
+        global_env.define(
+            "current-input-port".to_string(),
+            Value::Parameter(core.current_input_port_parameter()),
+        ).unwrap();
+        
+        let builtin_bindings = global_env.get_local_bindings().into_iter()
+            .filter_map(|name| global_env.lookup(&name).ok().map(|value| (name, value)))
+            .collect();
         
-        evaluator
+        let builtin_macros = core.global_macros();
+        
+        Evaluator { core, builtin_bindings: RefCell::new(builtin_bindings), builtin_macros }
+    }
+
+    /// 清除用户定义的全局绑定，只保留内置绑定和宿主经 register_native 注册的绑定；
+    /// 被覆盖的绑定恢复原值。用户以 define 绑定到内置过程的名字（如 (define my-car car)）同样被清除；
+    /// 用户以 define-syntax 定义的宏也一并清除
+    pub fn reset_user_bindings(&self) {
+        self.core.set_global_macros(self.builtin_macros.clone());
+        let global_env = self.core.global_env();
+        let builtin_bindings = self.builtin_bindings.borrow();
+        global_env.retain_bindings(|name, _| builtin_bindings.iter().any(|(builtin, _)| builtin == name));
+        for (name, value) in builtin_bindings.iter() {
+            global_env.define(name.clone(), value.clone())
+                .expect("global environment always exists");
+        }
+    }
+
+    /// 注册宿主提供的全局绑定（如原生函数）：与内置绑定一样在 reset_user_bindings 后保留
+    pub fn register_native(&self, name: &str, value: Value) {
+        self.define_global(name, value.clone());
+        let mut builtin_bindings = self.builtin_bindings.borrow_mut();
+        builtin_bindings.retain(|(builtin, _)| builtin != name);
+        builtin_bindings.push((name.to_string(), value));
     }
 
     /// 获取全局环境
@@ -37,12 +76,24 @@ impl Evaluator {
         self.core.global_env()
     }
 
+    /// 在全局环境中定义（或覆盖）绑定，供宿主在运行脚本前注入配置值；
+    /// 与用户定义一样会被 reset_user_bindings 清除，需要保留时用 register_native
+    pub fn define_global(&self, name: &str, value: Value) {
+        self.core.global_env().define(name.to_string(), value)
+            .expect("global environment always exists");
+    }
+
+    /// 读取全局绑定的当前值；未定义时返回 None
+    pub fn get_global(&self, name: &str) -> Option<Value> {
+        self.core.global_env().lookup(name).ok()
+    }
+
     /// 求值表达式
     pub fn eval(&self, expr: &Value, env: &Environment, context: Option<&EvaluationContext>) -> Result<Value> {
         self.core.eval(expr, env, context)
     }
 
-    /// 便利方法：求值字符串
+    /// 便利方法：求值字符串中的所有顶层表达式，返回最后一个的值
     pub fn eval_string(&self, input: &str, context: Option<&EvaluationContext>) -> Result<Value> {
         self.core.eval_string(input, context)
     }
@@ -57,10 +108,50 @@ impl Evaluator {
         self.core.eval_string_located(input, context)
     }
 
+    /// 求值整个程序，返回所有顶层表达式的结果（遇到第一个错误即返回）
+    pub fn eval_all(&self, input: &str) -> Result<Vec<Value>> {
+        self.core.eval_all(input)
+    }
+
     /// 获取全局环境
     pub fn get_global_env(&self) -> Environment {
         self.core.get_global_env()
     }
+
+    /// 保存全局环境（以及闭包捕获的局部环境）的当前绑定，供之后 restore 恢复
+    pub fn snapshot(&self) -> EnvSnapshot {
+        self.core.snapshot()
+    }
+
+    /// 将环境恢复为快照时的绑定：快照之后的定义和赋值被撤销；同一快照可以多次恢复
+    pub fn restore(&self, snapshot: &EnvSnapshot) {
+        self.core.restore(snapshot);
+    }
+
+    /// 开启或关闭按过程名的调用统计（结果可由 profile-report 读取）
+    pub fn set_profiling(&self, enabled: bool) {
+        self.core.set_profiling(enabled);
+    }
+
+    /// 按过程名排序的调用统计：(过程名, 调用次数与累计耗时)
+    pub fn profile_report(&self) -> Vec<(String, profile::ProfileEntry)> {
+        self.core.profile_report()
+    }
+
+    /// 开启或关闭常量折叠：顶层表达式求值前，先将参数全为数字字面量的纯算术调用替换为其结果
+    pub fn set_optimize(&self, enabled: bool) {
+        self.core.set_optimize(enabled);
+    }
+
+    /// 求值器创建以来执行的求值步数（诊断用）
+    pub fn eval_steps(&self) -> u64 {
+        self.core.eval_steps()
+    }
+
+    /// 最近一次顶层求值期间 eval 达到的最大递归深度（诊断用）
+    pub fn last_eval_max_depth(&self) -> usize {
+        self.core.last_eval_max_depth()
+    }
 }
 
 impl Default for Evaluator {
@@ -72,6 +163,7 @@ impl Default for Evaluator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::legacy::types::{SchemeError, Arity};
 
     #[test]
     fn test_eval_atoms() {
@@ -79,7 +171,7 @@ mod tests {
         
         assert_eq!(evaluator.eval_string("42", None).unwrap(), Value::Integer(42));
         assert_eq!(evaluator.eval_string("3.14", None).unwrap(), Value::Float(3.14));
-        assert_eq!(evaluator.eval_string("\"hello\"", None).unwrap(), Value::String("hello".to_string()));
+        assert_eq!(evaluator.eval_string("\"hello\"", None).unwrap(), Value::string("hello"));
         assert_eq!(evaluator.eval_string("#t", None).unwrap(), Value::Bool(true));
     }
 
@@ -97,7 +189,7 @@ mod tests {
         let evaluator = Evaluator::new();
         
         let result = evaluator.eval_string("'foo", None).unwrap();
-        assert_eq!(result, Value::Symbol("foo".to_string()));
+        assert_eq!(result, Value::symbol("foo"));
         
         let result = evaluator.eval_string("'(1 2 3)", None).unwrap();
         let expected = Value::from_vec(vec![
@@ -122,12 +214,45 @@ mod tests {
         let evaluator = Evaluator::new();
         
         // 测试 define
-        assert_eq!(evaluator.eval_string("(define x 42)", None).unwrap(), Value::Nil);
+        assert_eq!(evaluator.eval_string("(define x 42)", None).unwrap(), Value::Unspecified);
         assert_eq!(evaluator.eval_string("x", None).unwrap(), Value::Integer(42));
         
         // 测试字符串变量
-        assert_eq!(evaluator.eval_string("(define name \"hello\")", None).unwrap(), Value::Nil);
-        assert_eq!(evaluator.eval_string("name", None).unwrap(), Value::String("hello".to_string()));
+        assert_eq!(evaluator.eval_string("(define name \"hello\")", None).unwrap(), Value::Unspecified);
+        assert_eq!(evaluator.eval_string("name", None).unwrap(), Value::string("hello"));
+    }
+
+    #[test]
+    fn test_definitions_yield_unspecified() {
+        let evaluator = Evaluator::new();
+
+        // 副作用形式的结果是未指定值，而不是空表
+        let defined = evaluator.eval_string("(define x 1)", None).unwrap();
+        assert_eq!(defined, Value::Unspecified);
+        assert_ne!(defined, Value::Nil);
+        assert_eq!(evaluator.eval_string("(define (f) x)", None).unwrap(), Value::Unspecified);
+        assert_eq!(evaluator.eval_string("(set! x 2)", None).unwrap(), Value::Unspecified);
+        assert_eq!(evaluator.eval_string("(define v (vector 1))", None).unwrap(), Value::Unspecified);
+        assert_eq!(evaluator.eval_string("(vector-set! v 0 2)", None).unwrap(), Value::Unspecified);
+
+        // 真正的空表仍是空表
+        assert_eq!(evaluator.eval_string("'()", None).unwrap(), Value::Nil);
+        assert_eq!(evaluator.eval_string("(type-of (set! x 3))", None).unwrap(), Value::symbol("unspecified"));
+    }
+
+    #[test]
+    fn test_define_and_get_global() {
+        let evaluator = Evaluator::new();
+
+        // 宿主注入配置值，脚本读取并基于它定义新绑定
+        evaluator.define_global("max-retries", Value::Integer(3));
+        evaluator.eval_string("(define retry-budget (* max-retries 2))", None).unwrap();
+        assert_eq!(evaluator.get_global("retry-budget"), Some(Value::Integer(6)));
+
+        // 脚本修改后宿主读到新值
+        evaluator.eval_string("(set! max-retries 5)", None).unwrap();
+        assert_eq!(evaluator.get_global("max-retries"), Some(Value::Integer(5)));
+        assert_eq!(evaluator.get_global("no-such-binding"), None);
     }
 
     #[test]
@@ -139,10 +264,30 @@ mod tests {
         assert_eq!(evaluator.eval_string("x", None).unwrap(), Value::Integer(10));
         
         // 使用 set! 修改变量
-        assert_eq!(evaluator.eval_string("(set! x 20)", None).unwrap(), Value::Nil);
+        assert_eq!(evaluator.eval_string("(set! x 20)", None).unwrap(), Value::Unspecified);
         assert_eq!(evaluator.eval_string("x", None).unwrap(), Value::Integer(20));
     }
 
+    #[test]
+    fn test_multi_expression_bodies() {
+        let evaluator = Evaluator::new();
+        evaluator.eval_string("(define log '())", None).unwrap();
+
+        // lambda 主体：先产生副作用，再返回最后一个表达式的值
+        evaluator.eval_string("(define f (lambda (x) (set! log (cons x log)) (* x 2)))", None).unwrap();
+        assert_eq!(evaluator.eval_string("(f 5)", None).unwrap(), Value::Integer(10));
+
+        // define 的函数形式与 let 同样接受多个主体表达式
+        evaluator.eval_string("(define (g x) (set! log (cons 'g log)) (+ x 1))", None).unwrap();
+        assert_eq!(evaluator.eval_string("(g 1)", None).unwrap(), Value::Integer(2));
+        assert_eq!(evaluator.eval_string("(let ((y 3)) (set! log (cons y log)) (- y))", None).unwrap(), Value::Integer(-3));
+        assert_eq!(evaluator.eval_string("log", None).unwrap().to_string(), "(3 g 5)");
+
+        assert!(evaluator.eval_string("(lambda (x))", None).is_err());
+        assert!(evaluator.eval_string("(let ((x 1)))", None).is_err());
+        assert!(evaluator.eval_string("(define x 1 2)", None).is_err());
+    }
+
     #[test]
     fn test_lambda_with_environment() {
         let evaluator = Evaluator::new();
@@ -196,6 +341,34 @@ mod tests {
         assert_eq!(evaluator.eval_string("(min 1.5 2 0.3)", None).unwrap(), Value::Float(0.3));
     }
 
+    #[test]
+    fn test_special_float_literals() {
+        let evaluator = Evaluator::new();
+        
+        // 除以非精确的 0.0 得到与字面量相同的无穷大
+        assert_eq!(evaluator.eval_string("(= (/ 1.0 0.0) +inf.0)", None).unwrap(), Value::Bool(true));
+        assert_eq!(evaluator.eval_string("(= (/ -1 0.0) -inf.0)", None).unwrap(), Value::Bool(true));
+        assert_eq!(evaluator.eval_string("(< -inf.0 +inf.0)", None).unwrap(), Value::Bool(true));
+        assert_eq!(evaluator.eval_string("(= +nan.0 +nan.0)", None).unwrap(), Value::Bool(false));
+        assert!(matches!(evaluator.eval_string("(/ 1 0)", None), Err(SchemeError::DivisionByZero(_))));
+        
+        assert_eq!(evaluator.eval_string("(/ 1.0 0.0)", None).unwrap().to_string(), "+inf.0");
+        assert_eq!(evaluator.eval_string("(- +inf.0)", None).unwrap().to_string(), "-inf.0");
+        assert_eq!(evaluator.eval_string("(list +nan.0)", None).unwrap().to_string(), "(+nan.0)");
+    }
+
+    #[test]
+    fn test_procedure_arity() {
+        let evaluator = Evaluator::new();
+        let arity = |expr: &str| evaluator.eval_string(&format!("(procedure-arity {expr})"), None).unwrap().to_string();
+        
+        assert_eq!(arity("car"), "(1 . 1)");
+        assert_eq!(arity("+"), "(0 . #f)");
+        assert_eq!(arity("(lambda (a b) a)"), "(2 . 2)");
+        assert_eq!(arity("(lambda () 1)"), "(0 . 0)");
+        assert!(matches!(evaluator.eval_string("(procedure-arity 5)", None), Err(SchemeError::TypeError(..))));
+    }
+
     #[test]
     fn test_logical_operators() {
         let evaluator = Evaluator::new();
@@ -217,6 +390,582 @@ mod tests {
         assert_eq!(evaluator.eval_string("(or #f 42)", None).unwrap(), Value::Integer(42));
     }
 
+    #[test]
+    fn test_cond_clause_shapes() {
+        let evaluator = Evaluator::new();
+        
+        // 只有测试的子句返回测试值
+        assert_eq!(evaluator.eval_string("(cond (#f) ((+ 1 2)) (else 0))", None).unwrap(), Value::Integer(3));
+        
+        // (test => proc) 以测试值调用过程
+        assert_eq!(
+            evaluator.eval_string("(cond ((assv 2 '((1 . a) (2 . b))) => cdr) (else 'none))", None).unwrap(),
+            Value::symbol("b")
+        );
+        assert_eq!(
+            evaluator.eval_string("(cond ((assv 3 '((1 . a))) => cdr) (else 'none))", None).unwrap(),
+            Value::symbol("none")
+        );
+        
+        // (else => proc) 以 #t 调用过程
+        assert_eq!(
+            evaluator.eval_string("(cond (#f 1) (else => (lambda (t) (if t 'yes 'no))))", None).unwrap(),
+            Value::symbol("yes")
+        );
+        
+        // 多表达式主体
+        assert_eq!(evaluator.eval_string("(cond (#t 1 2 3))", None).unwrap(), Value::Integer(3));
+        
+        // => 后必须恰好有一个过程
+        assert!(evaluator.eval_string("(cond (#t =>))", None).is_err());
+        assert!(evaluator.eval_string("(cond (1 => car cdr))", None).is_err());
+    }
+
+    #[test]
+    fn test_case() {
+        let evaluator = Evaluator::new();
+        
+        evaluator.eval_string(
+            "(define (classify n) (case n ((1 2 3) 'small) ((4 5 6) 'medium) (else 'large)))",
+            None,
+        ).unwrap();
+        assert_eq!(evaluator.eval_string("(classify 2)", None).unwrap(), Value::symbol("small"));
+        assert_eq!(evaluator.eval_string("(classify 5)", None).unwrap(), Value::symbol("medium"));
+        assert_eq!(evaluator.eval_string("(classify 9)", None).unwrap(), Value::symbol("large"));
+        
+        // 符号数据按 eqv? 匹配，没有匹配时返回空表
+        assert_eq!(evaluator.eval_string("(case 'b ((a) 1) ((b c) 2))", None).unwrap(), Value::Integer(2));
+        assert_eq!(evaluator.eval_string("(case 'z ((a) 1))", None).unwrap(), Value::Nil);
+        
+        // => 以键值调用过程
+        assert_eq!(
+            evaluator.eval_string("(case (* 2 3) ((2 3 5 7) 'prime) ((1 4 6 8 9) => (lambda (k) (* k 10))))", None).unwrap(),
+            Value::Integer(60)
+        );
+        assert_eq!(
+            evaluator.eval_string("(case 11 ((1 2) 'low) (else => (lambda (k) (- k 1))))", None).unwrap(),
+            Value::Integer(10)
+        );
+        
+        assert!(evaluator.eval_string("(case 1 (1 'one))", None).is_err());
+    }
+
+    #[test]
+    fn test_let_syntax_scope() {
+        let evaluator = Evaluator::new();
+        
+        // 局部宏在 body 中可用
+        assert_eq!(
+            evaluator.eval_string(
+                "(let-syntax ((swap-args (syntax-rules () ((_ f a b) (f b a))))) (swap-args - 1 10))",
+                None,
+            ).unwrap(),
+            Value::Integer(9)
+        );
+        // 离开 body 后不可见
+        assert!(evaluator.eval_string("(swap-args - 1 10)", None).is_err());
+        
+        // body 中定义的闭包在外部调用时仍使用已展开的宏
+        evaluator.eval_string(
+            "(define first-true (letrec-syntax ((my-or (syntax-rules () ((_) #f) ((_ e r ...) (if e e (my-or r ...)))))) (lambda (x y) (my-or x y))))",
+            None,
+        ).unwrap();
+        assert_eq!(evaluator.eval_string("(first-true #f 7)", None).unwrap(), Value::Integer(7));
+
+        // 绑定位置上与局部宏同名的变量不被展开，并在其作用域中遮蔽该宏
+        let local_m = |body: &str| evaluator.eval_string(&format!("(let-syntax ((m (syntax-rules () ((_ x) (quote x))))) {body})"), None);
+        assert_eq!(local_m("(let ((m 5)) m)").unwrap(), Value::Integer(5));
+        assert_eq!(local_m("(let ((m car)) (m '(1 2)))").unwrap(), Value::Integer(1));
+        assert_eq!(local_m("(let loop ((m 3)) (if (= m 0) 'done (loop (- m 1))))").unwrap(), Value::symbol("done"));
+        assert_eq!(local_m("((lambda (m) (m 4)) -)").unwrap(), Value::Integer(-4));
+        assert_eq!(local_m("(begin (define (f m) (m 2)) (f -))").unwrap(), Value::Integer(-2));
+        assert_eq!(local_m("(do ((m 0 (+ m 1))) ((= m 3) m))").unwrap(), Value::Integer(3));
+        // 绑定的初始值和其作用域之外仍然展开
+        assert_eq!(local_m("(let ((y (m a))) (list y (m b)))").unwrap().to_string(), "(a b)");
+        assert_eq!(local_m("(let ((m (m a))) m)").unwrap(), Value::symbol("a"));
+        
+        // 全局宏
+        evaluator.eval_string("(define-syntax unless* (syntax-rules () ((_ c body ...) (if c #f (begin body ...)))))", None).unwrap();
+        assert_eq!(evaluator.eval_string("(unless* #f 1 2)", None).unwrap(), Value::Integer(2));
+    }
+
+    #[test]
+    fn test_macro_diagnostics() {
+        let evaluator = Evaluator::new();
+        evaluator.eval_string("(define-syntax swap! (syntax-rules () ((_ a b) (let ((tmp a)) (set! a b) (set! b tmp)))))", None).unwrap();
+        
+        // 无匹配规则：错误指明宏名、出错的形式和顶层形式的位置
+        match evaluator.eval_string_located("\n  (swap! x)", None) {
+            Err(SchemeError::SyntaxError(message, Some(position))) => {
+                assert_eq!(message, "swap!: no syntax-rules pattern matches (swap! x)");
+                assert_eq!((position.line, position.column), (2, 3));
+            },
+            other => panic!("unexpected result: {other:?}"),
+        }
+        
+        // 模板中的 syntax-error
+        evaluator.eval_string(
+            "(define-syntax pair-only (syntax-rules () ((_ a b) (cons a b)) ((_ . rest) (syntax-error \"pair-only expects two forms, got\" rest))))",
+            None,
+        ).unwrap();
+        assert_eq!(evaluator.eval_string("(pair-only 1 2)", None).unwrap().to_string(), "(1 . 2)");
+        assert_eq!(
+            evaluator.eval_string("(pair-only 1 2 3)", None),
+            Err(SchemeError::SyntaxError("pair-only expects two forms, got (1 2 3)".to_string(), None))
+        );
+    }
+
+    #[test]
+    fn test_with_input_from_string() {
+        let evaluator = Evaluator::new();
+        
+        // thunk 内的 (read) 从字符串端口依次读取数据
+        assert_eq!(
+            evaluator.eval_string(
+                "(with-input-from-string \"(a \\\"b)\\\" ; c\n 42) 'x\" (lambda () (let ((first (read))) (let ((second (read))) (list first second (read) (eof-object? (read)))))))",
+                None,
+            ).unwrap(),
+            Value::from_vec(vec![
+                Value::from_vec(vec![Value::symbol("a"), Value::string("b)"), Value::Integer(42)]),
+                Value::from_vec(vec![Value::symbol("quote"), Value::symbol("x")]),
+                Value::Eof,
+                Value::Bool(true),
+            ])
+        );
+        
+        // 出错后当前输入端口恢复为原端口
+        let before = evaluator.eval_string("(current-input-port)", None).unwrap();
+        assert!(evaluator.eval_string("(with-input-from-string \"1\" (lambda () (car (read))))", None).is_err());
+        assert!(evaluator.eval_string("(current-input-port)", None).unwrap().is_eq(&before));
+    }
+
+    #[test]
+    fn test_write_circular_list() {
+        let evaluator = Evaluator::new();
+        
+        evaluator.eval_string("(define l (list 1 2 3))", None).unwrap();
+        evaluator.eval_string("(set-cdr! (cdr (cdr l)) l)", None).unwrap();
+        
+        // 循环列表的输出使用数据标签而不会无限循环
+        let circular = evaluator.eval_string("l", None).unwrap();
+        assert_eq!(circular.to_string(), "#0=(1 2 3 . #0#)");
+        assert_eq!(
+            evaluator.eval_string("(format #f \"~s\" l)", None).unwrap(),
+            Value::string("#0=(1 2 3 . #0#)")
+        );
+        assert_eq!(evaluator.eval_string("(write l)", None).unwrap(), Value::Unspecified);
+        assert_eq!(evaluator.eval_string("(write-shared l)", None).unwrap(), Value::Unspecified);
+        
+        // set-car! 对共享该序对的值可见
+        evaluator.eval_string("(define tail (cdr l))", None).unwrap();
+        evaluator.eval_string("(set-car! tail 'two)", None).unwrap();
+        assert_eq!(evaluator.eval_string("(car (cdr l))", None).unwrap(), Value::symbol("two"));
+    }
+
+    #[test]
+    fn test_circular_list_operations_terminate() {
+        let evaluator = Evaluator::new();
+        let eval = |input: &str| evaluator.eval_string(input, None);
+
+        eval("(define l (list 1 2 3))").unwrap();
+        eval("(set-cdr! (cdr (cdr l)) l)").unwrap();
+        eval("(define self-car (list 1))").unwrap();
+        eval("(set-car! self-car self-car)").unwrap();
+
+        // 循环列表不是正常列表；需要遍历整个列表的操作报错而不是无限循环
+        assert!(eval("(reverse l)").is_err());
+        assert_eq!(eval("(length l)"), Err(SchemeError::TypeError("length: circular list".to_string(), None)));
+        assert!(eval("(apply + l)").is_err());
+        assert!(eval("(list->vector l)").is_err());
+        assert_eq!(eval("(last-pair l)"), Err(SchemeError::TypeError("last-pair: circular list".to_string(), None)));
+        assert_eq!(eval("(member 5 l)"), Err(SchemeError::TypeError("member: circular list".to_string(), None)));
+        assert_eq!(eval("(member 5 l eq?)"), Err(SchemeError::TypeError("member: circular list".to_string(), None)));
+        eval("(define alist (list '(1 . a) '(2 . b)))").unwrap();
+        eval("(set-cdr! (cdr alist) alist)").unwrap();
+        assert!(eval("(assv 5 alist)").is_err());
+        assert!(eval("(append! l (list 4))").is_err());
+        // 元素在环中时 member 照常找到
+        assert_eq!(eval("(car (member 3 l))").unwrap(), Value::Integer(3));
+        assert_eq!(eval("(equal-hash l)"), Err(SchemeError::TypeError("equal-hash: circular list".to_string(), None)));
+        assert!(eval("(equal-hash self-car)").is_err());
+        eval("(define table (make-hash-table))").unwrap();
+        assert_eq!(
+            eval("(hash-table-set! table l 1)"),
+            Err(SchemeError::TypeError("hash-table-set!: circular list".to_string(), None))
+        );
+        assert!(eval("(hash-table-contains? table self-car)").is_err());
+
+        // 共享而不成环的结构照常可用
+        eval("(define shared (list 1 2))").unwrap();
+        assert_eq!(eval("(length (list shared shared))").unwrap(), Value::Integer(2));
+        eval("(hash-table-set! table (list shared shared) 'ok)").unwrap();
+        assert_eq!(eval("(hash-table-ref/default table '((1 2) (1 2)) #f)").unwrap(), Value::symbol("ok"));
+    }
+
+    #[test]
+    fn test_sorting() {
+        let evaluator = Evaluator::new();
+        
+        // vector-sort! 原地修改同一个向量对象
+        evaluator.eval_string("(define v (vector 3 1 4 1 5 9 2 6))", None).unwrap();
+        evaluator.eval_string("(define alias v)", None).unwrap();
+        assert_eq!(evaluator.eval_string("(vector-sort! v >)", None).unwrap(), Value::Unspecified);
+        assert_eq!(evaluator.eval_string("alias", None).unwrap().to_string(), "#(9 6 5 4 3 2 1 1)");
+        
+        // list-sort 返回新列表
+        evaluator.eval_string("(define l (list 3 1 2))", None).unwrap();
+        assert_eq!(evaluator.eval_string("(list-sort < l)", None).unwrap().to_string(), "(1 2 3)");
+        assert_eq!(evaluator.eval_string("l", None).unwrap().to_string(), "(3 1 2)");
+        
+        assert!(matches!(evaluator.eval_string("(vector-sort! (list 1 2) <)", None), Err(SchemeError::TypeError(..))));
+        assert!(matches!(evaluator.eval_string("(vector-sort! v 5)", None), Err(SchemeError::TypeError(..))));
+    }
+
+    #[test]
+    fn test_mutable_string() {
+        let evaluator = Evaluator::new();
+        
+        // 可变字符串被共享：通过别名修改后原变量可见
+        evaluator.eval_string("(define s (string-copy \"hello\"))", None).unwrap();
+        evaluator.eval_string("(define alias s)", None).unwrap();
+        evaluator.eval_string("(string-set! alias 0 (string-ref \"J\" 0))", None).unwrap();
+        assert_eq!(evaluator.eval_string("s", None).unwrap(), Value::string("Jello"));
+        assert_eq!(evaluator.eval_string("(equal? s \"Jello\")", None).unwrap(), Value::Bool(true));
+        assert_eq!(evaluator.eval_string("(string? (make-string 2))", None).unwrap(), Value::Bool(true));
+        
+        // 字符串字面量不可修改
+        assert!(matches!(evaluator.eval_string("(string-fill! \"abc\" (string-ref s 0))", None), Err(SchemeError::RuntimeError(..))));
+    }
+
+    #[test]
+    fn test_last_eval_max_depth() {
+        let evaluator = Evaluator::new();
+        
+        evaluator.eval_string("(define (sum n) (if (= n 0) 0 (+ n (sum (- n 1)))))", None).unwrap();
+        evaluator.eval_string("(define (sum-iter n acc) (if (= n 0) acc (sum-iter (- n 1) (+ acc n))))", None).unwrap();
+        
+        // 常量求值只有一层
+        evaluator.eval_string("42", None).unwrap();
+        assert_eq!(evaluator.last_eval_max_depth(), 1);
+        
+        // 非尾递归的深度随 n 线性增长
+        evaluator.eval_string("(sum 10)", None).unwrap();
+        let shallow = evaluator.last_eval_max_depth();
+        evaluator.eval_string("(sum 100)", None).unwrap();
+        let deep = evaluator.last_eval_max_depth();
+        assert!(deep >= shallow + 90, "shallow {shallow}, deep {deep}");
+        
+        // 每次顶层求值重新统计；尾递归的深度与迭代次数无关
+        assert_eq!(evaluator.eval_string("(sum-iter 10 0)", None).unwrap(), Value::Integer(55));
+        let tail_shallow = evaluator.last_eval_max_depth();
+        assert!(tail_shallow < deep);
+        assert_eq!(evaluator.eval_string("(sum-iter 1000 0)", None).unwrap(), Value::Integer(500500));
+        assert_eq!(evaluator.last_eval_max_depth(), tail_shallow);
+    }
+
+    #[test]
+    fn test_tail_calls_through_special_forms() {
+        let evaluator = Evaluator::new();
+        
+        // 尾递归经由 cond、when、unless、case、and、or、let、begin
+        evaluator.eval_string(
+            "(define (count-cond n acc)
+               (cond ((= n 0) acc)
+                     (else (let ((m (- n 1))) (begin (count-cond m (+ acc 1)))))))",
+            None
+        ).unwrap();
+        evaluator.eval_string(
+            "(define (count-when n acc)
+               (when (>= n 0)
+                 (if (= n 0) acc (count-when (- n 1) (+ acc 1)))))",
+            None
+        ).unwrap();
+        evaluator.eval_string(
+            "(define (count-unless n acc)
+               (unless (< n 0)
+                 (case n
+                   ((0) acc)
+                   (else (and #t (or #f (count-unless (- n 1) (+ acc 1))))))))",
+            None
+        ).unwrap();
+        
+        for (name, n) in [("count-cond", 100000), ("count-when", 100000), ("count-unless", 10000)] {
+            assert_eq!(evaluator.eval_string(&format!("({name} 10 0)"), None).unwrap(), Value::Integer(10));
+            let shallow = evaluator.last_eval_max_depth();
+            assert_eq!(evaluator.eval_string(&format!("({name} {n} 0)"), None).unwrap(), Value::Integer(n));
+            assert_eq!(evaluator.last_eval_max_depth(), shallow, "{name} depth grows");
+        }
+        
+        // 带位置信息的求值（REPL 的路径）同样不随迭代增长
+        assert_eq!(evaluator.eval_string_located("(count-cond 10000 0)", None).unwrap(), Value::Integer(10000));
+        
+        // when / unless 不执行主体时返回空表
+        assert_eq!(evaluator.eval_string("(when #f 1)", None).unwrap(), Value::Nil);
+        assert_eq!(evaluator.eval_string("(unless #t 1)", None).unwrap(), Value::Nil);
+        assert_eq!(evaluator.eval_string("(when 1 2 3)", None).unwrap(), Value::Integer(3));
+    }
+
+    #[test]
+    fn test_eq_on_shared_objects() {
+        let evaluator = Evaluator::new();
+        
+        assert_eq!(evaluator.eval_string("(eq? '() '())", None).unwrap(), Value::Bool(true));
+        assert_eq!(evaluator.eval_string("(eq? 'a 'a)", None).unwrap(), Value::Bool(true));
+        assert_eq!(evaluator.eval_string("(let ((p (cons 1 2))) (eq? p p))", None).unwrap(), Value::Bool(true));
+        assert_eq!(evaluator.eval_string("(eq? (cons 1 2) (cons 1 2))", None).unwrap(), Value::Bool(false));
+        assert_eq!(evaluator.eval_string("(let ((l (list 1 2))) (eq? (cdr l) (cdr l)))", None).unwrap(), Value::Bool(true));
+
+        // 字符串按身份比较：内容相同的两个字符串不 eq?，但 equal?
+        assert_eq!(evaluator.eval_string("(eq? \"abc\" \"abc\")", None).unwrap(), Value::Bool(false));
+        assert_eq!(evaluator.eval_string("(eqv? \"abc\" \"abc\")", None).unwrap(), Value::Bool(false));
+        assert_eq!(evaluator.eval_string("(let ((s \"abc\")) (eq? s s))", None).unwrap(), Value::Bool(true));
+        assert_eq!(evaluator.eval_string("(let ((s \"abc\")) (eq? s (car (list s))))", None).unwrap(), Value::Bool(true));
+        assert_eq!(evaluator.eval_string("(equal? \"abc\" \"abc\")", None).unwrap(), Value::Bool(true));
+        
+        // 同一个闭包 eq?，两次求值得到的闭包不 eq?
+        evaluator.eval_string("(define (make) (lambda () 1))", None).unwrap();
+        assert_eq!(evaluator.eval_string("(let ((f (make))) (eq? f f))", None).unwrap(), Value::Bool(true));
+        assert_eq!(evaluator.eval_string("(eq? (make) (make))", None).unwrap(), Value::Bool(false));
+    }
+
+    #[test]
+    fn test_duplicate_binding_names() {
+        let evaluator = Evaluator::new();
+        let duplicate = |name: &str| SchemeError::SyntaxError(format!("duplicate binding name: {name}"), None);
+        
+        assert_eq!(evaluator.eval_string("(let ((x 1) (x 2)) x)", None), Err(duplicate("x")));
+        assert_eq!(evaluator.eval_string("(lambda (a b a) a)", None), Err(duplicate("a")));
+        assert_eq!(evaluator.eval_string("(define (f y y) y)", None), Err(duplicate("y")));
+        assert!(evaluator.eval_string("f", None).is_err());
+        
+        // 重复名在其初始化表达式求值之前就被拒绝
+        evaluator.eval_string("(define counter 0)", None).unwrap();
+        assert!(evaluator.eval_string("(let ((x 1) (x (set! counter 1))) x)", None).is_err());
+        assert_eq!(evaluator.eval_string("counter", None).unwrap(), Value::Integer(0));
+        
+        // 错误带有顶层形式的位置
+        match evaluator.eval_string_located("(let ((x 1) (x 2)) x)", None) {
+            Err(SchemeError::SyntaxError(_, Some(position))) => assert_eq!((position.line, position.column), (1, 1)),
+            other => panic!("unexpected result: {other:?}"),
+        }
+        
+        // 不同的名字不受影响
+        assert_eq!(evaluator.eval_string("(let ((x 1) (y 2)) (+ x y))", None).unwrap(), Value::Integer(3));
+    }
+
+    #[test]
+    fn test_version_and_features() {
+        let evaluator = Evaluator::new();
+        
+        assert_eq!(evaluator.eval_string("*version*", None).unwrap(), Value::string(env!("CARGO_PKG_VERSION").to_string()));
+        assert_eq!(evaluator.eval_string("(pair? (features))", None).unwrap(), Value::Bool(true));
+        for feature in ["r7rs", "tail-calls", "full-unicode", std::env::consts::OS] {
+            let program = format!("(and (member '{feature} (features)) (cond-expand ({feature} #t) (else #f)))");
+            assert_eq!(evaluator.eval_string(&program, None).unwrap(), Value::Bool(true), "{feature}");
+        }
+        
+        // 被覆盖后可由 reset_user_bindings 恢复
+        evaluator.eval_string("(define *version* 0)", None).unwrap();
+        evaluator.reset_user_bindings();
+        assert_eq!(evaluator.eval_string("(string? *version*)", None).unwrap(), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_raise_and_guard() {
+        let evaluator = Evaluator::new();
+        
+        // 匹配的子句处理条件
+        assert_eq!(
+            evaluator.eval_string("(guard (e ((symbol? e) (list 'caught e))) (+ 1 (raise 'oops)))", None).unwrap(),
+            Value::from_vec(vec![Value::symbol("caught"), Value::symbol("oops")])
+        );
+        assert_eq!(
+            evaluator.eval_string("(guard (e ((string? e) e) (else 'other)) (raise 42))", None).unwrap(),
+            Value::symbol("other")
+        );
+        
+        // 没有子句匹配时向外层 guard 重新抛出
+        assert_eq!(
+            evaluator.eval_string("(guard (outer (#t (* outer 2))) (guard (inner ((string? inner) 'inner)) (raise 21)))", None).unwrap(),
+            Value::Integer(42)
+        );
+        // 也可由外层 with-exception-handler 处理
+        assert_eq!(
+            evaluator.eval_string(
+                "(with-exception-handler (lambda (c) (+ c 1)) (lambda () (guard (e ((string? e) e)) (raise-continuable 1))))",
+                None
+            ).unwrap(),
+            Value::Integer(2)
+        );
+        
+        // raise-continuable 以处理过程的返回值继续；raise 的处理过程返回则报错
+        assert_eq!(
+            evaluator.eval_string("(with-exception-handler (lambda (c) 10) (lambda () (+ 1 (raise-continuable 'c))))", None).unwrap(),
+            Value::Integer(11)
+        );
+        assert!(matches!(
+            evaluator.eval_string("(with-exception-handler (lambda (c) 10) (lambda () (raise 'c)))", None),
+            Err(SchemeError::RuntimeError(..) | SchemeError::RuntimeErrorWithCallStack { .. })
+        ));
+        
+        // 未处理的任意值条件传播到顶层
+        assert_eq!(
+            evaluator.eval_string("(raise \"boom\")", None),
+            Err(SchemeError::Raised {
+                payload: Value::string("boom"),
+                continuable: false,
+            })
+        );
+        // 内置错误同样被 guard 捕获，处理器栈保持平衡
+        assert_eq!(evaluator.eval_string("(guard (e (#t 'caught)) (car '()))", None).unwrap(), Value::symbol("caught"));
+        assert_eq!(evaluator.eval_string("(guard (e (#t e)) (raise 'again))", None).unwrap(), Value::symbol("again"));
+    }
+
+    #[test]
+    fn test_string_ref_returns_chars() {
+        let evaluator = Evaluator::new();
+        assert_eq!(evaluator.eval_string("(string-ref \"abc\" 1)", None).unwrap(), Value::Char('b'));
+        assert_eq!(evaluator.eval_string("(string-ref \"abc\" 1)", None).unwrap().to_string(), "#\\b");
+        assert_eq!(evaluator.eval_string("(eqv? (string-ref \"日本語\" 2) #\\語)", None).unwrap(), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_profile_counts_calls() {
+        let evaluator = Evaluator::new();
+        evaluator.eval_string("(define (fib n) (if (< n 2) n (+ (fib (- n 1)) (fib (- n 2)))))", None).unwrap();
+
+        // 默认不统计
+        evaluator.eval_string("(fib 5)", None).unwrap();
+        assert!(evaluator.profile_report().is_empty());
+
+        // (fib 10) 共调用 fib 177 次；尾调用同样计数
+        evaluator.set_profiling(true);
+        assert_eq!(evaluator.eval_string("(fib 10)", None).unwrap(), Value::Integer(55));
+        evaluator.eval_string("(define (loop n) (if (= n 0) 'done (loop (- n 1))))", None).unwrap();
+        evaluator.eval_string("(loop 1000)", None).unwrap();
+        evaluator.set_profiling(false);
+
+        let report = evaluator.profile_report();
+        let calls = |name: &str| report.iter().find(|(n, _)| n == name).map(|(_, entry)| entry.calls);
+        assert_eq!(calls("fib"), Some(177));
+        assert_eq!(calls("loop"), Some(1001));
+        assert_eq!(calls("+"), Some(88));
+
+        // Scheme 侧读取同样的结果
+        let fib_entry = evaluator.eval_string("(assq 'fib (profile-report))", None).unwrap();
+        assert_eq!(fib_entry.cdr().unwrap().car().unwrap(), Value::Integer(177));
+        assert!(matches!(fib_entry.cdr().unwrap().cdr().unwrap(), Value::Float(seconds) if seconds >= 0.0));
+    }
+
+    #[test]
+    fn test_cond_expand() {
+        let evaluator = Evaluator::new();
+
+        // 支持的特性选中对应子句，主体中的定义对外可见
+        assert_eq!(
+            evaluator.eval_string("(cond-expand (no-such-feature 'wrong) (arbores (define picked 'arbores) picked))", None).unwrap(),
+            Value::symbol("arbores")
+        );
+        assert_eq!(evaluator.eval_string("picked", None).unwrap(), Value::symbol("arbores"));
+
+        // 没有特性成立时使用 else 子句
+        assert_eq!(
+            evaluator.eval_string("(cond-expand (no-such-feature 1) ((library (scheme base)) 2) (else 3))", None).unwrap(),
+            Value::Integer(3)
+        );
+
+        // and/or/not 组合
+        assert_eq!(evaluator.eval_string("(cond-expand ((and arbores syntax-rules) 'both) (else 'neither))", None).unwrap(), Value::symbol("both"));
+        assert_eq!(evaluator.eval_string("(cond-expand ((and arbores no-such-feature) 'both) (else 'neither))", None).unwrap(), Value::symbol("neither"));
+        assert_eq!(evaluator.eval_string("(cond-expand ((or no-such-feature arbores) 'one) (else 'none))", None).unwrap(), Value::symbol("one"));
+        assert_eq!(evaluator.eval_string("(cond-expand ((not no-such-feature) 'absent))", None).unwrap(), Value::symbol("absent"));
+
+        assert_eq!(evaluator.eval_string("(cond-expand (no-such-feature 1))", None).unwrap(), Value::Unspecified);
+        assert!(evaluator.eval_string("(cond-expand ((not) 1))", None).is_err());
+    }
+
+    #[test]
+    fn test_builtin_errors_are_catchable() {
+        let evaluator = Evaluator::new();
+
+        // guard 捕获除零错误并读取其消息
+        assert_eq!(
+            evaluator.eval_string("(guard (e ((error-object? e) (error-object-message e))) (/ 1 0))", None).unwrap(),
+            Value::string("Division by zero")
+        );
+        assert_eq!(
+            evaluator.eval_string("(guard (e ((error? e) (error-object-irritants e))) (car 1))", None).unwrap(),
+            Value::Nil
+        );
+
+        // 带调用栈求值时，guard 与 with-exception-handler 得到的消息一致，不含错误类型前缀
+        let results = evaluator.eval_all(
+            "(define (f x) (car x))
+             (guard (e (#t (error-object-message e))) (car 1))
+             (guard (e (#t (error-object-message e))) (f 1))
+             (guard (m ((string? m) m)) (with-exception-handler (lambda (e) (raise (error-object-message e))) (lambda () (f 1))))"
+        ).unwrap();
+        for result in &results[1..] {
+            assert_eq!(result, &Value::string("car expects a pair, got 1"));
+        }
+
+        // with-exception-handler 的处理过程收到错误对象
+        assert_eq!(
+            evaluator.eval_string(
+                "(guard (c ((pair? c) c)) (with-exception-handler (lambda (e) (raise (list 'wrapped (error-object-message e)))) (lambda () (/ 5 0))))",
+                None
+            ).unwrap().to_string(),
+            "(wrapped \"Division by zero\")"
+        );
+
+        // error 产生带附加对象的错误对象
+        assert_eq!(
+            evaluator.eval_string("(guard (e (#t (list (error-object-message e) (error-object-irritants e)))) (error \"bad thing\" 1 'x))", None).unwrap().to_string(),
+            "(\"bad thing\" (1 x))"
+        );
+        assert_eq!(evaluator.eval_string("(error-object? 'x)", None).unwrap(), Value::Bool(false));
+
+        // 未被捕获时保留原始错误；未处理的 error 输出消息与附加对象
+        assert!(matches!(evaluator.eval_string("(guard (e ((string? e) e)) (/ 1 0))", None), Err(SchemeError::DivisionByZero(_))));
+        let uncaught = evaluator.eval_string("(error \"bad thing\" 1 \"two\")", None).unwrap_err();
+        assert_eq!(uncaught.to_string(), "Error: bad thing 1 \"two\"");
+
+        // exit 不被 guard 捕获
+        assert_eq!(evaluator.eval_string("(guard (e (#t 'caught)) (exit 3))", None), Err(SchemeError::Exit(3)));
+    }
+    
+    #[test]
+    fn test_reset_user_bindings() {
+        let evaluator = Evaluator::new();
+        
+        evaluator.eval_string("(define x 10)", None).unwrap();
+        evaluator.eval_string("(define (+ a b) 'shadowed)", None).unwrap();
+        evaluator.eval_string("(define my-car car)", None).unwrap();
+        evaluator.eval_string("(set! car cdr)", None).unwrap();
+        // 宿主注册的原生函数，以及未经 register_native 注入的绑定
+        evaluator.register_native("native-answer", Value::BuiltinFunction {
+            name: "native-answer".to_string(),
+            func: |_| Ok(Value::Integer(42)),
+            arity: Arity::Exact(0),
+        });
+        evaluator.define_global("config", Value::Integer(1));
+        evaluator.eval_string("(define (native-answer) 0)", None).unwrap();
+        // 用户定义的宏，包括与内置过程同名的宏
+        evaluator.eval_string("(define-syntax twice (syntax-rules () ((_ e) (begin e e))))", None).unwrap();
+        evaluator.eval_string("(define-syntax - (syntax-rules () ((_ a b) 'macro)))", None).unwrap();
+        
+        evaluator.reset_user_bindings();
+        
+        assert!(evaluator.eval_string("x", None).is_err());
+        assert!(evaluator.eval_string("my-car", None).is_err());
+        assert!(evaluator.eval_string("config", None).is_err());
+        assert_eq!(evaluator.eval_string("(+ 1 2)", None).unwrap(), Value::Integer(3));
+        assert_eq!(evaluator.eval_string("(car '(1 2))", None).unwrap(), Value::Integer(1));
+        assert_eq!(evaluator.eval_string("(native-answer)", None).unwrap(), Value::Integer(42));
+        assert!(evaluator.eval_string("(twice 1)", None).is_err());
+        assert_eq!(evaluator.eval_string("(- 3 1)", None).unwrap(), Value::Integer(2));
+    }
+
     #[test]
     fn test_cond() {
         let evaluator = Evaluator::new();
@@ -243,4 +992,896 @@ mod tests {
             Value::Nil
         );
     }
+
+    #[test]
+    fn test_assert_passes() {
+        let evaluator = Evaluator::new();
+        
+        assert_eq!(evaluator.eval_string("(assert (= 1 1))", None).unwrap(), Value::Nil);
+        assert_eq!(evaluator.eval_string("(assert 0 \"zero is truthy\")", None).unwrap(), Value::Nil);
+    }
+
+    #[test]
+    fn test_assert_fails() {
+        use crate::legacy::types::{SchemeError, Position};
+
+        let evaluator = Evaluator::new();
+        
+        // 失败信息包含断言表达式的源码
+        let err = evaluator.eval_string("(assert (= 1 2))", None).unwrap_err();
+        assert_eq!(err, SchemeError::AssertionError("Assertion failed: (= 1 2)".to_string(), None));
+        
+        // 带自定义信息
+        let err = evaluator.eval_string("(assert (> 1 2) \"one is not greater\")", None).unwrap_err();
+        assert_eq!(err, SchemeError::AssertionError(
+            "Assertion failed: (> 1 2) - one is not greater".to_string(), None
+        ));
+        
+        // 带位置信息的求值会指向断言所在位置
+        let err = evaluator.eval_string_located("\n  (assert #f)", None).unwrap_err();
+        assert_eq!(err, SchemeError::AssertionError(
+            "Assertion failed: #f".to_string(), Some(Position::new(2, 3))
+        ));
+    }
+
+    #[test]
+    fn test_map_for_each_and_apply() {
+        let evaluator = Evaluator::new();
+        let eval = |input: &str| evaluator.eval_string(input, None).unwrap().to_string();
+
+        // 单个、两个与三个列表
+        assert_eq!(eval("(map (lambda (x) (* x x)) '(1 2 3))"), "(1 4 9)");
+        assert_eq!(eval("(map + '(1 2 3) '(10 20 30))"), "(11 22 33)");
+        assert_eq!(eval("(map list '(1 2) '(a b) '(\"x\" \"y\"))"), "((1 a \"x\") (2 b \"y\"))");
+
+        // 长度不同时在最短的列表处结束
+        assert_eq!(eval("(map + '(1 2 3) '(10 20))"), "(11 22)");
+        assert_eq!(eval("(map + '(1 2 3) '())"), "()");
+
+        // for-each 按顺序调用
+        evaluator.eval_string("(define seen '())", None).unwrap();
+        evaluator.eval_string("(for-each (lambda (a b) (set! seen (cons (- a b) seen))) '(10 20 30) '(1 2))", None).unwrap();
+        assert_eq!(eval("seen"), "(18 9)");
+
+        // apply 将前面的参数与最后的列表拼接
+        assert_eq!(eval("(apply + '(1 2 3))"), "6");
+        assert_eq!(eval("(apply list 1 2 '(3 4))"), "(1 2 3 4)");
+
+        assert!(matches!(evaluator.eval_string("(map + '(1 2) 5)", None), Err(SchemeError::TypeError(..))));
+        assert!(matches!(evaluator.eval_string("(apply + 1 2)", None), Err(SchemeError::TypeError(..))));
+    }
+
+    #[test]
+    fn test_find_list_index_any_every() {
+        let evaluator = Evaluator::new();
+        let eval = |input: &str| evaluator.eval_string(input, None).unwrap().to_string();
+
+        // 找到与找不到
+        assert_eq!(eval("(find even? '(1 3 4 6))"), "4");
+        assert_eq!(eval("(find even? '(1 3 5))"), "#f");
+        assert_eq!(eval("(list-index even? '(1 3 4 6))"), "2");
+        assert_eq!(eval("(list-index even? '())"), "#f");
+
+        // any 返回第一个为真的谓词结果，every 返回最后一个
+        assert_eq!(eval("(any (lambda (x) (and (> x 1) (* x 10))) '(1 2 3))"), "20");
+        assert_eq!(eval("(any even? '(1 3))"), "#f");
+        assert_eq!(eval("(every (lambda (x) (and (> x 0) (* x 10))) '(1 2 3))"), "30");
+        assert_eq!(eval("(every even? '(2 3 4))"), "#f");
+        assert_eq!(eval("(every even? '())"), "#t");
+
+        // 短路：结果确定后不再调用谓词
+        evaluator.eval_string("(define calls 0)", None).unwrap();
+        evaluator.eval_string("(define (probe x) (set! calls (+ calls 1)) (= x 2))", None).unwrap();
+        assert_eq!(eval("(any probe '(1 2 3 4))"), "#t");
+        assert_eq!(eval("calls"), "2");
+        evaluator.eval_string("(set! calls 0)", None).unwrap();
+        assert_eq!(eval("(every probe '(1 2 3 4))"), "#f");
+        assert_eq!(eval("calls"), "1");
+
+        assert!(evaluator.eval_string("(find even? 5)", None).is_err());
+    }
+
+    #[test]
+    fn test_remove_and_delete() {
+        let evaluator = Evaluator::new();
+        
+        // remove 保留谓词为假的元素
+        assert_eq!(
+            evaluator.eval_string("(remove (lambda (x) (> x 2)) '(1 2 3 4))", None).unwrap(),
+            Value::from_vec(vec![Value::Integer(1), Value::Integer(2)])
+        );
+        assert_eq!(evaluator.eval_string("(remove number? '())", None).unwrap(), Value::Nil);
+        assert_eq!(evaluator.eval_string("(remove number? '(1 2 3))", None).unwrap(), Value::Nil);
+        
+        // delete 使用 equal? 比较
+        assert_eq!(
+            evaluator.eval_string("(delete 2 '(1 2 3 2))", None).unwrap(),
+            Value::from_vec(vec![Value::Integer(1), Value::Integer(3)])
+        );
+        assert_eq!(
+            evaluator.eval_string("(delete '(1 2) '((1 2) 3 (1 2)))", None).unwrap(),
+            Value::from_vec(vec![Value::Integer(3)])
+        );
+    }
+
+    #[test]
+    fn test_input_port_peek_and_read() {
+        let evaluator = Evaluator::new();
+        
+        evaluator.eval_string("(define p (open-input-string \"ok\"))", None).unwrap();
+        assert_eq!(evaluator.eval_string("(peek-char p)", None).unwrap(), Value::Char('o'));
+        assert_eq!(evaluator.eval_string("(read-char p)", None).unwrap(), Value::Char('o'));
+        assert_eq!(evaluator.eval_string("(read-char p)", None).unwrap(), Value::Char('k'));
+        assert_eq!(evaluator.eval_string("(eof-object? (read-char p))", None).unwrap(), Value::Bool(true));
+    }
+
+    #[test]
+    fn test_string_port_round_trip() {
+        let evaluator = Evaluator::new();
+        let eval = |input: &str| evaluator.eval_string(input, None).unwrap();
+
+        // 向输出字符串端口写入若干数据
+        eval("(define out (open-output-string))");
+        eval("(begin (write '(1 \"two\" #\\3) out) (write-char #\\space out) (display 'sym out) (newline out) (write-string \"rest of line\" out) (newline out) (write 4.5 out))");
+        assert_eq!(eval("(get-output-string out)"), Value::string("(1 \"two\" #\\3) sym\nrest of line\n4.5"));
+        assert_eq!(eval("(close-port out)"), Value::Unspecified);
+
+        // 再从由结果构造的输入字符串端口读回
+        eval("(define in (open-input-string (get-output-string out)))");
+        assert_eq!(eval("(read in)").to_string(), "(1 \"two\" #\\3)");
+        assert_eq!(eval("(peek-char in)"), Value::Char(' '));
+        assert_eq!(eval("(read in)"), Value::symbol("sym"));
+        assert_eq!(eval("(read-char in)"), Value::Char('\n'));
+        assert_eq!(eval("(read-line in)"), Value::string("rest of line"));
+        assert_eq!(eval("(read in)"), Value::Float(4.5));
+        assert_eq!(eval("(eof-object? (read-line in))"), Value::Bool(true));
+        assert_eq!(eval("(close-port in)"), Value::Unspecified);
+
+        // 端口方向不对时报错
+        assert!(evaluator.eval_string("(display 1 in)", None).is_err());
+        assert!(evaluator.eval_string("(read-char out)", None).is_err());
+        assert!(evaluator.eval_string("(get-output-string in)", None).is_err());
+    }
+
+    #[test]
+    fn test_define_record_type() {
+        let evaluator = Evaluator::new();
+        
+        evaluator.eval_string(
+            "(define-record-type point (make-point x y) point? (x point-x set-point-x!) (y point-y set-point-y!))",
+            None,
+        ).unwrap();
+        evaluator.eval_string("(define p (make-point 1 2))", None).unwrap();
+        
+        assert_eq!(evaluator.eval_string("(point? p)", None).unwrap(), Value::Bool(true));
+        assert_eq!(evaluator.eval_string("(point? 42)", None).unwrap(), Value::Bool(false));
+        assert_eq!(evaluator.eval_string("(point-x p)", None).unwrap(), Value::Integer(1));
+        assert_eq!(evaluator.eval_string("(point-y p)", None).unwrap(), Value::Integer(2));
+        
+        // 修改字段
+        evaluator.eval_string("(set-point-x! p 10)", None).unwrap();
+        assert_eq!(evaluator.eval_string("(point-x p)", None).unwrap(), Value::Integer(10));
+        assert_eq!(evaluator.eval_string("p", None).unwrap().to_string(), "#<point x: 10 y: 2>");
+        
+        // 类型错误和参数个数错误
+        assert!(evaluator.eval_string("(point-x 42)", None).is_err());
+        assert!(evaluator.eval_string("(make-point 1)", None).is_err());
+
+        // 同名的两个记录类型互不相同
+        evaluator.eval_string("(define-record-type <a> (mk-a x) a? (x a-x))", None).unwrap();
+        evaluator.eval_string("(define-record-type <a> (mk-a2 x) a2? (x a2-x))", None).unwrap();
+        assert_eq!(evaluator.eval_string("(a2? (mk-a 1))", None).unwrap(), Value::Bool(false));
+        assert_eq!(evaluator.eval_string("(a? (mk-a 1))", None).unwrap(), Value::Bool(true));
+        assert!(evaluator.eval_string("(a2-x (mk-a 1))", None).is_err());
+        // 用户定义的同名类型不是错误对象
+        evaluator.eval_string("(define-record-type error-object (fake-error m i) fake-error? (m fake-m) (i fake-i))", None).unwrap();
+        assert_eq!(evaluator.eval_string("(error-object? (fake-error \"m\" '()))", None).unwrap(), Value::Bool(false));
+        assert_eq!(evaluator.eval_string("(fake-error? (guard (e (#t e)) (error \"boom\")))", None).unwrap(), Value::Bool(false));
+    }
+
+    #[test]
+    fn test_integer_overflow_is_an_error() {
+        use crate::legacy::types::SchemeError;
+
+        let evaluator = Evaluator::new();
+        
+        let err = evaluator.eval_string("(* 9223372036854775807 2)", None).unwrap_err();
+        assert!(matches!(err, SchemeError::RuntimeError(ref msg, _) if msg == "integer overflow"));
+        
+        // 朴素阶乘在溢出前正常工作
+        evaluator.eval_string("(define fact (lambda (n) (if (= n 0) 1 (* n (fact (- n 1))))))", None).unwrap();
+        assert_eq!(evaluator.eval_string("(fact 20)", None).unwrap(), Value::Integer(2432902008176640000));
+        assert!(evaluator.eval_string("(fact 21)", None).is_err());
+    }
+
+    #[test]
+    fn test_eval_all() {
+        let evaluator = Evaluator::new();
+        
+        let results = evaluator.eval_all("(define x 2)\n(define (double n) (* n 2))\n(double x)").unwrap();
+        assert_eq!(results, vec![Value::Unspecified, Value::Unspecified, Value::Integer(4)]);
+        assert_eq!(evaluator.eval_all("").unwrap(), vec![]);
+        
+        // 返回第一个错误及其位置
+        let err = evaluator.eval_all("(define y 1)\n(+ y undefined-var)\n(car 1)").unwrap_err();
+        assert!(err.to_string().contains("at line 2, column 1"));
+        assert!(err.to_string().contains("undefined-var"));
+    }
+
+    #[test]
+    fn test_parameterize() {
+        let evaluator = Evaluator::new();
+        
+        evaluator.eval_string("(define p (make-parameter 10))", None).unwrap();
+        assert_eq!(evaluator.eval_string("(p)", None).unwrap(), Value::Integer(10));
+        assert_eq!(evaluator.eval_string("(parameterize ((p 20)) (p))", None).unwrap(), Value::Integer(20));
+        assert_eq!(evaluator.eval_string("(p)", None).unwrap(), Value::Integer(10));
+        
+        // 嵌套与被调用函数可见
+        evaluator.eval_string("(define (get-p) (p))", None).unwrap();
+        assert_eq!(
+            evaluator.eval_string("(parameterize ((p 1)) (+ (parameterize ((p 2)) (get-p)) (get-p)))", None).unwrap(),
+            Value::Integer(3)
+        );
+        
+        // 出错后恢复原值
+        assert!(evaluator.eval_string("(parameterize ((p 30)) (car 1))", None).is_err());
+        assert_eq!(evaluator.eval_string("(p)", None).unwrap(), Value::Integer(10));
+        
+        // 转换过程
+        evaluator.eval_string("(define q (make-parameter 1 (lambda (x) (* x 10))))", None).unwrap();
+        assert_eq!(evaluator.eval_string("(q)", None).unwrap(), Value::Integer(10));
+        assert_eq!(evaluator.eval_string("(parameterize ((q 2)) (q))", None).unwrap(), Value::Integer(20));
+    }
+
+    #[test]
+    fn test_association_lists() {
+        let evaluator = Evaluator::new();
+        
+        evaluator.eval_string("(define alist '((1 . one) (2.5 . two-and-half) (b . bee)))", None).unwrap();
+        
+        // 浮点数键：eqv? 按值比较，eq? 不保证
+        assert_eq!(evaluator.eval_string("(assv 2.5 alist)", None).unwrap().to_string(), "(2.5 . two-and-half)");
+        assert_eq!(evaluator.eval_string("(assq 2.5 alist)", None).unwrap(), Value::Bool(false));
+        assert_eq!(evaluator.eval_string("(assv 1 alist)", None).unwrap().to_string(), "(1 . one)");
+        assert_eq!(evaluator.eval_string("(assq 'b alist)", None).unwrap().to_string(), "(b . bee)");
+        assert_eq!(evaluator.eval_string("(assv 3 alist)", None).unwrap(), Value::Bool(false));
+        assert_eq!(evaluator.eval_string("(assoc '(x) '(((x) . 1)))", None).unwrap().to_string(), "((x) . 1)");
+        
+        // 纯函数式更新，原列表不变
+        assert_eq!(
+            evaluator.eval_string("(alist-update 'b 'buzz alist)", None).unwrap().to_string(),
+            "((1 . one) (2.5 . two-and-half) (b . buzz))"
+        );
+        assert_eq!(
+            evaluator.eval_string("(alist-update 'c 'sea alist)", None).unwrap().to_string(),
+            "((c . sea) (1 . one) (2.5 . two-and-half) (b . bee))"
+        );
+        assert_eq!(
+            evaluator.eval_string("(del-assq 'b alist)", None).unwrap().to_string(),
+            "((1 . one) (2.5 . two-and-half))"
+        );
+        assert_eq!(
+            evaluator.eval_string("alist", None).unwrap().to_string(),
+            "((1 . one) (2.5 . two-and-half) (b . bee))"
+        );
+    }
+
+    #[test]
+    fn test_member_and_assoc_with_comparator() {
+        let evaluator = Evaluator::new();
+        
+        // 默认使用 equal?
+        assert_eq!(evaluator.eval_string("(member '(b) '(a (b) c))", None).unwrap().to_string(), "((b) c)");
+        assert_eq!(evaluator.eval_string("(member 2.0 '(1 2 3))", None).unwrap(), Value::Bool(false));
+        assert_eq!(evaluator.eval_string("(assoc 2.0 '((1 . one) (2 . two)))", None).unwrap(), Value::Bool(false));
+        assert_eq!(evaluator.eval_string("(member \"b\" (list \"a\" (make-string 1 #\\b)))", None).unwrap().to_string(), "(\"b\")");
+        // 闭包与自身 equal?
+        evaluator.eval_string("(define f (lambda () 1))", None).unwrap();
+        assert!(evaluator.eval_string("(member f (list f))", None).unwrap().is_truthy());
+        assert!(evaluator.eval_string("(assoc f (list (cons f 1)))", None).unwrap().is_truthy());
+        assert_eq!(evaluator.eval_string("(member f (list (lambda () 1)))", None).unwrap(), Value::Bool(false));
+        assert_eq!(evaluator.eval_string("(delete f (list 1 f 2))", None).unwrap().to_string(), "(1 2)");
+        
+        // 提供比较过程时改用它
+        assert_eq!(evaluator.eval_string("(member 2.0 '(1 2 3) =)", None).unwrap().to_string(), "(2 3)");
+        assert_eq!(evaluator.eval_string("(assoc 2.0 '((1 . one) (2 . two)) =)", None).unwrap().to_string(), "(2 . two)");
+        assert_eq!(
+            evaluator.eval_string("(member 5 '(1 7 3) (lambda (x y) (< x y)))", None).unwrap().to_string(),
+            "(7 3)"
+        );
+        
+        assert!(evaluator.eval_string("(member 1 '(1) = 'extra)", None).is_err());
+        assert!(evaluator.eval_string("(member 1 5)", None).is_err());
+    }
+
+    #[test]
+    fn test_length_and_last_pair() {
+        use crate::legacy::types::SchemeError;
+
+        let evaluator = Evaluator::new();
+        
+        assert_eq!(evaluator.eval_string("(length '())", None).unwrap(), Value::Integer(0));
+        assert_eq!(evaluator.eval_string("(length '(1 2 3))", None).unwrap(), Value::Integer(3));
+        let err = evaluator.eval_string("(length '(1 2 . 3))", None).unwrap_err();
+        assert!(matches!(err, SchemeError::TypeError(..)));
+        
+        assert_eq!(evaluator.eval_string("(last-pair '(1 2 3))", None).unwrap().to_string(), "(3)");
+        assert_eq!(evaluator.eval_string("(last-pair '(1 2 . 3))", None).unwrap().to_string(), "(2 . 3)");
+        assert!(evaluator.eval_string("(last-pair '())", None).is_err());
+    }
+
+    #[test]
+    fn test_pp_shows_lambda_source() {
+        use crate::legacy::builtins::port::pretty_print;
+
+        let evaluator = Evaluator::new();
+        
+        let square = evaluator.eval_string("(lambda (x y) (+ (* x x) y))", None).unwrap();
+        let text = pretty_print(&square);
+        assert_eq!(text, "(lambda (x y) (+ (* x x) y))");
+        
+        evaluator.eval_string("(define (thunk) 42)", None).unwrap();
+        assert_eq!(pretty_print(&evaluator.eval_string("thunk", None).unwrap()), "(lambda () 42)");
+        
+        // 内置函数显示名称和参数个数
+        assert_eq!(pretty_print(&evaluator.eval_string("car", None).unwrap()), "#<builtin:car arity=1>");
+        assert_eq!(pretty_print(&evaluator.eval_string("+", None).unwrap()), "#<builtin:+ arity=variadic>");
+        
+        assert_eq!(evaluator.eval_string("(pp car)", None).unwrap(), Value::Unspecified);
+    }
+
+    #[test]
+    fn test_load_file_and_io_errors() {
+        let evaluator = Evaluator::new();
+
+        // 文件不存在时返回提到路径的 IoError
+        let path = std::env::temp_dir().join("arbores-missing-file.scm");
+        let error = evaluator.eval_string(&format!("(load {:?})", path.display().to_string()), None).unwrap_err();
+        match &error {
+            SchemeError::IoError(message) => assert!(message.contains(&path.display().to_string()), "unexpected message: {message}"),
+            other => panic!("expected IoError, got {other:?}"),
+        }
+        assert!(error.to_string().starts_with("IO Error: "));
+
+        // 成功加载时在全局环境中求值所有表达式
+        let path = std::env::temp_dir().join(format!("arbores-load-{}.scm", std::process::id()));
+        std::fs::write(&path, "(define loaded-a 1)\n(define (loaded-f x) (+ x loaded-a))").unwrap();
+        let result = evaluator.eval_string(&format!("(load {:?})", path.display().to_string()), None);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(result.unwrap(), Value::Unspecified);
+        assert_eq!(evaluator.eval_string("(loaded-f 41)", None).unwrap(), Value::Integer(42));
+    }
+
+    #[test]
+    fn test_call_with_output_file_closes_on_error() {
+        let evaluator = Evaluator::new();
+        let path = std::env::temp_dir().join(format!("arbores-port-{}.txt", std::process::id()));
+        let path = path.display().to_string();
+
+        // proc 出错时端口仍被关闭，已写入的内容落盘
+        evaluator.eval_string("(define saved #f)", None).unwrap();
+        let result = evaluator.eval_string(&format!(
+            "(call-with-output-file {path:?} (lambda (port) (set! saved port) (display \"partial\" port) (car '())))"
+        ), None);
+        let written = std::fs::read_to_string(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+        assert_eq!(written.unwrap(), "partial");
+
+        // 关闭后的端口不能再写入
+        assert_eq!(evaluator.eval_string("saved", None).unwrap().to_string(), "#<closed-port>");
+        assert!(evaluator.eval_string("(display 1 saved)", None).is_err());
+        assert!(matches!(evaluator.eval_string("(open-input-file \"/nonexistent/arbores\")", None), Err(SchemeError::IoError(_))));
+    }
+
+    #[test]
+    fn test_call_with_input_file_closes_port() {
+        let evaluator = Evaluator::new();
+        let path = std::env::temp_dir().join(format!("arbores-input-port-{}.txt", std::process::id()));
+        std::fs::write(&path, "(1 2) (3 4)").unwrap();
+        let path = path.display().to_string();
+
+        // 正常返回和出错后，保存下来的输入端口都已关闭，不能再读取
+        evaluator.eval_string("(define saved #f)", None).unwrap();
+        let result = evaluator.eval_string(&format!(
+            "(call-with-input-file {path:?} (lambda (port) (set! saved port) (read port)))"
+        ), None);
+        assert_eq!(result.unwrap().to_string(), "(1 2)");
+        for read in ["(read-char saved)", "(peek-char saved)", "(read saved)", "(read-line saved)"] {
+            let err = evaluator.eval_string(read, None).unwrap_err();
+            assert!(err.to_string().contains("port is closed"), "{read}: {err}");
+        }
+
+        let result = evaluator.eval_string(&format!(
+            "(call-with-input-file {path:?} (lambda (port) (set! saved port) (car '())))"
+        ), None);
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+        assert!(evaluator.eval_string("(read-char saved)", None).is_err());
+        assert_eq!(evaluator.eval_string("saved", None).unwrap().to_string(), "#<closed-port>");
+
+        // 字符串输入端口同样可以关闭
+        evaluator.eval_string("(define in (open-input-string \"abc\"))", None).unwrap();
+        evaluator.eval_string("(close-port in)", None).unwrap();
+        assert!(evaluator.eval_string("(read-char in)", None).is_err());
+    }
+
+    #[test]
+    fn test_make_object_counter() {
+        let evaluator = Evaluator::new();
+        let eval = |input: &str| evaluator.eval_string(input, None);
+
+        // 方法闭包共享 let 绑定的状态
+        eval("(define (make-counter) (let ((n 0)) (make-object ((inc () (set! n (+ n 1)) n) (add (k) (set! n (+ n k)) n) (get () n)))))").unwrap();
+        eval("(define c (make-counter))").unwrap();
+        assert_eq!(eval("(c 'inc)").unwrap(), Value::Integer(1));
+        assert_eq!(eval("(c 'inc)").unwrap(), Value::Integer(2));
+        assert_eq!(eval("(c 'add 10)").unwrap(), Value::Integer(12));
+        assert_eq!(eval("(c 'get)").unwrap(), Value::Integer(12));
+        assert_eq!(eval("(let ((d (make-counter))) (d 'inc) (d 'get))").unwrap(), Value::Integer(1));
+        assert_eq!(eval("(type-of c)").unwrap(), Value::symbol("procedure"));
+        assert_eq!(eval("(map (lambda (m) (c m)) '(get inc))").unwrap().to_string(), "(12 13)");
+
+        // 未知方法与参数个数错误
+        assert!(matches!(eval("(c 'reset)"), Err(SchemeError::RuntimeError(msg, _)) if msg.contains("unknown method reset")));
+        assert!(matches!(eval("(c 'add)"), Err(SchemeError::ArityError(..))));
+        assert!(eval("(make-object ((get () 1) (get () 2)))").is_err());
+    }
+
+    #[test]
+    fn test_set_in_closures_mutates_captured_binding() {
+        let evaluator = Evaluator::new();
+        let eval = |input: &str| evaluator.eval_string(input, None).unwrap();
+
+        // 计数器的 n 在多次调用间共享，不同计数器互不影响
+        eval("(define (make-counter) (let ((n 0)) (lambda () (set! n (+ n 1)) n)))");
+        eval("(define c (make-counter))");
+        eval("(define d (make-counter))");
+        assert_eq!(eval("(c)"), Value::Integer(1));
+        assert_eq!(eval("(c)"), Value::Integer(2));
+        assert_eq!(eval("(d)"), Value::Integer(1));
+        assert_eq!(eval("(c)"), Value::Integer(3));
+
+        // 捕获同一环境的两个闭包看到彼此的修改
+        eval("(define n 100)");
+        eval("(define account (let ((n 0)) (cons (lambda (k) (set! n (+ n k))) (lambda () n))))");
+        eval("((car account) 5)");
+        eval("((car account) 7)");
+        assert_eq!(eval("((cdr account))"), Value::Integer(12));
+        // 修改的是最近的绑定，遮蔽的全局变量不受影响
+        assert_eq!(eval("n"), Value::Integer(100));
+
+        // 修改参数只影响本次调用
+        eval("(define (bump x) (set! x (+ x 1)) x)");
+        assert_eq!(eval("(bump n)"), Value::Integer(101));
+        assert_eq!(eval("n"), Value::Integer(100));
+
+        assert!(matches!(
+            evaluator.eval_string("((lambda () (set! undefined-name 1)))", None),
+            Err(SchemeError::UndefinedVariable(..))
+        ));
+    }
+
+    #[test]
+    fn test_do_and_named_let() {
+        let evaluator = Evaluator::new();
+        let eval = |input: &str| evaluator.eval_string(input, None).unwrap().to_string();
+
+        // step 在本轮的环境中一起求值，然后同时更新：a 与 b 交换
+        assert_eq!(eval("(do ((a 1 b) (b 2 a) (i 0 (+ i 1))) ((= i 3) (list a b)))"), "(2 1)");
+        assert_eq!(eval("(do ((a 1 (+ a b)) (b 1 a)) ((> a 10) (list a b)))"), "(13 8)");
+
+        // 没有 step 的变量保持当前值，命令可以用 set! 修改它
+        assert_eq!(eval("(do ((v (make-vector 3)) (i 0 (+ i 1))) ((= i 3) v) (vector-set! v i (* i i)))"), "#(0 1 4)");
+        assert_eq!(eval("(do ((acc '()) (i 0 (+ i 1))) ((= i 3) acc) (set! acc (cons i acc)))"), "(2 1 0)");
+        assert_eq!(evaluator.eval_string("(do ((i 0 (+ i 1))) ((= i 2)))", None).unwrap(), Value::Unspecified);
+
+        // 每轮是新的绑定，闭包捕获各自的值
+        evaluator.eval_string("(define fs (do ((i 0 (+ i 1)) (fs '() (cons (lambda () i) fs))) ((= i 3) fs)))", None).unwrap();
+        assert_eq!(eval("(list ((car fs)) ((car (cdr fs))) ((car (cdr (cdr fs)))))"), "(2 1 0)");
+
+        // 命名 let 与 do 嵌套，深度循环不增加栈深度
+        assert_eq!(eval("(let loop ((i 0) (acc 0)) (if (= i 100000) acc (loop (+ i 1) (+ acc i))))"), "4999950000");
+        assert_eq!(
+            eval("(let outer ((n 3) (acc '())) (if (= n 0) acc (outer (- n 1) (cons (do ((i 0 (+ i 1)) (s 0 (+ s n))) ((= i n) s)) acc))))"),
+            "(1 4 9)"
+        );
+        // 命名 let 的名字只在主体中可见
+        assert_eq!(eval("(let named-loop ((x 1)) x)"), "1");
+        assert!(evaluator.eval_string("named-loop", None).is_err());
+
+        assert!(evaluator.eval_string("(do ((i 0 1 2)) (#t))", None).is_err());
+        assert!(evaluator.eval_string("(do ((i 0) (i 1)) (#t))", None).is_err());
+        assert!(evaluator.eval_string("(let loop ((i 0) (i 1)) i)", None).is_err());
+    }
+
+    #[test]
+    fn test_eval_string_multiple_forms() {
+        let evaluator = Evaluator::new();
+
+        // 多个顶层表达式共享全局环境，返回最后一个的值
+        assert_eq!(evaluator.eval_string("(define x 1) (+ x 1)", None).unwrap(), Value::Integer(2));
+        assert_eq!(evaluator.eval_string_located("(define (f) x) (set! x 5) (f)", None).unwrap(), Value::Integer(5));
+
+        // 出错时停止，之前的表达式已经生效
+        assert!(evaluator.eval_string("(define y 1) (car '()) (define y 2)", None).is_err());
+        assert_eq!(evaluator.eval_string("y", None).unwrap(), Value::Integer(1));
+
+        assert!(evaluator.eval_string("", None).is_err());
+    }
+
+    #[test]
+    fn test_eval_string_builtin() {
+        let evaluator = Evaluator::new();
+        
+        assert_eq!(evaluator.eval_string("(eval-string \"(+ 1 2)\")", None).unwrap(), Value::Integer(3));
+        assert_eq!(
+            evaluator.eval_string("(eval-string \"(define (twice x) (* 2 x)) (twice 21)\")", None).unwrap(),
+            Value::Integer(42)
+        );
+        // 定义发生在全局环境中
+        assert_eq!(evaluator.eval_string("(twice 5)", None).unwrap(), Value::Integer(10));
+        
+        // 解析错误作为 Scheme 错误返回
+        assert!(evaluator.eval_string("(eval-string \"(+ 1\")", None).is_err());
+        
+        // 自我求值的递归被深度限制拦截
+        evaluator.eval_string("(define src \"(eval-string src)\")", None).unwrap();
+        let err = evaluator.eval_string("(eval-string src)", None).unwrap_err();
+        assert!(err.to_string().contains("maximum nesting depth"));
+        // 深度计数在出错后恢复
+        assert_eq!(evaluator.eval_string("(eval-string \"1\")", None).unwrap(), Value::Integer(1));
+    }
+
+    #[test]
+    fn test_eval_with_environment() {
+        let evaluator = Evaluator::new();
+        
+        // 捕获 let 内部的环境，并在其中求值
+        evaluator.eval_string("(define env (let ((secret 42)) (the-environment)))", None).unwrap();
+        assert_eq!(evaluator.eval_string("(eval 'secret env)", None).unwrap(), Value::Integer(42));
+        
+        // 在该环境中定义的变量不泄漏到全局
+        evaluator.eval_string("(eval '(define local 7) env)", None).unwrap();
+        assert_eq!(evaluator.eval_string("(eval '(+ local secret) env)", None).unwrap(), Value::Integer(49));
+        assert!(evaluator.eval_string("local", None).is_err());
+        
+        // interaction-environment 即全局环境
+        evaluator.eval_string("(eval '(define shared 1) (interaction-environment))", None).unwrap();
+        assert_eq!(evaluator.eval_string("shared", None).unwrap(), Value::Integer(1));
+        assert_eq!(evaluator.eval_string("(eval '(+ 1 2))", None).unwrap(), Value::Integer(3));
+        
+        assert!(evaluator.eval_string("(eval 'secret 5)", None).is_err());
+    }
+
+    #[test]
+    fn test_environment_bindings() {
+        let evaluator = Evaluator::new();
+        
+        evaluator.eval_string("(define x 1)", None).unwrap();
+        evaluator.eval_string("(define (inner x y) (the-environment))", None).unwrap();
+        evaluator.eval_string("(define bindings (environment-bindings (inner 10 20)))", None).unwrap();
+        // 内层的参数 x 遮蔽全局的 x，全局绑定同样可见
+        assert_eq!(evaluator.eval_string("(cdr (assq 'x bindings))", None).unwrap(), Value::Integer(10));
+        assert_eq!(evaluator.eval_string("(cdr (assq 'y bindings))", None).unwrap(), Value::Integer(20));
+        assert_eq!(evaluator.eval_string("(type-of (cdr (assq 'car bindings)))", None).unwrap(), Value::symbol("procedure"));
+        
+        // 不带参数时列出全局环境
+        assert_eq!(evaluator.eval_string("(cdr (assq 'x (environment-bindings)))", None).unwrap(), Value::Integer(1));
+        assert!(matches!(evaluator.eval_string("(environment-bindings 5)", None), Err(SchemeError::TypeError(..))));
+    }
+
+    #[test]
+    fn test_hash_table_update() {
+        let evaluator = Evaluator::new();
+        
+        // 统计符号出现次数
+        evaluator.eval_string("(define counts (make-hash-table))", None).unwrap();
+        evaluator.eval_string(
+            "(define (count-all symbols)
+               (if (null? symbols)
+                   counts
+                   (begin
+                     (hash-table-update! counts (car symbols) (lambda (v) (+ v 1)) (lambda () 0))
+                     (count-all (cdr symbols)))))",
+            None
+        ).unwrap();
+        evaluator.eval_string("(count-all '(a b a c a b))", None).unwrap();
+        assert_eq!(evaluator.eval_string("(hash-table-ref/default counts 'a 0)", None).unwrap(), Value::Integer(3));
+        assert_eq!(evaluator.eval_string("(hash-table-ref/default counts 'b 0)", None).unwrap(), Value::Integer(2));
+        assert_eq!(evaluator.eval_string("(hash-table-ref/default counts 'c 0)", None).unwrap(), Value::Integer(1));
+        
+        evaluator.eval_string("(hash-table-update!/default counts 'd (lambda (v) (* v 10)) 5)", None).unwrap();
+        assert_eq!(evaluator.eval_string("(hash-table-ref/default counts 'd 0)", None).unwrap(), Value::Integer(50));
+        
+        // 键不存在且没有 failure 过程时报错
+        assert!(evaluator.eval_string("(hash-table-update! counts 'e (lambda (v) v))", None).is_err());
+    }
+
+    #[test]
+    fn test_promises() {
+        let evaluator = Evaluator::new();
+        
+        // delay 只求值一次
+        evaluator.eval_string("(define count 0)", None).unwrap();
+        evaluator.eval_string("(define p (delay (begin (set! count (+ count 1)) count)))", None).unwrap();
+        assert_eq!(evaluator.eval_string("(+ (force p) (force p))", None).unwrap(), Value::Integer(2));
+        assert_eq!(evaluator.eval_string("count", None).unwrap(), Value::Integer(1));
+        
+        assert_eq!(evaluator.eval_string("(force (make-promise 5))", None).unwrap(), Value::Integer(5));
+        assert_eq!(evaluator.eval_string("(promise? (make-promise p))", None).unwrap(), Value::Bool(true));
+        assert_eq!(evaluator.eval_string("(force 7)", None).unwrap(), Value::Integer(7));
+    }
+
+    #[test]
+    fn test_delay_force_shares_inner_promise() {
+        let evaluator = Evaluator::new();
+        let eval = |input: &str| evaluator.eval_string(input, None).unwrap();
+
+        // 强制外层后再强制内层，内层主体只执行一次
+        eval("(define c 0)");
+        eval("(define inner (delay (begin (set! c (+ c 1)) c)))");
+        eval("(define outer (delay-force inner))");
+        eval("(force outer)");
+        eval("(force inner)");
+        assert_eq!(eval("c"), Value::Integer(1));
+
+        // 反过来先强制内层，外层得到同一结果
+        eval("(define inner2 (delay (begin (set! c (+ c 1)) c)))");
+        eval("(define outer2 (delay-force inner2))");
+        assert_eq!(eval("(force inner2)"), Value::Integer(2));
+        assert_eq!(eval("(force outer2)"), Value::Integer(2));
+        assert_eq!(eval("c"), Value::Integer(2));
+    }
+
+    #[test]
+    fn test_delay_force_infinite_stream() {
+        let evaluator = Evaluator::new();
+        
+        // 无限整数流：每个流是一个承诺，其值为 (n . 下一个流)
+        evaluator.eval_string(
+            "(define (integers-from n) (delay (cons n (integers-from (+ n 1)))))",
+            None,
+        ).unwrap();
+        evaluator.eval_string(
+            "(define (stream-drop s k) (delay-force (if (= k 0) s (stream-drop (cdr (force s)) (- k 1)))))",
+            None,
+        ).unwrap();
+        
+        // 强制第 1000 个元素时迭代跟随承诺链，不会增长栈
+        assert_eq!(
+            evaluator.eval_string("(car (force (stream-drop (integers-from 0) 1000)))", None).unwrap(),
+            Value::Integer(1000)
+        );
+    }
+
+    #[test]
+    fn test_numeric_equality_vs_equal() {
+        let evaluator = Evaluator::new();
+        let eval = |input: &str| evaluator.eval_string(input, None).unwrap();
+
+        // = 跨精确性比较数值，equal? 不做数值转换
+        assert_eq!(eval("(= 2 2)"), Value::Bool(true));
+        assert_eq!(eval("(= 2 2.0)"), Value::Bool(true));
+        assert_eq!(eval("(equal? 2 2)"), Value::Bool(true));
+        assert_eq!(eval("(equal? 2 2.0)"), Value::Bool(false));
+
+        // 同样适用于结构内部与另一方向
+        assert_eq!(eval("(equal? 2.0 2)"), Value::Bool(false));
+        assert_eq!(eval("(equal? 2.0 2.0)"), Value::Bool(true));
+        assert_eq!(eval("(equal? '(1 2) '(1 2.0))"), Value::Bool(false));
+        assert_eq!(eval("(eqv? 2 2.0)"), Value::Bool(false));
+    }
+
+    #[test]
+    fn test_symbol_equal_and_interning() {
+        let evaluator = Evaluator::new();
+        
+        assert_eq!(evaluator.eval_string("(symbol=? 'a 'a 'a)", None).unwrap(), Value::Bool(true));
+        assert_eq!(evaluator.eval_string("(symbol=? 'a 'a 'b)", None).unwrap(), Value::Bool(false));
+        assert!(evaluator.eval_string("(symbol=? 'a \"a\")", None).is_err());
+        
+        // 同名符号在不同位置解析得到同一个驻留编号
+        match (evaluator.eval_string("'interned", None).unwrap(), evaluator.eval_string("(car '(interned))", None).unwrap()) {
+            (Value::Symbol(a), Value::Symbol(b)) => assert_eq!(a.id(), b.id()),
+            other => panic!("expected symbols, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_exit_propagates() {
+        let evaluator = Evaluator::new();
+        
+        // exit 穿过嵌套调用原样到达顶层，不被包装为调用栈错误
+        evaluator.eval_string("(define (quit-with n) (begin (exit n) 'unreachable))", None).unwrap();
+        assert_eq!(evaluator.eval_string("(quit-with 42)", None), Err(SchemeError::Exit(42)));
+        assert_eq!(evaluator.eval_string("(exit)", None), Err(SchemeError::Exit(0)));
+    }
+
+    #[test]
+    fn test_vector_builtins() {
+        let evaluator = Evaluator::new();
+        
+        // 列表经向量往返
+        assert_eq!(
+            evaluator.eval_string("(vector->list (list->vector '(1 2 3)))", None).unwrap(),
+            Value::from_vec(vec![Value::Integer(1), Value::Integer(2), Value::Integer(3)])
+        );
+        assert_eq!(
+            evaluator.eval_string("(vector-map (lambda (x) (* x x)) (vector 1 2 3))", None).unwrap().to_string(),
+            "#(1 4 9)"
+        );
+        
+        // 通过 vector-for-each 累加求和
+        evaluator.eval_string("(define total 0)", None).unwrap();
+        evaluator.eval_string("(vector-for-each (lambda (x) (set! total (+ total x))) (vector 1 2 3 4))", None).unwrap();
+        assert_eq!(evaluator.eval_string("total", None).unwrap(), Value::Integer(10));
+        
+        evaluator.eval_string("(define v (make-vector 2 0))", None).unwrap();
+        evaluator.eval_string("(vector-fill! v 7)", None).unwrap();
+        assert_eq!(evaluator.eval_string("v", None).unwrap().to_string(), "#(7 7)");
+    }
+
+    #[test]
+    fn test_interned_literal_pairs_are_immutable() {
+        let evaluator = Evaluator::new();
+
+        // 整个程序求值时写法相同的 quote 字面量共享结构，因此不能被修改
+        let err = evaluator.eval_all("(define a '(1 2)) (define b '(1 2)) (set-car! a 9)").unwrap_err();
+        assert!(err.to_string().contains("cannot mutate literal"), "{err:?}");
+        assert_eq!(evaluator.eval_string("b", None).unwrap().to_string(), "(1 2)");
+        for program in ["(set-cdr! '(1 2) '())", "(reverse! '(1 2))", "(append! '(1) '(2))", "(set-car! (vector-ref '#((1)) 0) 2)"] {
+            assert!(evaluator.eval_all(program).is_err(), "{program}");
+        }
+
+        // REPL 逐行求值（eval_string_located）以及 (quote x) 写法同样不可修改
+        for program in ["(define e '(1 2)) (set-car! e 9)", "(set-car! (quote (1 2)) 9)", "(set-car! (vector-ref (quote #((1))) 0) 2)"] {
+            assert!(evaluator.eval_string_located(program, None).is_err(), "{program}");
+            assert!(evaluator.eval_string(program, None).is_err(), "{program}");
+        }
+        let err = evaluator.eval_string_located("(define f (quote (1 2))) (set-car! f 9)", None).unwrap_err();
+        assert!(err.to_string().contains("cannot mutate literal"), "{err:?}");
+
+        // 字面量的副本和新构造的列表可以修改
+        let results = evaluator.eval_all("(define c (map (lambda (x) x) '(1 2))) (set-car! c 9) c").unwrap();
+        assert_eq!(results[2].to_string(), "(9 2)");
+        let results = evaluator.eval_all("(define d (cons 0 '(1 2))) (set-car! d 9) d").unwrap();
+        assert_eq!(results[2].to_string(), "(9 1 2)");
+    }
+
+    #[test]
+    fn test_literal_vectors_are_immutable() {
+        let evaluator = Evaluator::new();
+        
+        // 字面量向量不可修改
+        let err = evaluator.eval_string("(vector-set! '#(1 2 3) 0 9)", None).unwrap_err();
+        assert!(matches!(&err, SchemeError::RuntimeError(msg, _) if msg.contains("cannot mutate literal")));
+        assert!(evaluator.eval_string("(vector-fill! #(1 2) 0)", None).is_err());
+        assert!(evaluator.eval_string("(vector-sort! #(2 1) <)", None).is_err());
+        
+        // 构造出的向量以及字面量的副本可以修改
+        assert_eq!(evaluator.eval_string("(let ((v (vector 1 2 3))) (vector-set! v 0 9) v)", None).unwrap().to_string(), "#(9 2 3)");
+        assert_eq!(evaluator.eval_string("(let ((v (vector-copy '#(1 2 3)))) (vector-set! v 0 9) v)", None).unwrap().to_string(), "#(9 2 3)");
+    }
+
+    #[test]
+    fn test_define_values() {
+        let evaluator = Evaluator::new();
+        let eval = |input: &str| evaluator.eval_string(input, None);
+
+        eval("(define-values (q r) (floor/ 17 5))").unwrap();
+        assert_eq!(eval("(list q r)").unwrap().to_string(), "(3 2)");
+
+        // 剩余参数形式
+        eval("(define-values (a . more) (values 1 2 3))").unwrap();
+        assert_eq!(eval("(list a more)").unwrap().to_string(), "(1 (2 3))");
+        eval("(define-values all (values 4 5))").unwrap();
+        assert_eq!(eval("all").unwrap().to_string(), "(4 5)");
+        eval("(define-values (x . none) 7)").unwrap();
+        assert_eq!(eval("(list x none)").unwrap().to_string(), "(7 ())");
+
+        // 在过程体内定义局部绑定
+        assert_eq!(eval("((lambda () (define-values (s t) (values 1 2)) (+ s t)))").unwrap(), Value::Integer(3));
+
+        // 值与形参个数不符
+        assert!(matches!(eval("(define-values (m n) (values 1 2 3))"), Err(SchemeError::ArityError(..))));
+        assert!(matches!(eval("(define-values (m n . k) 1)"), Err(SchemeError::ArityError(..))));
+        assert!(eval("(define-values (m m) (values 1 2))").is_err());
+    }
+
+    #[test]
+    fn test_constant_folding_preserves_results() {
+        let evaluator = Evaluator::new();
+        evaluator.set_optimize(true);
+        let eval = |input: &str| evaluator.eval_string(input, None);
+
+        assert_eq!(eval("(* 2 (+ 3 4))").unwrap(), Value::Integer(14));
+        assert_eq!(eval("(list (+ 1 2) '(+ 1 2))").unwrap().to_string(), "(3 (+ 1 2))");
+        assert_eq!(eval("(if (< 1 2) (/ 6 4) 0)").unwrap(), Value::Float(1.5));
+
+        // 会出错的调用不被折叠掉：仍在求值时报错，不被执行时不报错
+        assert!(matches!(eval("(/ 1 0)"), Err(SchemeError::DivisionByZero(_))));
+        assert_eq!(eval("(if #f (/ 1 0) 1)").unwrap(), Value::Integer(1));
+
+        // 被遮蔽或重新定义的过程不折叠
+        assert_eq!(eval("(let ((+ -)) (+ 5 2))").unwrap(), Value::Integer(3));
+        assert_eq!(eval("(let () (define + -) (+ 5 2))").unwrap(), Value::Integer(3));
+        assert_eq!(eval("(let () (define-values (+) (values -)) (+ 5 2))").unwrap(), Value::Integer(3));
+        assert_eq!(eval("(do ((* + *)) (#t (* 5 2)))").unwrap(), Value::Integer(7));
+        eval("(define (square x) 0)").unwrap();
+        assert_eq!(eval("(square 3)").unwrap(), Value::Integer(0));
+    }
+
+    #[test]
+    fn test_constant_folding_reduces_eval_steps() {
+        let program = "(define (f n) (let loop ((i 0) (acc 0)) (if (= i n) acc (loop (+ i 1) (+ acc (* 2 (+ 3 4)))))))";
+        let run = |optimize: bool| {
+            let evaluator = Evaluator::new();
+            evaluator.set_optimize(optimize);
+            evaluator.eval_string(program, None).unwrap();
+            let before = evaluator.eval_steps();
+            let result = evaluator.eval_string("(f 1000)", None).unwrap();
+            (result, evaluator.eval_steps() - before)
+        };
+
+        let (plain, plain_steps) = run(false);
+        let (optimized, optimized_steps) = run(true);
+        assert_eq!(plain, Value::Integer(14000));
+        assert_eq!(optimized, plain);
+        // 每轮循环省去 (* 2 (+ 3 4)) 中两次调用及其参数的求值
+        assert!(optimized_steps + 1000 * 5 <= plain_steps, "{optimized_steps} vs {plain_steps}");
+    }
+
+    #[test]
+    fn test_snapshot_and_restore() {
+        let evaluator = Evaluator::new();
+        let eval = |input: &str| evaluator.eval_string(input, None);
+
+        eval("(define x 1)").unwrap();
+        eval("(define (get-x) x)").unwrap();
+        eval("(define counter (let ((n 0)) (lambda () (set! n (+ n 1)) n)))").unwrap();
+        let snapshot = evaluator.snapshot();
+
+        eval("(define x 2)").unwrap();
+        eval("(define y 3)").unwrap();
+        assert_eq!(eval("(get-x)").unwrap(), Value::Integer(2));
+        assert_eq!(eval("(counter)").unwrap(), Value::Integer(1));
+        assert_eq!(eval("(counter)").unwrap(), Value::Integer(2));
+
+        evaluator.restore(&snapshot);
+        assert_eq!(eval("x").unwrap(), Value::Integer(1));
+        assert!(eval("y").is_err());
+        // 闭包看到恢复后的全局绑定，捕获的局部状态也回到快照时
+        assert_eq!(eval("(get-x)").unwrap(), Value::Integer(1));
+        assert_eq!(eval("(counter)").unwrap(), Value::Integer(1));
+
+        // 同一快照可以再次恢复
+        eval("(set! x 5)").unwrap();
+        evaluator.restore(&snapshot);
+        assert_eq!(eval("x").unwrap(), Value::Integer(1));
+
+        // 快照只保存仍可达的环境，不随历史调用次数增长
+        eval("(define (id v) (let ((w v)) w))").unwrap();
+        let before = evaluator.snapshot().environment_count();
+        eval("(do ((i 0 (+ i 1))) ((= i 1000)) (id i))").unwrap();
+        assert_eq!(evaluator.snapshot().environment_count(), before);
+    }
+
+    #[test]
+    fn test_two_value_division() {
+        let evaluator = Evaluator::new();
+        
+        assert_eq!(
+            evaluator.eval_string("(call-with-values (lambda () (floor/ -7 2)) list)", None).unwrap(),
+            Value::from_vec(vec![Value::Integer(-4), Value::Integer(1)])
+        );
+        assert_eq!(
+            evaluator.eval_string("(call-with-values (lambda () (truncate/ -7 2)) list)", None).unwrap(),
+            Value::from_vec(vec![Value::Integer(-3), Value::Integer(-1)])
+        );
+        assert_eq!(evaluator.eval_string("(floor-quotient -7 2)", None).unwrap(), Value::Integer(-4));
+        assert_eq!(evaluator.eval_string("(truncate-remainder -7 2)", None).unwrap(), Value::Integer(-1));
+        assert!(evaluator.eval_string("(floor/ 1 0)", None).is_err());
+    }
 }
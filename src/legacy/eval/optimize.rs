@@ -0,0 +1,298 @@
+use crate::legacy::types::{Value, Symbol, Result, Arity};
+
+/// 常量折叠只计算这些内置过程：没有副作用，对相同的数字参数总是给出相同结果
+pub const PURE_BUILTINS: &[&str] = &[
+    "+", "-", "*", "/", "=", "<", ">", "<=", ">=",
+    "abs", "min", "max", "square", "floor", "ceiling", "truncate", "round",
+    "positive?", "negative?", "odd?", "even?",
+    "floor-quotient", "floor-remainder", "truncate-quotient", "truncate-remainder",
+];
+
+/// 运算符符号在全局环境中的含义，由求值器提供
+pub enum Operator {
+    /// 可折叠的纯内置过程
+    Pure(fn(&[Value]) -> Result<Value>, Arity),
+    /// 其他过程：只折叠其参数
+    Procedure,
+    /// 宏、未单独处理的特殊形式或未绑定的名字：其中任何部分都不折叠
+    Opaque,
+}
+
+/// 常量折叠：将参数全为数字字面量的纯内置过程调用替换为其结果，嵌套的调用由内向外折叠。
+/// 调用出错（如 (/ 1 0)）时保留原调用，使错误在求值时照常报告。
+///
+/// resolve 给出运算符符号在全局的含义；被 lambda、let、do 或主体内部定义局部绑定的名字视为普通过程，
+/// 表达式中任何位置被 set! 的名字在整个表达式中都不折叠。
+/// 折叠在求值前进行，因此假定 PURE_BUILTINS 中的过程不会在被折叠的表达式求值期间以其他方式重新定义
+pub fn fold_constants(expr: &Value, resolve: &dyn Fn(Symbol) -> Operator) -> Value {
+    let mut shadowed = Vec::new();
+    assigned_names(expr, &mut shadowed);
+    Folder { resolve, shadowed }.fold(expr)
+}
+
+struct Folder<'a> {
+    resolve: &'a dyn Fn(Symbol) -> Operator,
+    /// 当前位置上被局部绑定遮蔽的名字
+    shadowed: Vec<Symbol>,
+}
+
+impl Folder<'_> {
+    fn fold(&mut self, expr: &Value) -> Value {
+        let Some(items) = expr.to_vec().filter(|items| !items.is_empty()) else {
+            return expr.clone();
+        };
+        let Value::Symbol(op) = &items[0] else {
+            return Value::from_vec(self.fold_all(&items));
+        };
+        if self.shadowed.contains(op) {
+            return self.fold_call(&items, Operator::Procedure);
+        }
+
+        match op.as_str() {
+            "quote" | "quasiquote" => expr.clone(),
+            "lambda" if items.len() >= 3 => match symbol_list(&items[1]) {
+                Some(params) => self.fold_scoped(&items, 2, params),
+                None => expr.clone(),
+            },
+            // (define (name param ...) body ...)：名字与参数在主体中都遮蔽全局绑定
+            "define" if matches!(items.get(1), Some(Value::Cons(_, _, _))) => match symbol_list(&items[1]) {
+                Some(names) => self.fold_scoped(&items, 2, names),
+                None => expr.clone(),
+            },
+            "let" => self.fold_let(&items).unwrap_or_else(|| expr.clone()),
+            "do" => self.fold_do(&items).unwrap_or_else(|| expr.clone()),
+            // begin 中的定义与外层主体中的一样，遮蔽其后的全部形式
+            "begin" => self.fold_scoped(&items, 1, Vec::new()),
+            "define-values" if items.len() >= 2 => {
+                let mut folded = items[..2].to_vec();
+                folded.extend(self.fold_all(&items[2..]));
+                Value::from_vec(folded)
+            },
+            "define" | "if" | "when" | "unless" | "and" | "or" | "set!" | "cond" | "assert" => {
+                let mut folded = vec![items[0].clone()];
+                folded.extend(self.fold_all(&items[1..]));
+                Value::from_vec(folded)
+            },
+            _ => {
+                let operator = (self.resolve)(*op);
+                match operator {
+                    Operator::Opaque => expr.clone(),
+                    operator => self.fold_call(&items, operator),
+                }
+            },
+        }
+    }
+
+    fn fold_all(&mut self, items: &[Value]) -> Vec<Value> {
+        items.iter().map(|item| self.fold(item)).collect()
+    }
+
+    /// 折叠调用的参数；纯过程的参数全为数字时尝试就地计算
+    fn fold_call(&mut self, items: &[Value], operator: Operator) -> Value {
+        let args = self.fold_all(&items[1..]);
+        if let Operator::Pure(func, arity) = operator {
+            if arity.accepts(args.len()) && args.iter().all(|arg| matches!(arg, Value::Integer(_) | Value::Float(_))) {
+                if let Ok(result @ (Value::Integer(_) | Value::Float(_) | Value::Bool(_))) = func(&args) {
+                    return result;
+                }
+            }
+        }
+        let mut folded = vec![items[0].clone()];
+        folded.extend(args);
+        Value::from_vec(folded)
+    }
+
+    /// 在 names 遮蔽全局绑定的作用域中折叠主体 items[body_start..]，其余部分原样保留。
+    /// 主体中的内部定义（define、define-values 及 begin 中的定义）同样遮蔽整个主体
+    fn fold_scoped(&mut self, items: &[Value], body_start: usize, names: Vec<Symbol>) -> Value {
+        let depth = self.shadowed.len();
+        self.shadowed.extend(names);
+        for form in &items[body_start..] {
+            defined_names(form, &mut self.shadowed);
+        }
+        let body = self.fold_all(&items[body_start..]);
+        self.shadowed.truncate(depth);
+
+        let mut folded = items[..body_start].to_vec();
+        folded.extend(body);
+        Value::from_vec(folded)
+    }
+
+    /// 折叠 (let [name] ((var init) ...) body ...)：init 在外层作用域中折叠，主体中 var（以及 name）被遮蔽；
+    /// 绑定列表格式不对时返回 None，交由求值时报错
+    fn fold_let(&mut self, items: &[Value]) -> Option<Value> {
+        let (mut names, bindings_index) = match items.get(1)? {
+            Value::Symbol(name) => (vec![*name], 2),
+            _ => (Vec::new(), 1),
+        };
+
+        let mut bindings = Vec::new();
+        for binding in items.get(bindings_index)?.to_vec()? {
+            match binding.to_vec()?.as_slice() {
+                [Value::Symbol(var), init] => {
+                    bindings.push(Value::from_vec(vec![Value::Symbol(*var), self.fold(init)]));
+                    names.push(*var);
+                },
+                _ => return None,
+            }
+        }
+
+        let folded = self.fold_scoped(items, bindings_index + 1, names).to_vec()?;
+        let mut result = folded[..bindings_index].to_vec();
+        result.push(Value::from_vec(bindings));
+        result.extend_from_slice(&folded[bindings_index + 1..]);
+        Some(Value::from_vec(result))
+    }
+
+    /// 折叠 (do ((var init [step]) ...) (test expr ...) command ...)：init 在外层作用域中折叠，
+    /// step、退出子句和循环体中 var 被遮蔽；格式不对时返回 None，交由求值时报错
+    fn fold_do(&mut self, items: &[Value]) -> Option<Value> {
+        let mut specs = Vec::new();
+        for spec in items.get(1)?.to_vec()? {
+            match spec.to_vec()? {
+                parts if matches!(parts.first(), Some(Value::Symbol(_))) && (2..=3).contains(&parts.len()) => specs.push(parts),
+                _ => return None,
+            }
+        }
+        let exit_clause = items.get(2)?.to_vec()?;
+
+        let inits: Vec<Value> = specs.iter().map(|parts| self.fold(&parts[1])).collect();
+        let depth = self.shadowed.len();
+        for parts in &specs {
+            if let Value::Symbol(var) = &parts[0] {
+                self.shadowed.push(*var);
+            }
+        }
+        let mut folded_specs = Vec::new();
+        for (parts, init) in specs.iter().zip(inits) {
+            let mut folded = vec![parts[0].clone(), init];
+            folded.extend(self.fold_all(&parts[2..]));
+            folded_specs.push(Value::from_vec(folded));
+        }
+        let exit_clause = self.fold_all(&exit_clause);
+        let body = self.fold_all(&items[3..]);
+        self.shadowed.truncate(depth);
+
+        let mut result = vec![items[0].clone(), Value::from_vec(folded_specs), Value::from_vec(exit_clause)];
+        result.extend(body);
+        Some(Value::from_vec(result))
+    }
+}
+
+/// 收集主体中一个形式定义的名字：(define name ...)、(define (name ...) ...)、
+/// (define-values formals ...) 以及 begin 中的这些定义
+fn defined_names(form: &Value, names: &mut Vec<Symbol>) {
+    let Some(items) = form.to_vec() else { return };
+    match items.as_slice() {
+        [Value::Symbol(keyword), target, ..] if keyword.as_str() == "define" => {
+            // (define ((curried a) b) ...) 定义的是最内层的名字
+            let mut target = target.clone();
+            while let Some(head) = target.car() {
+                target = head;
+            }
+            if let Value::Symbol(name) = target {
+                names.push(name);
+            }
+        },
+        [Value::Symbol(keyword), formals, ..] if keyword.as_str() == "define-values" => {
+            let mut rest = formals.clone();
+            while let Some(formal) = rest.car() {
+                if let Value::Symbol(name) = formal {
+                    names.push(name);
+                }
+                rest = rest.cdr().unwrap_or(Value::Nil);
+            }
+            if let Value::Symbol(name) = rest {
+                names.push(name);
+            }
+        },
+        [Value::Symbol(keyword), body @ ..] if keyword.as_str() == "begin" => {
+            for form in body {
+                defined_names(form, names);
+            }
+        },
+        _ => {},
+    }
+}
+
+/// 收集表达式中（quote 之外）被 set! 赋值的名字
+fn assigned_names(expr: &Value, names: &mut Vec<Symbol>) {
+    let Some(items) = expr.to_vec() else { return };
+    match items.as_slice() {
+        [Value::Symbol(keyword), ..] if matches!(keyword.as_str(), "quote" | "quasiquote") => return,
+        [Value::Symbol(keyword), Value::Symbol(name), ..] if keyword.as_str() == "set!" => names.push(*name),
+        _ => {},
+    }
+    for item in &items {
+        assigned_names(item, names);
+    }
+}
+
+/// 只由符号组成的列表
+fn symbol_list(value: &Value) -> Option<Vec<Symbol>> {
+    value.to_vec()?.iter()
+        .map(|item| match item {
+            Value::Symbol(name) => Some(*name),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::legacy::parser::Parser;
+    use crate::legacy::builtins;
+
+    /// 只认识 + 和 / 的全局环境
+    fn resolve(name: Symbol) -> Operator {
+        match name.as_str() {
+            "+" => Operator::Pure(builtins::add, Arity::AtLeast(0)),
+            "/" => Operator::Pure(builtins::divide, Arity::AtLeast(0)),
+            "display" => Operator::Procedure,
+            _ => Operator::Opaque,
+        }
+    }
+
+    fn fold(source: &str) -> String {
+        fold_constants(&Parser::parse(source).unwrap(), &resolve).to_string()
+    }
+
+    #[test]
+    fn test_folds_nested_literal_calls() {
+        assert_eq!(fold("(+ 1 (+ 2 3))"), "6");
+        assert_eq!(fold("(display (+ 1 2))"), "(display 3)");
+        assert_eq!(fold("(if (+ 1 2) (+ 3 4) x)"), "(if 3 7 x)");
+        assert_eq!(fold("(+ x (+ 1 2))"), "(+ x 3)");
+    }
+
+    #[test]
+    fn test_keeps_calls_that_would_fail() {
+        assert_eq!(fold("(/ 1 0)"), "(/ 1 0)");
+        assert_eq!(fold("(+ 1 \"a\")"), "(+ 1 \"a\")");
+    }
+
+    #[test]
+    fn test_respects_quote_and_local_bindings() {
+        assert_eq!(fold("'(+ 1 2)"), "(quote (+ 1 2))");
+        assert_eq!(fold("(lambda (+) (+ 1 2))"), "(lambda (+) (+ 1 2))");
+        assert_eq!(fold("(let ((+ -)) (+ 1 2))"), "(let ((+ -)) (+ 1 2))");
+        assert_eq!(fold("(let ((x (+ 1 2))) (+ x (+ 1 1)))"), "(let ((x 3)) (+ x 2))");
+        assert_eq!(fold("(define (f +) (+ 1 2))"), "(define (f +) (+ 1 2))");
+        // 主体中的内部定义遮蔽整个主体，包括定义之前的形式
+        assert_eq!(fold("(let () (define + -) (+ 5 2))"), "(let () (define + -) (+ 5 2))");
+        assert_eq!(fold("(lambda () (+ 1 2) (define (+ a b) 0))"), "(lambda () (+ 1 2) (define (+ a b) 0))");
+        assert_eq!(fold("(let () (begin (define + -)) (+ 5 2))"), "(let () (begin (define + -)) (+ 5 2))");
+        assert_eq!(fold("(begin (define + -) (+ 5 2))"), "(begin (define + -) (+ 5 2))");
+        assert_eq!(fold("(let () (define-values (x . +) (values 1 2)) (+ 5 2))"), "(let () (define-values (x . +) (values 1 2)) (+ 5 2))");
+        assert_eq!(fold("(let () (define x 1) (+ 5 2))"), "(let () (define x 1) 7)");
+        // do 的变量在 step、退出子句和循环体中遮蔽全局绑定，init 照常折叠
+        assert_eq!(fold("(do ((+ (+ 1 2) (+ 1 1))) ((+ 1 1) (+ 1 1)) (+ 1 1))"), "(do ((+ 3 (+ 1 1))) ((+ 1 1) (+ 1 1)) (+ 1 1))");
+        assert_eq!(fold("(do ((i 0 (+ i (+ 1 1)))) ((+ 1 1)))"), "(do ((i 0 (+ i 2))) (2))");
+        // 被 set! 的名字在整个表达式中都不折叠
+        assert_eq!(fold("(begin (set! + -) (+ 5 2))"), "(begin (set! + -) (+ 5 2))");
+        assert_eq!(fold("(display (lambda () (set! + -)) (+ 5 2))"), "(display (lambda () (set! + -)) (+ 5 2))");
+        // 宏和未知的形式不折叠
+        assert_eq!(fold("(my-macro (+ 1 2))"), "(my-macro (+ 1 2))");
+    }
+}
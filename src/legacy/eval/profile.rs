@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// 单个过程的统计
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ProfileEntry {
+    /// 被调用的次数
+    pub calls: u64,
+    /// 最外层调用累计耗时（递归调用不重复计时）
+    pub time: Duration,
+}
+
+/// 按过程名统计调用次数与耗时。只统计以符号为运算符的调用，
+/// 如 (fact n)；经由 apply、sort 等高阶过程发起的调用没有名字，不计入
+#[derive(Debug, Default)]
+pub struct Profiler {
+    enabled: bool,
+    entries: HashMap<String, ProfileEntry>,
+    /// 正在进行中的调用：过程名 -> (嵌套层数, 最外层调用的开始时间)
+    active: HashMap<String, (usize, Instant)>,
+}
+
+impl Profiler {
+    /// 是否正在统计
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// 开启或关闭统计；已有数据保留
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// 清空已有数据
+    pub fn reset(&mut self) {
+        self.entries.clear();
+        self.active.clear();
+    }
+
+    /// 记录一次调用开始
+    pub fn enter(&mut self, name: &str) {
+        self.entries.entry(name.to_string()).or_default().calls += 1;
+        let (depth, _) = self.active.entry(name.to_string()).or_insert((0, Instant::now()));
+        *depth += 1;
+    }
+
+    /// 记录一次调用结束；同名调用全部结束时累计其耗时
+    pub fn leave(&mut self, name: &str) {
+        let Some((depth, start)) = self.active.get_mut(name) else {
+            return;
+        };
+        *depth -= 1;
+        if *depth == 0 {
+            let elapsed = start.elapsed();
+            self.active.remove(name);
+            self.entries.entry(name.to_string()).or_default().time += elapsed;
+        }
+    }
+
+    /// 按过程名排序的统计结果
+    pub fn report(&self) -> Vec<(String, ProfileEntry)> {
+        let mut report: Vec<_> = self.entries.iter()
+            .map(|(name, entry)| (name.clone(), *entry))
+            .collect();
+        report.sort_by(|a, b| a.0.cmp(&b.0));
+        report
+    }
+}
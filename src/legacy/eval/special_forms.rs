@@ -1,6 +1,23 @@
+use std::cell::RefCell;
 use std::rc::Rc;
-use crate::legacy::types::{Value, SchemeError, Result};
+use crate::legacy::types::{Value, SchemeError, Result, RecordType, RecordProcedure, Promise, Object, Symbol};
 use crate::legacy::env::Environment;
+use crate::legacy::builtins::control::{FEATURES, values_to_vec};
+
+/// 可能处于尾位置的特殊形式的求值结果。核心求值循环就地继续处理 Eval 和 Apply，
+/// 不递归调用求值器，因此经由这些形式的尾调用不增加 Rust 栈深度
+pub enum Tail {
+    /// 已经得到的值
+    Value(Value),
+    /// 尚需在给定环境中求值的尾表达式
+    Eval(Value, Environment),
+    /// 尚需以已求值的参数调用的过程（函数应用、cond/case 的 => 子句），name 为调用处的过程名
+    Apply {
+        func: Value,
+        args: Vec<Value>,
+        name: Option<String>,
+    },
+}
 
 /// 特殊形式求值器
 pub struct SpecialFormsEvaluator;
@@ -15,7 +32,7 @@ impl SpecialFormsEvaluator {
     }
 
     /// 求值 if 特殊形式
-    pub fn eval_if(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Value> {
+    pub fn eval_if(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Tail> {
         if args.len() < 2 || args.len() > 3 {
             return Err(SchemeError::ArityError("if requires 2 or 3 arguments".to_string(), None));
         }
@@ -23,17 +40,48 @@ impl SpecialFormsEvaluator {
         let condition = eval_fn(&args[0], env)?;
         
         if condition.is_truthy() {
-            eval_fn(&args[1], env)
+            Ok(Tail::Eval(args[1].clone(), env.clone()))
         } else if args.len() == 3 {
-            eval_fn(&args[2], env)
+            Ok(Tail::Eval(args[2].clone(), env.clone()))
+        } else {
+            Ok(Tail::Value(Value::Nil))
+        }
+    }
+
+    /// 求值 when 特殊形式：(when test body ...)，测试为真时求值主体，否则返回空表
+    pub fn eval_when(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Tail> {
+        if args.is_empty() {
+            return Err(SchemeError::ArityError("when requires a test expression".to_string(), None));
+        }
+
+        if eval_fn(&args[0], env)?.is_truthy() {
+            Self::eval_sequence(&args[1..], env, eval_fn)
+        } else {
+            Ok(Tail::Value(Value::Nil))
+        }
+    }
+
+    /// 求值 unless 特殊形式：(unless test body ...)，测试为假时求值主体，否则返回空表
+    pub fn eval_unless(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Tail> {
+        if args.is_empty() {
+            return Err(SchemeError::ArityError("unless requires a test expression".to_string(), None));
+        }
+
+        if eval_fn(&args[0], env)?.is_truthy() {
+            Ok(Tail::Value(Value::Nil))
         } else {
-            Ok(Value::Nil)
+            Self::eval_sequence(&args[1..], env, eval_fn)
         }
     }
 
     /// 求值 define 特殊形式
     pub fn eval_define(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Value> {
-        if args.len() != 2 {
+        // 变量定义恰好 2 个参数；函数定义的主体可以有多个表达式
+        let valid = match args.first() {
+            Some(Value::Cons(_, _, _)) => args.len() >= 2,
+            _ => args.len() == 2,
+        };
+        if !valid {
             return Err(SchemeError::ArityError("define requires exactly 2 arguments".to_string(), None));
         }
 
@@ -41,11 +89,11 @@ impl SpecialFormsEvaluator {
             // 变量定义: (define var value)
             Value::Symbol(name) => {
                 let value = eval_fn(&args[1], env)?;
-                env.define(name.clone(), value)?;
-                Ok(Value::Nil)
+                env.define(name.to_string(), value)?;
+                Ok(Value::Unspecified)
             },
             // 函数定义: (define (func-name param1 param2 ...) body)
-            Value::Cons(_, _) => {
+            Value::Cons(_, _, _) => {
                 if let Some(func_def) = args[0].to_vec() {
                     if func_def.is_empty() {
                         return Err(SchemeError::TypeError("Empty function definition".to_string(), None));
@@ -57,21 +105,23 @@ impl SpecialFormsEvaluator {
                         let mut params = Vec::new();
                         for param in &func_def[1..] {
                             if let Value::Symbol(param_name) = param {
-                                params.push(param_name.clone());
+                                params.push(param_name.to_string());
                             } else {
                                 return Err(SchemeError::TypeError("Function parameters must be symbols".to_string(), None));
                             }
                         }
                         
+                        check_duplicate_names(&params)?;
+                        
                         // 创建 lambda 并绑定到函数名
                         let lambda = Value::Lambda {
                             params,
-                            body: Rc::new(args[1].clone()),
+                            body: Rc::new(body_expression(&args[1..])),
                             env_id: env.id(),
                         };
                         
-                        env.define(func_name.clone(), lambda)?;
-                        Ok(Value::Nil)
+                        env.define(func_name.to_string(), lambda)?;
+                        Ok(Value::Unspecified)
                     } else {
                         Err(SchemeError::TypeError("Function name must be a symbol".to_string(), None))
                     }
@@ -83,6 +133,53 @@ impl SpecialFormsEvaluator {
         }
     }
 
+    /// 求值 define-values 特殊形式：(define-values formals expr)。
+    /// formals 为 (var ...)、带剩余参数的 (var ... . rest) 或单个 rest 符号；
+    /// expr 产生的多值依次绑定到各个 var，多出的值以列表绑定到 rest
+    pub fn eval_define_values(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Value> {
+        if args.len() != 2 {
+            return Err(SchemeError::ArityError("define-values requires formals and an expression".to_string(), None));
+        }
+
+        // 解析形参：固定名字直到遇到非序对的尾部
+        let mut names = Vec::new();
+        let mut formals = args[0].clone();
+        while let Value::Cons(_, _, _) = &formals {
+            match formals.car() {
+                Some(Value::Symbol(name)) => names.push(name.to_string()),
+                _ => return Err(SchemeError::SyntaxError(format!("define-values: formals must be symbols, got {}", args[0]), None)),
+            }
+            formals = formals.cdr().unwrap_or(Value::Nil);
+        }
+        let rest = match &formals {
+            Value::Nil => None,
+            Value::Symbol(name) => Some(name.to_string()),
+            _ => return Err(SchemeError::SyntaxError(format!("define-values: formals must be symbols, got {}", args[0]), None)),
+        };
+        check_duplicate_names(&names.iter().chain(&rest).cloned().collect::<Vec<_>>())?;
+
+        let mut values = values_to_vec(eval_fn(&args[1], env)?);
+        let count_matches = match rest {
+            Some(_) => values.len() >= names.len(),
+            None => values.len() == names.len(),
+        };
+        if !count_matches {
+            return Err(SchemeError::ArityError(format!(
+                "define-values: expected {}{} values, got {}",
+                if rest.is_some() { "at least " } else { "" }, names.len(), values.len()
+            ), None));
+        }
+
+        let rest_values = values.split_off(names.len());
+        for (name, value) in names.into_iter().zip(values) {
+            env.define(name, value)?;
+        }
+        if let Some(rest) = rest {
+            env.define(rest, Value::from_vec(rest_values))?;
+        }
+        Ok(Value::Unspecified)
+    }
+
     /// 求值 set! 特殊形式
     pub fn eval_set(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Value> {
         if args.len() != 2 {
@@ -93,7 +190,7 @@ impl SpecialFormsEvaluator {
             Value::Symbol(name) => {
                 let value = eval_fn(&args[1], env)?;
                 env.set(name, value)?;
-                Ok(Value::Nil)
+                Ok(Value::Unspecified)
             },
             _ => Err(SchemeError::TypeError("set! expects a symbol".to_string(), None)),
         }
@@ -101,8 +198,8 @@ impl SpecialFormsEvaluator {
 
     /// 求值 lambda 特殊形式
     pub fn eval_lambda(args: &[Value], env: &Environment) -> Result<Value> {
-        if args.len() != 2 {
-            return Err(SchemeError::ArityError("lambda requires exactly 2 arguments".to_string(), None));
+        if args.len() < 2 {
+            return Err(SchemeError::ArityError("lambda requires a parameter list and a body".to_string(), None));
         }
 
         // 解析参数列表
@@ -113,7 +210,7 @@ impl SpecialFormsEvaluator {
                     let mut params = Vec::new();
                     for param in param_list {
                         if let Value::Symbol(name) = param {
-                            params.push(name);
+                            params.push(name.to_string());
                         } else {
                             return Err(SchemeError::TypeError("lambda parameters must be symbols".to_string(), None));
                         }
@@ -124,56 +221,107 @@ impl SpecialFormsEvaluator {
                 }
             }
         };
+        check_duplicate_names(&params)?;
 
         Ok(Value::Lambda {
             params,
-            body: Rc::new(args[1].clone()),
+            body: Rc::new(body_expression(&args[1..])),
             env_id: env.id(),
         })
     }
 
-    /// 求值 let 特殊形式
-    pub fn eval_let(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Value> {
-        if args.len() != 2 {
-            return Err(SchemeError::ArityError("let requires exactly 2 arguments".to_string(), None));
+    /// 求值 let 特殊形式；第一个参数为符号时按命名 let 处理
+    pub fn eval_let(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Tail> {
+        if let Some(Value::Symbol(name)) = args.first() {
+            return Self::eval_named_let(name.as_str(), &args[1..], env, eval_fn);
+        }
+        if args.len() < 2 {
+            return Err(SchemeError::ArityError("let requires bindings and a body".to_string(), None));
         }
 
-        // 解析绑定列表
-        let bindings = match &args[0] {
-            Value::Nil => Vec::new(),
-            expr => {
-                if let Some(binding_list) = expr.to_vec() {
-                    let mut bindings = Vec::new();
-                    for binding in binding_list {
-                        if let Some(pair) = binding.to_vec() {
-                            if pair.len() == 2 {
-                                if let Value::Symbol(name) = &pair[0] {
-                                    let value = eval_fn(&pair[1], env)?;
-                                    bindings.push((name.clone(), value));
-                                } else {
-                                    return Err(SchemeError::TypeError("let binding name must be a symbol".to_string(), None));
-                                }
-                            } else {
-                                return Err(SchemeError::TypeError("let binding must have exactly 2 elements".to_string(), None));
-                            }
-                        } else {
-                            return Err(SchemeError::TypeError("let binding must be a list".to_string(), None));
-                        }
-                    }
-                    bindings
-                } else {
-                    return Err(SchemeError::TypeError("let bindings must be a list".to_string(), None));
+        // 创建新环境
+        let (names, values) = eval_let_bindings(&args[0], env, eval_fn)?;
+        let new_env = env.extend(names, values)?;
+
+        // 主体在新环境中按顺序求值，最后一个表达式处于尾位置
+        Self::eval_sequence(&args[1..], &new_env, eval_fn)
+    }
+
+    /// 求值命名 let (let name ((var init) ...) body ...)：name 在主体中绑定到以 var 为参数的过程，
+    /// 以各 init 的值调用它；调用处于尾位置，因此以 name 递归的循环不增加栈深度
+    fn eval_named_let(name: &str, args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Tail> {
+        if args.len() < 2 {
+            return Err(SchemeError::ArityError("named let requires bindings and a body".to_string(), None));
+        }
+
+        let (params, values) = eval_let_bindings(&args[0], env, eval_fn)?;
+        let loop_env = env.extend(vec![name.to_string()], vec![Value::Unspecified])?;
+        let procedure = Value::Lambda {
+            params,
+            body: Rc::new(body_expression(&args[1..])),
+            env_id: loop_env.id(),
+        };
+        loop_env.set(name, procedure.clone())?;
+
+        Ok(Tail::Apply {
+            func: procedure,
+            args: values,
+            name: Some(name.to_string()),
+        })
+    }
+
+    /// 求值 do 循环 (do ((var init [step]) ...) (test expr ...) command ...)。
+    /// 每轮先对所有 step 求值，再以这些值一起建立下一轮的绑定（并行更新），
+    /// 因此 step 中看到的都是本轮的变量值；没有 step 的变量保持当前值
+    pub fn eval_do(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Tail> {
+        if args.len() < 2 {
+            return Err(SchemeError::ArityError("do requires variable specs and a test clause".to_string(), None));
+        }
+
+        let specs = args[0].to_vec().ok_or_else(|| {
+            SchemeError::SyntaxError(format!("do variable specs must be a list, got {}", args[0]), None)
+        })?;
+        let mut names = Vec::new();
+        let mut values = Vec::new();
+        let mut steps = Vec::new();
+        for spec in &specs {
+            match spec.to_vec().as_deref() {
+                Some([Value::Symbol(name), init, step @ ..]) if step.len() <= 1 => {
+                    names.push(name.to_string());
+                    values.push(eval_fn(init, env)?);
+                    steps.push(step.first().cloned());
+                },
+                _ => return Err(SchemeError::SyntaxError(format!("Invalid do variable spec: {spec}"), None)),
+            }
+        }
+        check_duplicate_names(&names)?;
+
+        let exit_clause = args[1].to_vec().filter(|clause| !clause.is_empty()).ok_or_else(|| {
+            SchemeError::SyntaxError(format!("do test clause must be a non-empty list, got {}", args[1]), None)
+        })?;
+        let commands = &args[2..];
+
+        loop {
+            let iteration_env = env.extend(names.clone(), values)?;
+            if eval_fn(&exit_clause[0], &iteration_env)?.is_truthy() {
+                if exit_clause.len() == 1 {
+                    return Ok(Tail::Value(Value::Unspecified));
                 }
+                return Self::eval_sequence(&exit_clause[1..], &iteration_env, eval_fn);
             }
-        };
 
-        // 创建新环境
-        let names: Vec<String> = bindings.iter().map(|(name, _)| name.clone()).collect();
-        let values: Vec<Value> = bindings.into_iter().map(|(_, value)| value).collect();
-        let new_env = env.extend(names, values)?;
+            for command in commands {
+                eval_fn(command, &iteration_env)?;
+            }
 
-        // 在新环境中求值 body
-        eval_fn(&args[1], &new_env)
+            values = Vec::with_capacity(names.len());
+            for (name, step) in names.iter().zip(&steps) {
+                values.push(match step {
+                    Some(step) => eval_fn(step, &iteration_env)?,
+                    None => iteration_env.lookup(name)?,
+                });
+            }
+        }
     }
 
     /// 求值 begin 特殊形式
@@ -189,81 +337,419 @@ impl SpecialFormsEvaluator {
         Ok(result)
     }
 
-    /// 求值 and 特殊形式
-    pub fn eval_and(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Value> {
-        if args.is_empty() {
-            return Ok(Value::Bool(true));
+    /// 求值表达式序列（begin 与各种主体）：最后一个表达式处于尾位置，空序列返回空表
+    pub fn eval_sequence(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Tail> {
+        let Some((last, init)) = args.split_last() else {
+            return Ok(Tail::Value(Value::Nil));
+        };
+
+        for expr in init {
+            eval_fn(expr, env)?;
         }
+        Ok(Tail::Eval(last.clone(), env.clone()))
+    }
+
+    /// 求值 and 特殊形式
+    pub fn eval_and(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Tail> {
+        let Some((last, init)) = args.split_last() else {
+            return Ok(Tail::Value(Value::Bool(true)));
+        };
 
-        for arg in args {
+        for arg in init {
             let result = eval_fn(arg, env)?;
             if !result.is_truthy() {
-                return Ok(result);
+                return Ok(Tail::Value(result));
             }
         }
         
-        // 如果所有表达式都为真，返回最后一个表达式的值
-        eval_fn(&args[args.len() - 1], env)
+        // 前面的表达式都为真，最后一个表达式处于尾位置
+        Ok(Tail::Eval(last.clone(), env.clone()))
     }
 
     /// 求值 or 特殊形式
-    pub fn eval_or(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Value> {
-        if args.is_empty() {
-            return Ok(Value::Bool(false));
-        }
+    pub fn eval_or(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Tail> {
+        let Some((last, init)) = args.split_last() else {
+            return Ok(Tail::Value(Value::Bool(false)));
+        };
 
-        for arg in args {
+        for arg in init {
             let result = eval_fn(arg, env)?;
             if result.is_truthy() {
-                return Ok(result);
+                return Ok(Tail::Value(result));
             }
         }
         
-        // 如果所有表达式都为假，返回最后一个表达式的值
-        eval_fn(&args[args.len() - 1], env)
+        // 前面的表达式都为假，最后一个表达式处于尾位置
+        Ok(Tail::Eval(last.clone(), env.clone()))
+    }
+
+    /// 求值 cond 特殊形式，子句形式：
+    /// (test)、(test body ...)、(test => proc)、(else body ...)、(else => proc)
+    pub fn eval_cond(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Tail> {
+        // 没有匹配的子句时返回空表
+        Ok(Self::eval_cond_clauses("cond", args, env, eval_fn)?.unwrap_or(Tail::Value(Value::Nil)))
     }
 
-    /// 求值 cond 特殊形式
-    pub fn eval_cond(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Value> {
+    /// 求值 cond-expand 特殊形式：(cond-expand (requirement body ...) ... [(else body ...)])，
+    /// 求值第一个特性要求成立的子句主体；没有子句成立时结果未指定
+    pub fn eval_cond_expand(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Tail> {
         for clause in args {
-            if let Some(clause_list) = clause.to_vec() {
-                if clause_list.len() < 1 {
-                    return Err(SchemeError::SyntaxError("cond clause must have at least a condition".to_string(), None));
-                }
-                
-                // 检查是否为 else 子句
-                if let Value::Symbol(s) = &clause_list[0] {
-                    if s == "else" {
-                        if clause_list.len() == 1 {
-                            return Ok(Value::Nil);
-                        } else if clause_list.len() == 2 {
-                            return eval_fn(&clause_list[1], env);
-                        } else {
-                            // 多个表达式，当作 begin 处理
-                            return Self::eval_begin(&clause_list[1..], env, eval_fn);
-                        }
-                    }
-                }
-                
-                // 求值条件
-                let condition = eval_fn(&clause_list[0], env)?;
-                
-                if condition.is_truthy() {
-                    if clause_list.len() == 1 {
-                        return Ok(condition);
-                    } else if clause_list.len() == 2 {
-                        return eval_fn(&clause_list[1], env);
-                    } else {
-                        // 多个表达式，当作 begin 处理
-                        return Self::eval_begin(&clause_list[1..], env, eval_fn);
-                    }
+            let clause_list = clause.to_vec().filter(|list| !list.is_empty()).ok_or_else(|| {
+                SchemeError::SyntaxError(format!("cond-expand clause must be a non-empty list, got {clause}"), None)
+            })?;
+            let matched = match &clause_list[0] {
+                Value::Symbol(s) if s == "else" => true,
+                requirement => feature_requirement_holds(requirement)?,
+            };
+            if matched {
+                return Self::eval_sequence(&clause_list[1..], env, eval_fn);
+            }
+        }
+        Ok(Tail::Value(Value::Unspecified))
+    }
+
+    /// 依次尝试 cond 风格的子句（cond 与 guard 共用），返回第一个匹配子句的结果；
+    /// 没有子句匹配时返回 None
+    pub fn eval_cond_clauses(
+        form: &str,
+        clauses: &[Value],
+        env: &Environment,
+        eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>,
+    ) -> Result<Option<Tail>> {
+        for clause in clauses {
+            let clause_list = clause.to_vec()
+                .ok_or_else(|| SchemeError::SyntaxError(format!("{form} clause must be a list"), None))?;
+            if clause_list.is_empty() {
+                return Err(SchemeError::SyntaxError(format!("{form} clause must have at least a condition"), None));
+            }
+
+            // else 子句总是匹配，其测试值视为 #t；空的 else 子句保持返回空表
+            let test = if matches!(&clause_list[0], Value::Symbol(s) if s == "else") {
+                if clause_list.len() == 1 {
+                    return Ok(Some(Tail::Value(Value::Nil)));
                 }
+                Value::Bool(true)
             } else {
-                return Err(SchemeError::SyntaxError("cond clause must be a list".to_string(), None));
+                eval_fn(&clause_list[0], env)?
+            };
+
+            if test.is_truthy() {
+                return Self::eval_clause_body(form, test, &clause_list[1..], env, eval_fn).map(Some);
             }
         }
-        
+
+        Ok(None)
+    }
+
+    /// 求值 case 特殊形式：(case key ((datum ...) body ...) ... (else body ...))，
+    /// 数据按 eqv? 与键比较；子句主体可为 => proc，以键值调用 proc
+    pub fn eval_case(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Tail> {
+        if args.is_empty() {
+            return Err(SchemeError::ArityError("case requires a key expression".to_string(), None));
+        }
+
+        let key = eval_fn(&args[0], env)?;
+        for clause in &args[1..] {
+            let clause_list = clause.to_vec()
+                .ok_or_else(|| SchemeError::SyntaxError("case clause must be a list".to_string(), None))?;
+            if clause_list.is_empty() {
+                return Err(SchemeError::SyntaxError("case clause must start with a datum list or else".to_string(), None));
+            }
+
+            let matched = match &clause_list[0] {
+                Value::Symbol(s) if s == "else" => true,
+                datums => datums.to_vec()
+                    .ok_or_else(|| SchemeError::SyntaxError(format!("case clause data must be a list, got {datums}"), None))?
+                    .iter()
+                    .any(|datum| datum.is_eqv(&key)),
+            };
+
+            if matched {
+                return Self::eval_clause_body("case", key, &clause_list[1..], env, eval_fn);
+            }
+        }
+
         // 没有匹配的子句
-        Ok(Value::Nil)
+        Ok(Tail::Value(Value::Nil))
+    }
+
+    /// 求值 cond/case 子句的主体：空主体返回测试值，=> 以测试值调用过程，其余当作 begin
+    fn eval_clause_body(
+        form: &str,
+        test: Value,
+        body: &[Value],
+        env: &Environment,
+        eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>,
+    ) -> Result<Tail> {
+        match body {
+            [] => Ok(Tail::Value(test)),
+            [Value::Symbol(arrow), rest @ ..] if arrow == "=>" => {
+                if rest.len() != 1 {
+                    return Err(SchemeError::SyntaxError(
+                        format!("{form} clause with => requires exactly 1 procedure"), None
+                    ));
+                }
+                let procedure = eval_fn(&rest[0], env)?;
+                Ok(Tail::Apply { func: procedure, args: vec![test], name: None })
+            },
+            // 多个表达式，当作 begin 处理
+            exprs => Self::eval_sequence(exprs, env, eval_fn),
+        }
+    }
+
+    /// 求值 assert 特殊形式: (assert expr) 或 (assert expr "message")
+    pub fn eval_assert(args: &[Value], env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<Value> {
+        if args.is_empty() || args.len() > 2 {
+            return Err(SchemeError::ArityError("assert requires 1 or 2 arguments".to_string(), None));
+        }
+
+        if eval_fn(&args[0], env)?.is_truthy() {
+            return Ok(Value::Nil);
+        }
+
+        // 错误信息中包含断言表达式的源码文本
+        let message = if args.len() == 2 {
+            match &eval_fn(&args[1], env)? {
+                Value::String(msg) => format!("Assertion failed: {} - {}", args[0], msg),
+                other => format!("Assertion failed: {} - {}", args[0], other),
+            }
+        } else {
+            format!("Assertion failed: {}", args[0])
+        };
+        Err(SchemeError::AssertionError(message, None))
+    }
+
+    /// 求值 syntax-error 特殊形式: (syntax-error "message" form ...)，
+    /// 通常出现在宏模板中，用于在展开时报告用户可读的错误
+    pub fn eval_syntax_error(args: &[Value]) -> Result<Value> {
+        let mut message = match args.first() {
+            Some(Value::String(message)) => message.to_string(),
+            _ => return Err(SchemeError::SyntaxError(
+                "syntax-error requires a message string".to_string(), None
+            )),
+        };
+        for irritant in &args[1..] {
+            message.push_str(&format!(" {irritant}"));
+        }
+        Err(SchemeError::SyntaxError(message, None))
+    }
+
+    /// 求值 delay 特殊形式：创建延迟求值的承诺
+    pub fn eval_delay(args: &[Value], env: &Environment) -> Result<Value> {
+        if args.len() != 1 {
+            return Err(SchemeError::ArityError("delay requires exactly 1 argument".to_string(), None));
+        }
+        Ok(Value::Promise(Rc::new(RefCell::new(Promise::Delayed {
+            expr: args[0].clone(),
+            env_id: env.id(),
+        }))))
+    }
+
+    /// 求值 delay-force 特殊形式：表达式应求值为承诺，force 时迭代跟随而不增长栈
+    pub fn eval_delay_force(args: &[Value], env: &Environment) -> Result<Value> {
+        if args.len() != 1 {
+            return Err(SchemeError::ArityError("delay-force requires exactly 1 argument".to_string(), None));
+        }
+        Ok(Value::Promise(Rc::new(RefCell::new(Promise::DelayForce {
+            expr: args[0].clone(),
+            env_id: env.id(),
+        }))))
+    }
+
+    /// 求值 the-environment 特殊形式：捕获当前环境
+    pub fn eval_the_environment(args: &[Value], env: &Environment) -> Result<Value> {
+        if !args.is_empty() {
+            return Err(SchemeError::ArityError("the-environment requires no arguments".to_string(), None));
+        }
+        Ok(Value::Environment(env.id()))
+    }
+
+    /// 求值 define-record-type 特殊形式:
+    /// (define-record-type <name> (<constructor> <field> ...) <predicate> (<field> <accessor> [<modifier>]) ...)
+    pub fn eval_define_record_type(args: &[Value], env: &Environment) -> Result<Value> {
+        if args.len() < 3 {
+            return Err(SchemeError::ArityError("define-record-type requires a type name, constructor and predicate".to_string(), None));
+        }
+
+        let type_name = match &args[0] {
+            Value::Symbol(name) => name.to_string(),
+            other => return Err(SchemeError::SyntaxError(format!("define-record-type name must be a symbol, got {other}"), None)),
+        };
+
+        let expect_symbol = |value: &Value, what: &str| -> Result<String> {
+            match value {
+                Value::Symbol(name) => Ok(name.to_string()),
+                other => Err(SchemeError::SyntaxError(format!("define-record-type {what} must be a symbol, got {other}"), None)),
+            }
+        };
+
+        // 解析字段说明: (field accessor [modifier])
+        let mut field_names = Vec::new();
+        let mut field_procs = Vec::new();
+        for spec in &args[3..] {
+            let parts = spec.to_vec().filter(|parts| parts.len() == 2 || parts.len() == 3).ok_or_else(|| {
+                SchemeError::SyntaxError(format!("Invalid record field spec: {spec}"), None)
+            })?;
+            let field = expect_symbol(&parts[0], "field name")?;
+            if field_names.contains(&field) {
+                return Err(SchemeError::SyntaxError(format!("Duplicate record field: {field}"), None));
+            }
+            let accessor = expect_symbol(&parts[1], "accessor")?;
+            let modifier = parts.get(2).map(|m| expect_symbol(m, "modifier")).transpose()?;
+            field_names.push(field);
+            field_procs.push((accessor, modifier));
+        }
+
+        // 解析构造器: (constructor field ...)
+        let constructor = args[1].to_vec().filter(|parts| !parts.is_empty()).ok_or_else(|| {
+            SchemeError::SyntaxError(format!("Invalid record constructor spec: {}", args[1]), None)
+        })?;
+        let constructor_name = expect_symbol(&constructor[0], "constructor name")?;
+        let mut field_indices = Vec::new();
+        for field in &constructor[1..] {
+            let field = expect_symbol(field, "constructor field")?;
+            let index = field_names.iter().position(|name| *name == field).ok_or_else(|| {
+                SchemeError::SyntaxError(format!("Constructor field {field} is not a field of {type_name}"), None)
+            })?;
+            field_indices.push(index);
+        }
+
+        let predicate_name = expect_symbol(&args[2], "predicate")?;
+
+        let record_type = Rc::new(RecordType { name: type_name, field_names });
+
+        env.define(constructor_name.clone(), Value::RecordProcedure(Rc::new(RecordProcedure::Constructor {
+            name: constructor_name,
+            record_type: Rc::clone(&record_type),
+            field_indices,
+        })))?;
+        env.define(predicate_name.clone(), Value::RecordProcedure(Rc::new(RecordProcedure::Predicate {
+            name: predicate_name,
+            record_type: Rc::clone(&record_type),
+        })))?;
+        for (index, (accessor, modifier)) in field_procs.into_iter().enumerate() {
+            env.define(accessor.clone(), Value::RecordProcedure(Rc::new(RecordProcedure::Accessor {
+                name: accessor,
+                record_type: Rc::clone(&record_type),
+                index,
+            })))?;
+            if let Some(modifier) = modifier {
+                env.define(modifier.clone(), Value::RecordProcedure(Rc::new(RecordProcedure::Modifier {
+                    name: modifier,
+                    record_type: Rc::clone(&record_type),
+                    index,
+                })))?;
+            }
+        }
+
+        Ok(Value::Unspecified)
+    }
+
+    /// 求值 make-object 特殊形式：(make-object ((method (param ...) body ...) ...))。
+    /// 每个方法在当前环境中创建为闭包，结果是以 (obj 'method arg ...) 调用的对象
+    pub fn eval_make_object(args: &[Value], env: &Environment) -> Result<Value> {
+        if args.len() != 1 {
+            return Err(SchemeError::ArityError("make-object requires a list of method clauses".to_string(), None));
+        }
+        let clauses = args[0].to_vec().ok_or_else(|| {
+            SchemeError::SyntaxError(format!("make-object: method clauses must be a list, got {}", args[0]), None)
+        })?;
+
+        let mut methods: Vec<(Symbol, Value)> = Vec::new();
+        for clause in clauses {
+            let parts = clause.to_vec().unwrap_or_default();
+            let Some(Value::Symbol(name)) = parts.first() else {
+                return Err(SchemeError::SyntaxError(format!("make-object: invalid method clause {clause}"), None));
+            };
+            if methods.iter().any(|(method, _)| method == name) {
+                return Err(duplicate_name_error(name.as_str()));
+            }
+            methods.push((*name, Self::eval_lambda(&parts[1..], env)?));
+        }
+
+        Ok(Value::Object(Rc::new(Object { methods })))
+    }
+}
+
+/// 将 lambda/define 的主体表达式序列合成单个表达式：只有一个时原样保留，多个时包装为 begin
+fn body_expression(body: &[Value]) -> Value {
+    match body {
+        [single] => single.clone(),
+        _ => Value::cons(Value::symbol("begin"), Value::from_vec(body.to_vec())),
+    }
+}
+
+/// 判断 cond-expand 的特性要求：特性标识符在 features 列表中，或由 and/or/not/library 组合而成。
+/// 尚不支持库，(library name) 总不成立
+fn feature_requirement_holds(requirement: &Value) -> Result<bool> {
+    let invalid = || SchemeError::SyntaxError(format!("Invalid cond-expand feature requirement: {requirement}"), None);
+    if let Value::Symbol(feature) = requirement {
+        return Ok(FEATURES.contains(&feature.as_str()));
+    }
+
+    let list = requirement.to_vec().ok_or_else(invalid)?;
+    let (Some(Value::Symbol(operator)), operands) = (list.first(), list.get(1..).unwrap_or_default()) else {
+        return Err(invalid());
+    };
+    match (operator.as_str(), operands) {
+        ("and", requirements) => {
+            for requirement in requirements {
+                if !feature_requirement_holds(requirement)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        },
+        ("or", requirements) => {
+            for requirement in requirements {
+                if feature_requirement_holds(requirement)? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        },
+        ("not", [requirement]) => Ok(!feature_requirement_holds(requirement)?),
+        ("library", [_]) => Ok(false),
+        _ => Err(invalid()),
+    }
+}
+
+/// 解析并求值 let 的绑定列表 ((name init) ...)，返回名字与初值；名字不能重复
+fn eval_let_bindings(bindings: &Value, env: &Environment, eval_fn: &dyn Fn(&Value, &Environment) -> Result<Value>) -> Result<(Vec<String>, Vec<Value>)> {
+    let binding_list = bindings.to_vec()
+        .ok_or_else(|| SchemeError::TypeError("let bindings must be a list".to_string(), None))?;
+
+    let mut names: Vec<String> = Vec::new();
+    let mut values = Vec::new();
+    for binding in binding_list {
+        let pair = binding.to_vec()
+            .ok_or_else(|| SchemeError::TypeError("let binding must be a list".to_string(), None))?;
+        if pair.len() != 2 {
+            return Err(SchemeError::TypeError("let binding must have exactly 2 elements".to_string(), None));
+        }
+        let Value::Symbol(name) = &pair[0] else {
+            return Err(SchemeError::TypeError("let binding name must be a symbol".to_string(), None));
+        };
+        if names.iter().any(|bound| bound == name.as_str()) {
+            return Err(duplicate_name_error(name.as_str()));
+        }
+        values.push(eval_fn(&pair[1], env)?);
+        names.push(name.to_string());
+    }
+    Ok((names, values))
+}
+
+/// 重复绑定名的语法错误
+fn duplicate_name_error(name: &str) -> SchemeError {
+    SchemeError::SyntaxError(format!("duplicate binding name: {name}"), None)
+}
+
+/// 检查参数列表中没有重复的名字（R7RS 要求 lambda 参数与 let 绑定名互不相同）
+fn check_duplicate_names(names: &[String]) -> Result<()> {
+    for (index, name) in names.iter().enumerate() {
+        if names[..index].contains(name) {
+            return Err(duplicate_name_error(name));
+        }
     }
-} 
\ No newline at end of file
+    Ok(())
+}
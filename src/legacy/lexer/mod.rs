@@ -19,6 +19,7 @@ impl LocatedToken {
 pub enum Token {
     // 分隔符
     LeftParen,          // (
+    VectorStart,        // #(
     RightParen,         // )
     Quote,              // '
     Quasiquote,         // `
@@ -29,8 +30,11 @@ pub enum Token {
     Integer(i64),
     Float(f64),
     String(String),
+    Char(char),
     Symbol(String),
     Boolean(bool),
+    EofObject,          // #!eof
+    DefaultObject,      // #!default
     
     // 特殊
     Dot,                // .
@@ -41,16 +45,22 @@ impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Token::LeftParen => write!(f, "("),
+            Token::VectorStart => write!(f, "#("),
             Token::RightParen => write!(f, ")"),
             Token::Quote => write!(f, "'"),
             Token::Quasiquote => write!(f, "`"),
             Token::Unquote => write!(f, ","),
             Token::UnquoteSplicing => write!(f, ",@"),
             Token::Integer(n) => write!(f, "{n}"),
-            Token::Float(n) => write!(f, "{n}"),
+            Token::Float(n) if n.is_nan() => write!(f, "+nan.0"),
+            Token::Float(n) if n.is_infinite() => write!(f, "{}inf.0", if *n > 0.0 { '+' } else { '-' }),
+            Token::Float(n) => write!(f, "{n:?}"),
             Token::String(s) => write!(f, "\"{s}\""),
+            Token::Char(c) => write!(f, "#\\{c}"),
             Token::Symbol(s) => write!(f, "{s}"),
             Token::Boolean(b) => write!(f, "#{}", if *b { "t" } else { "f" }),
+            Token::EofObject => write!(f, "#!eof"),
+            Token::DefaultObject => write!(f, "#!default"),
             Token::Dot => write!(f, "."),
             Token::EOF => write!(f, "<EOF>"),
         }
@@ -64,8 +74,13 @@ pub struct Lexer {
     current_char: Option<char>,
     line: usize,
     column: usize,
+    /// 是否将符号名折叠为小写（默认区分大小写，可由 #!fold-case 指令开启）
+    fold_case: bool,
 }
 
+/// 切换大小写折叠模式的读取器指令
+const FOLD_CASE_DIRECTIVES: [(&str, bool); 2] = [("#!fold-case", true), ("#!no-fold-case", false)];
+
 impl Lexer {
     /// 创建新的词法分析器
     pub fn new(input: &str) -> Self {
@@ -78,9 +93,43 @@ impl Lexer {
             current_char,
             line: 1,
             column: 1,
+            fold_case: false,
         }
     }
 
+    /// 设置初始的大小写折叠模式；输入中的 #!fold-case / #!no-fold-case 指令仍可切换
+    pub fn with_fold_case(mut self, fold_case: bool) -> Self {
+        self.fold_case = fold_case;
+        self
+    }
+
+    /// 当前位置是否为大小写折叠指令，返回指令文本及其设置的模式
+    fn fold_case_directive(&self) -> Option<(&'static str, bool)> {
+        let rest = &self.input[self.position..];
+        FOLD_CASE_DIRECTIVES.into_iter().find(|(directive, _)| {
+            let len = directive.chars().count();
+            // 指令之后必须是分隔符，避免吞掉 #!fold-casex 之类的记号
+            rest.len() >= len
+                && rest[..len].iter().copied().eq(directive.chars())
+                && rest.get(len).is_none_or(|c| c.is_whitespace() || "()\";".contains(*c))
+        })
+    }
+
+    /// 读取大小写折叠指令；该模式对之后的全部输入生效
+    fn read_fold_case_directive(&mut self) {
+        if let Some((directive, fold_case)) = self.fold_case_directive() {
+            for _ in directive.chars() {
+                self.advance();
+            }
+            self.fold_case = fold_case;
+        }
+    }
+
+    /// 已读过的字符数，即下一个 token（或其前的空白）开始的字符下标
+    pub fn offset(&self) -> usize {
+        self.position
+    }
+
     /// 获取当前位置
     #[allow(dead_code)]
     fn current_position(&self) -> Position {
@@ -125,8 +174,8 @@ impl Lexer {
         }
     }
 
-    /// 读取数字（整数或浮点数）
-    fn read_number(&mut self) -> Token {
+    /// 读取数字（整数或浮点数）；尚无有理数类型，7/2 这样的有理数写法报错
+    fn read_number(&mut self) -> Result<Token, String> {
         let mut number_str = String::new();
         let mut is_float = false;
 
@@ -144,16 +193,104 @@ impl Lexer {
                 is_float = true;
                 number_str.push(ch);
                 self.advance();
+            } else if (ch == 'e' || ch == 'E') && self.exponent_follows() {
+                // 指数部分：e 之后可带符号，必须有数字
+                is_float = true;
+                number_str.push('e');
+                self.advance();
+                if let Some(sign @ ('+' | '-')) = self.current_char {
+                    number_str.push(sign);
+                    self.advance();
+                }
+                while let Some(digit) = self.current_char.filter(char::is_ascii_digit) {
+                    number_str.push(digit);
+                    self.advance();
+                }
+                break;
             } else {
                 break;
             }
         }
 
+        if self.current_char == Some('/') {
+            return Err(format!("Rational literals are not supported: {number_str}/..."));
+        }
+
         if is_float {
-            Token::Float(number_str.parse().unwrap_or(0.0))
+            Ok(Token::Float(number_str.parse().unwrap_or(0.0)))
         } else {
-            Token::Integer(number_str.parse().unwrap_or(0))
+            Ok(Token::Integer(number_str.parse().unwrap_or(0)))
+        }
+    }
+
+    /// 当前的 e/E 之后是否跟着合法的指数（可选符号加数字）
+    fn exponent_follows(&self) -> bool {
+        let mut offset = self.position + 1;
+        if matches!(self.input.get(offset), Some('+' | '-')) {
+            offset += 1;
+        }
+        self.input.get(offset).is_some_and(char::is_ascii_digit)
+    }
+
+    /// 读取字符字面量（#\a、#\space 等）
+    fn read_char(&mut self) -> Result<Token, String> {
+        self.advance(); // 跳过 #
+        self.advance(); // 跳过 \
+        let first = self.current_char.ok_or("Unexpected end of input in character")?;
+        self.advance();
+
+        // 字母开头时继续读取，以识别字符名
+        let mut name = first.to_string();
+        if first.is_alphabetic() {
+            while let Some(ch) = self.current_char.filter(|ch| ch.is_alphanumeric()) {
+                name.push(ch);
+                self.advance();
+            }
+        }
+
+        if name.chars().count() == 1 {
+            return Ok(Token::Char(first));
+        }
+        if let Some(hex) = name.strip_prefix('x').filter(|hex| hex.chars().all(|ch| ch.is_ascii_hexdigit())) {
+            let code = u32::from_str_radix(hex, 16)
+                .map_err(|_| format!("Character code point out of range: #\\{name}"))?;
+            return char::from_u32(code)
+                .map(Token::Char)
+                .ok_or_else(|| format!("Invalid character code point: #\\{name} (U+{code:X} is not a Unicode scalar value)"));
+        }
+        match name.as_str() {
+            "space" => Ok(Token::Char(' ')),
+            "newline" => Ok(Token::Char('\n')),
+            "tab" => Ok(Token::Char('\t')),
+            "return" => Ok(Token::Char('\r')),
+            "nul" | "null" => Ok(Token::Char('\0')),
+            "delete" => Ok(Token::Char('\x7f')),
+            "escape" => Ok(Token::Char('\x1b')),
+            "alarm" => Ok(Token::Char('\x07')),
+            "backspace" => Ok(Token::Char('\x08')),
+            _ => Err(format!("Unknown character name: #\\{name}")),
+        }
+    }
+
+    /// 读取 |...| 形式的符号，其中 \| 与 \\ 为转义
+    fn read_bar_symbol(&mut self) -> Result<Token, String> {
+        let mut symbol = String::new();
+        self.advance(); // 跳过开始的 |
+
+        while let Some(ch) = self.current_char {
+            self.advance();
+            match ch {
+                '|' => return Ok(Token::Symbol(symbol)),
+                '\\' => {
+                    let escaped = self.current_char.ok_or("Unexpected end of input in symbol")?;
+                    symbol.push(escaped);
+                    self.advance();
+                },
+                _ => symbol.push(ch),
+            }
         }
+
+        Err("Unterminated |symbol|".to_string())
     }
 
     /// 读取字符串字面量
@@ -201,14 +338,30 @@ impl Lexer {
             }
         }
 
-        // 检查是否为布尔值
+        // 检查是否为布尔值或特殊浮点数字面量
         match symbol.as_str() {
-            "#t" => Token::Boolean(true),
-            "#f" => Token::Boolean(false),
+            "#t" | "#true" => Token::Boolean(true),
+            "#f" | "#false" => Token::Boolean(false),
+            "+inf.0" => Token::Float(f64::INFINITY),
+            "-inf.0" => Token::Float(f64::NEG_INFINITY),
+            "+nan.0" | "-nan.0" => Token::Float(f64::NAN),
+            _ if self.fold_case => Token::Symbol(symbol.to_lowercase()),
             _ => Token::Symbol(symbol),
         }
     }
 
+    /// 读取 #! 前缀的特殊对象（如 #!eof、#!default）
+    fn read_hash_bang(&mut self) -> Result<Token, String> {
+        match self.read_symbol() {
+            Token::Symbol(name) => match name.as_str() {
+                "#!eof" => Ok(Token::EofObject),
+                "#!default" => Ok(Token::DefaultObject),
+                _ => Err(format!("Unknown #! syntax: {name}")),
+            },
+            other => Err(format!("Unknown #! syntax: {other}")),
+        }
+    }
+
     /// 获取下一个 token
     pub fn next_token(&mut self) -> Result<Token, String> {
         loop {
@@ -241,21 +394,38 @@ impl Lexer {
                         return Ok(Token::Unquote);
                     }
                 },
+                // 省略号 ... 是标识符（用于 syntax-rules）
+                Some('.') if self.input.get(self.position + 1) == Some(&'.') => {
+                    return Ok(self.read_symbol());
+                },
                 Some('.') => {
                     self.advance();
                     return Ok(Token::Dot);
                 },
                 Some('"') => return self.read_string(),
-                Some(ch) if ch.is_ascii_digit() => return Ok(self.read_number()),
+                Some(ch) if ch.is_ascii_digit() => return self.read_number(),
                 Some('-') => {
                     // 检查 '-' 后面是否是数字
                     if let Some(next_char) = self.input.get(self.position + 1) {
                         if next_char.is_ascii_digit() {
-                            return Ok(self.read_number());
+                            return self.read_number();
                         }
                     }
                     return Ok(self.read_symbol());
                 },
+                Some('#') if self.fold_case_directive().is_some() => self.read_fold_case_directive(),
+                Some('#') if self.input.get(self.position + 1) == Some(&'!') => {
+                    return self.read_hash_bang();
+                },
+                Some('#') if self.input.get(self.position + 1) == Some(&'\\') => {
+                    return self.read_char();
+                },
+                Some('#') if self.input.get(self.position + 1) == Some(&'(') => {
+                    self.advance();
+                    self.advance();
+                    return Ok(Token::VectorStart);
+                },
+                Some('|') => return self.read_bar_symbol(),
                 Some(ch) if ch.is_alphabetic() || "+*/<>=!?_#".contains(ch) => {
                     return Ok(self.read_symbol());
                 },
@@ -297,6 +467,8 @@ impl Lexer {
                         }
                     }
                 },
+                // 指令不产生 token，记号位置应从其后开始
+                Some('#') if self.fold_case_directive().is_some() => self.read_fold_case_directive(),
                 _ => break,
             }
         }
@@ -362,6 +534,16 @@ mod tests {
             Token::Boolean(false),
             Token::EOF,
         ]);
+
+        // 长格式布尔值
+        let mut lexer = Lexer::new("#true #false");
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens, vec![
+            Token::Boolean(true),
+            Token::Boolean(false),
+            Token::EOF,
+        ]);
     }
 
     #[test]
@@ -396,6 +578,97 @@ mod tests {
             Token::RightParen,
             Token::EOF,
         ]);
+
+        // 尚不支持有理数写法，报错而不是读成 7 与符号 /2
+        assert!(Lexer::new("7/2").tokenize().unwrap_err().contains("Rational literals are not supported"));
+        assert!(Lexer::new("(floor -7/2)").tokenize().is_err());
+    }
+
+    #[test]
+    fn test_lexer_special_floats() {
+        let mut lexer = Lexer::new("+inf.0 -inf.0 +nan.0 +info");
+        let tokens = lexer.tokenize().unwrap();
+        
+        assert_eq!(tokens[0], Token::Float(f64::INFINITY));
+        assert_eq!(tokens[1], Token::Float(f64::NEG_INFINITY));
+        assert!(matches!(tokens[2], Token::Float(n) if n.is_nan()));
+        // 仅精确匹配的记号才是特殊浮点数
+        assert_eq!(tokens[3], Token::Symbol("+info".to_string()));
+        
+        // 输出形式可以被重新读入
+        let printed: Vec<String> = tokens[..3].iter().map(|token| token.to_string()).collect();
+        assert_eq!(printed, vec!["+inf.0", "-inf.0", "+nan.0"]);
+    }
+
+    #[test]
+    fn test_lexer_chars_vectors_and_bar_symbols() {
+        let mut lexer = Lexer::new(r"#\a #\space #\( #(1) |a b| |x\|y| 1e3 2.5E-2 3e");
+        let tokens = lexer.tokenize().unwrap();
+
+        assert_eq!(tokens[0], Token::Char('a'));
+        assert_eq!(tokens[1], Token::Char(' '));
+        assert_eq!(tokens[2], Token::Char('('));
+        assert_eq!(tokens[3], Token::VectorStart);
+        assert_eq!(tokens[4], Token::Integer(1));
+        assert_eq!(tokens[5], Token::RightParen);
+        assert_eq!(tokens[6], Token::Symbol("a b".to_string()));
+        assert_eq!(tokens[7], Token::Symbol("x|y".to_string()));
+        assert_eq!(tokens[8], Token::Float(1000.0));
+        assert_eq!(tokens[9], Token::Float(0.025));
+        // 没有数字的 e 不属于指数
+        assert_eq!(tokens[10], Token::Integer(3));
+        assert_eq!(tokens[11], Token::Symbol("e".to_string()));
+
+        assert!(Lexer::new(r"#\bogus").tokenize().is_err());
+        assert!(Lexer::new("|open").tokenize().is_err());
+    }
+
+    #[test]
+    fn test_lexer_hex_and_named_chars() {
+        let tokens = Lexer::new(r"#\x41 #\x3bb #\x #\nul #\delete #\escape #\alarm #\backspace").tokenize().unwrap();
+        assert_eq!(tokens[..8], [
+            Token::Char('A'), Token::Char('λ'), Token::Char('x'), Token::Char('\0'),
+            Token::Char('\x7f'), Token::Char('\x1b'), Token::Char('\x07'), Token::Char('\x08'),
+        ]);
+
+        assert_eq!(crate::legacy::parser::Parser::parse(r"#\x41").unwrap().to_string(), r"#\A");
+        assert_eq!(crate::legacy::types::Value::Char('\x1b').to_string(), r"#\escape");
+        assert_eq!(crate::legacy::types::Value::Char('\x01').to_string(), r"#\x1");
+
+        // 超出范围或代理区的码点报错
+        let error = Lexer::new(r"#\x110000").tokenize().unwrap_err();
+        assert!(error.contains("#\\x110000"), "unexpected error: {error}");
+        assert!(Lexer::new(r"#\xD800").tokenize().is_err());
+        assert!(Lexer::new(r"#\xFFFFFFFFF").tokenize().is_err());
+        // x 后不全是十六进制数字时按字符名处理
+        assert!(Lexer::new(r"#\xyz").tokenize().is_err());
+    }
+
+    #[test]
+    fn test_lexer_fold_case() {
+        // 默认区分大小写
+        let tokens = Lexer::new("Foo foo").tokenize().unwrap();
+        assert_eq!(tokens[0], Token::Symbol("Foo".to_string()));
+        assert_eq!(tokens[1], Token::Symbol("foo".to_string()));
+
+        // 指令切换的模式持续到输入结束或下一条指令；|...| 符号不折叠
+        let tokens = Lexer::new("Foo #!fold-case Foo |Bar| #!no-fold-case Baz").tokenize().unwrap();
+        assert_eq!(tokens, vec![
+            Token::Symbol("Foo".to_string()),
+            Token::Symbol("foo".to_string()),
+            Token::Symbol("Bar".to_string()),
+            Token::Symbol("Baz".to_string()),
+            Token::EOF,
+        ]);
+
+        // 以选项开启，记号位置从指令之后开始
+        let tokens = Lexer::new("#!no-fold-case\nABC #!fold-case\nABC").with_fold_case(true).tokenize_with_positions().unwrap();
+        assert_eq!(tokens[0], LocatedToken::new(Token::Symbol("ABC".to_string()), Position::new(2, 1)));
+        assert_eq!(tokens[1], LocatedToken::new(Token::Symbol("abc".to_string()), Position::new(3, 1)));
+        assert_eq!(Lexer::new("X").with_fold_case(true).tokenize().unwrap()[0], Token::Symbol("x".to_string()));
+
+        // 不是完整指令时仍按 #! 对象处理
+        assert!(Lexer::new("#!fold-cases").tokenize().is_err());
     }
 
     #[test]
@@ -440,4 +713,31 @@ mod tests {
             Token::EOF,
         ]);
     }
+
+    #[test]
+    fn test_lexer_hash_bang() {
+        let mut lexer = Lexer::new("#!eof #!default");
+        let tokens = lexer.tokenize().unwrap();
+        assert_eq!(tokens, vec![Token::EofObject, Token::DefaultObject, Token::EOF]);
+
+        // 未知的 #! 语法报错
+        let mut lexer = Lexer::new("(a #!foo)");
+        let err = lexer.tokenize().unwrap_err();
+        assert!(err.contains("#!foo"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn test_lexer_ellipsis() {
+        let mut lexer = Lexer::new("(a ... . b)");
+        let tokens = lexer.tokenize().unwrap();
+        assert_eq!(tokens, vec![
+            Token::LeftParen,
+            Token::Symbol("a".to_string()),
+            Token::Symbol("...".to_string()),
+            Token::Dot,
+            Token::Symbol("b".to_string()),
+            Token::RightParen,
+            Token::EOF,
+        ]);
+    }
 }
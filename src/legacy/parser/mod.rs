@@ -1,3 +1,5 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::rc::Rc;
 use crate::legacy::lexer::{Lexer, Token, LocatedToken};
 use crate::legacy::types::{Value, SchemeError, Result, Position, LocatedValue};
@@ -6,6 +8,8 @@ use crate::legacy::types::{Value, SchemeError, Result, Position, LocatedValue};
 pub struct Parser {
     tokens: Vec<LocatedToken>,
     position: usize,
+    /// 常量数据驻留表（write 形式 -> 首次解析出的数据）；None 表示不驻留
+    literals: HashMap<String, Value>,
 }
 
 impl Parser {
@@ -18,9 +22,30 @@ impl Parser {
         Ok(Parser {
             tokens,
             position: 0,
+            literals: HashMap::new(),
         })
     }
 
+    /// 驻留 quote 的数据（'x 与 (quote x) 两种写法相同处理）；原子值不需要共享。
+    /// 复合字面量一律标记为常量，不能被修改；同一解析器中写法相同的字面量共享同一份
+    /// 序对/向量结构，避免重复分配，因此 eq? 可能对两个相同的字面量返回 #t
+    fn intern_literal(&mut self, datum: Value) -> Value {
+        if !matches!(datum, Value::Cons(_, _, _) | Value::Vector(_, _)) {
+            return datum;
+        }
+        self.literals.entry(datum.write_string()).or_insert_with(|| mark_literal_pairs(&datum)).clone()
+    }
+
+    /// 由解析出的列表元素构造列表；形如 (quote x) 的表达式与 'x 一样驻留其数据
+    fn finish_list(&mut self, mut elements: Vec<Value>) -> Value {
+        if elements.len() == 2 && elements[0] == Value::symbol("quote") {
+            let datum = elements.pop().unwrap();
+            let datum = self.intern_literal(datum);
+            elements.push(datum);
+        }
+        Value::from_vec(elements)
+    }
+
     /// 获取当前 token
     fn current_token(&self) -> &Token {
         self.tokens.get(self.position).map(|lt| &lt.token).unwrap_or(&Token::EOF)
@@ -55,12 +80,17 @@ impl Parser {
             
             Token::String(s) => {
                 self.advance();
-                Ok(Value::String(s))
+                Ok(Value::string(s))
+            },
+            
+            Token::Char(c) => {
+                self.advance();
+                Ok(Value::Char(c))
             },
             
             Token::Symbol(s) => {
                 self.advance();
-                Ok(Value::Symbol(s))
+                Ok(Value::symbol(&s))
             },
             
             Token::Boolean(b) => {
@@ -68,6 +98,16 @@ impl Parser {
                 Ok(Value::Bool(b))
             },
             
+            Token::EofObject => {
+                self.advance();
+                Ok(Value::Eof)
+            },
+            
+            Token::DefaultObject => {
+                self.advance();
+                Ok(Value::Default)
+            },
+            
             Token::Quote => {
                 let quote_pos = self.current_position();
                 self.advance();
@@ -78,8 +118,8 @@ impl Parser {
                         other => other,
                     })?;
                 Ok(Value::from_vec(vec![
-                    Value::Symbol("quote".to_string()),
-                    expr
+                    Value::symbol("quote"),
+                    self.intern_literal(expr)
                 ]))
             },
             
@@ -93,7 +133,7 @@ impl Parser {
                         other => other,
                     })?;
                 Ok(Value::from_vec(vec![
-                    Value::Symbol("quasiquote".to_string()),
+                    Value::symbol("quasiquote"),
                     expr
                 ]))
             },
@@ -108,7 +148,7 @@ impl Parser {
                         other => other,
                     })?;
                 Ok(Value::from_vec(vec![
-                    Value::Symbol("unquote".to_string()),
+                    Value::symbol("unquote"),
                     expr
                 ]))
             },
@@ -123,7 +163,7 @@ impl Parser {
                         other => other,
                     })?;
                 Ok(Value::from_vec(vec![
-                    Value::Symbol("unquote-splicing".to_string()),
+                    Value::symbol("unquote-splicing"),
                     expr
                 ]))
             },
@@ -133,6 +173,11 @@ impl Parser {
                 self.parse_list()
             },
             
+            Token::VectorStart => {
+                self.advance();
+                self.parse_vector()
+            },
+            
             _ => Err(SchemeError::SyntaxError(
                 format!("Unexpected token: {}", self.current_token()), self.current_position()
             )),
@@ -171,7 +216,7 @@ impl Parser {
                 // 构造 dotted pair
                 let mut result = tail;
                 for elem in elements.into_iter().rev() {
-                    result = Value::Cons(Rc::new(elem), Rc::new(result));
+                    result = Value::cons(elem, result);
                 }
                 return Ok(result);
             }
@@ -190,7 +235,34 @@ impl Parser {
         }
 
         self.advance(); // 跳过 ')'
-        Ok(Value::from_vec(elements))
+        Ok(self.finish_list(elements))
+    }
+
+    /// 解析向量字面量 #(...) 的元素（#( 已被跳过）
+    fn parse_vector(&mut self) -> Result<Value> {
+        let mut elements = Vec::new();
+
+        while !matches!(self.current_token(), Token::RightParen | Token::EOF) {
+            if matches!(self.current_token(), Token::Dot) {
+                return Err(SchemeError::SyntaxError(
+                    "Unexpected dot in vector".to_string(), self.current_position()
+                ));
+            }
+            let element = self.parse_expression()
+                .map_err(|e| match e {
+                    SchemeError::SyntaxError(msg, pos) =>
+                        SchemeError::SyntaxError(format!("In vector element: {}", msg), pos),
+                    other => other,
+                })?;
+            elements.push(element);
+        }
+
+        if matches!(self.current_token(), Token::EOF) {
+            return Err(SchemeError::SyntaxError("Unclosed vector".to_string(), self.current_position()));
+        }
+
+        self.advance(); // 跳过 ')'
+        Ok(Value::Vector(Rc::new(RefCell::new(elements)), true))
     }
 
     /// 解析程序（多个表达式）
@@ -235,12 +307,17 @@ impl Parser {
             
             Token::String(s) => {
                 self.advance();
-                Ok(LocatedValue::new(Value::String(s), current_pos))
+                Ok(LocatedValue::new(Value::string(s), current_pos))
+            },
+            
+            Token::Char(c) => {
+                self.advance();
+                Ok(LocatedValue::new(Value::Char(c), current_pos))
             },
             
             Token::Symbol(s) => {
                 self.advance();
-                Ok(LocatedValue::new(Value::Symbol(s), current_pos))
+                Ok(LocatedValue::new(Value::symbol(&s), current_pos))
             },
             
             Token::Boolean(b) => {
@@ -248,6 +325,16 @@ impl Parser {
                 Ok(LocatedValue::new(Value::Bool(b), current_pos))
             },
             
+            Token::EofObject => {
+                self.advance();
+                Ok(LocatedValue::new(Value::Eof, current_pos))
+            },
+            
+            Token::DefaultObject => {
+                self.advance();
+                Ok(LocatedValue::new(Value::Default, current_pos))
+            },
+            
             Token::Quote => {
                 let quote_pos = self.current_position();
                 self.advance();
@@ -259,8 +346,8 @@ impl Parser {
                     })?;
                 
                 let quoted_value = Value::from_vec(vec![
-                    Value::Symbol("quote".to_string()),
-                    expr.value
+                    Value::symbol("quote"),
+                    self.intern_literal(expr.value)
                 ]);
                 Ok(LocatedValue::new(quoted_value, quote_pos))
             },
@@ -276,7 +363,7 @@ impl Parser {
                     })?;
                 
                 let quasiquoted_value = Value::from_vec(vec![
-                    Value::Symbol("quasiquote".to_string()),
+                    Value::symbol("quasiquote"),
                     expr.value
                 ]);
                 Ok(LocatedValue::new(quasiquoted_value, quasiquote_pos))
@@ -293,7 +380,7 @@ impl Parser {
                     })?;
                 
                 let unquoted_value = Value::from_vec(vec![
-                    Value::Symbol("unquote".to_string()),
+                    Value::symbol("unquote"),
                     expr.value
                 ]);
                 Ok(LocatedValue::new(unquoted_value, unquote_pos))
@@ -310,7 +397,7 @@ impl Parser {
                     })?;
                 
                 let unquote_splicing_value = Value::from_vec(vec![
-                    Value::Symbol("unquote-splicing".to_string()),
+                    Value::symbol("unquote-splicing"),
                     expr.value
                 ]);
                 Ok(LocatedValue::new(unquote_splicing_value, unquote_splicing_pos))
@@ -323,6 +410,11 @@ impl Parser {
                 Ok(LocatedValue::new(list_value, paren_pos))
             },
             
+            Token::VectorStart => {
+                self.advance();
+                Ok(LocatedValue::new(self.parse_vector()?, current_pos))
+            },
+            
             _ => Err(SchemeError::SyntaxError(
                 format!("Unexpected token: {}", self.current_token()), current_pos
             )),
@@ -361,7 +453,7 @@ impl Parser {
                 // 构造 dotted pair
                 let mut result = tail.value;
                 for elem in elements.into_iter().rev() {
-                    result = Value::Cons(Rc::new(elem), Rc::new(result));
+                    result = Value::cons(elem, result);
                 }
                 return Ok(result);
             }
@@ -380,7 +472,7 @@ impl Parser {
         }
 
         self.advance(); // 跳过 ')'
-        Ok(Value::from_vec(elements))
+        Ok(self.finish_list(elements))
     }
 
     /// 解析程序（多个表达式，返回带位置信息的值）
@@ -394,6 +486,52 @@ impl Parser {
         Ok(expressions)
     }
 
+    /// 逐个解析顶层表达式，每个顶层数据对应一个结果；
+    /// 某个表达式出错时跳过到下一个平衡的顶层边界后继续解析
+    pub fn parse_program_recovering(&mut self) -> Vec<Result<LocatedValue>> {
+        let mut results = Vec::new();
+
+        while !matches!(self.current_token(), Token::EOF) {
+            let start = self.position;
+            let result = self.parse_expression_located();
+            if result.is_err() {
+                self.skip_top_level_datum(start);
+            }
+            results.push(result);
+        }
+
+        results
+    }
+
+    /// 从 start 处重新扫描，跳过一个括号平衡的顶层数据（多余的右括号单独跳过）
+    fn skip_top_level_datum(&mut self, start: usize) {
+        self.position = start;
+        let mut depth = 0usize;
+
+        loop {
+            match self.current_token() {
+                Token::EOF => return,
+                Token::LeftParen | Token::VectorStart => depth += 1,
+                Token::RightParen => {
+                    if depth <= 1 {
+                        self.advance();
+                        return;
+                    }
+                    depth -= 1;
+                },
+                // 引号前缀属于后面的数据
+                Token::Quote | Token::Quasiquote | Token::Unquote | Token::UnquoteSplicing => {},
+                _ => {
+                    if depth == 0 {
+                        self.advance();
+                        return;
+                    }
+                },
+            }
+            self.advance();
+        }
+    }
+
     /// 解析单个完整的表达式（便利方法，返回带位置信息的值）
     pub fn parse_located(input: &str) -> Result<LocatedValue> {
         let mut parser = Parser::new(input)?;
@@ -405,6 +543,34 @@ impl Parser {
         let mut parser = Parser::new(input)?;
         parser.parse_program_located()
     }
+
+    /// 解析所有顶层表达式并分别报告错误（便利方法）；词法错误时返回单个错误
+    pub fn parse_all_located(input: &str) -> Vec<Result<LocatedValue>> {
+        match Parser::new(input) {
+            Ok(mut parser) => parser.parse_program_recovering(),
+            Err(e) => vec![Err(e)],
+        }
+    }
+}
+
+/// 复制数据中的序对与向量并标记为字面量（包括嵌套在向量中的序对）
+fn mark_literal_pairs(datum: &Value) -> Value {
+    if let Value::Vector(items, _) = datum {
+        let items = items.borrow().iter().map(mark_literal_pairs).collect();
+        return Value::Vector(Rc::new(RefCell::new(items)), true);
+    }
+
+    let mut elements = Vec::new();
+    let mut current = datum.clone();
+    while let Value::Cons(car, cdr, _) = &current {
+        elements.push(mark_literal_pairs(&car.borrow()));
+        let next = cdr.borrow().clone();
+        current = next;
+    }
+    let tail = if elements.is_empty() { current } else { mark_literal_pairs(&current) };
+    elements.into_iter().rev().fold(tail, |tail, element| {
+        Value::Cons(Rc::new(RefCell::new(element)), Rc::new(RefCell::new(tail)), true)
+    })
 }
 
 #[cfg(test)]
@@ -415,17 +581,22 @@ mod tests {
     fn test_parse_atom() {
         assert_eq!(Parser::parse("42").unwrap(), Value::Integer(42));
         assert_eq!(Parser::parse("3.14").unwrap(), Value::Float(3.14));
-        assert_eq!(Parser::parse("\"hello\"").unwrap(), Value::String("hello".to_string()));
-        assert_eq!(Parser::parse("foo").unwrap(), Value::Symbol("foo".to_string()));
+        assert_eq!(Parser::parse("\"hello\"").unwrap(), Value::string("hello"));
+        assert_eq!(Parser::parse("foo").unwrap(), Value::symbol("foo"));
         assert_eq!(Parser::parse("#t").unwrap(), Value::Bool(true));
         assert_eq!(Parser::parse("#f").unwrap(), Value::Bool(false));
+        assert_eq!(Parser::parse("#true").unwrap(), Value::Bool(true));
+        assert_eq!(Parser::parse("#false").unwrap(), Value::Bool(false));
+        
+        // 长格式显示时回到短格式
+        assert_eq!(Parser::parse("(#true #false)").unwrap().to_string(), "(#t #f)");
     }
 
     #[test]
     fn test_parse_list() {
         let result = Parser::parse("(+ 1 2)").unwrap();
         let expected = Value::from_vec(vec![
-            Value::Symbol("+".to_string()),
+            Value::symbol("+"),
             Value::Integer(1),
             Value::Integer(2),
         ]);
@@ -436,12 +607,12 @@ mod tests {
     fn test_parse_nested_list() {
         let result = Parser::parse("(+ (* 2 3) 4)").unwrap();
         let inner_list = Value::from_vec(vec![
-            Value::Symbol("*".to_string()),
+            Value::symbol("*"),
             Value::Integer(2),
             Value::Integer(3),
         ]);
         let expected = Value::from_vec(vec![
-            Value::Symbol("+".to_string()),
+            Value::symbol("+"),
             inner_list,
             Value::Integer(4),
         ]);
@@ -452,8 +623,8 @@ mod tests {
     fn test_parse_quote() {
         let result = Parser::parse("'foo").unwrap();
         let expected = Value::from_vec(vec![
-            Value::Symbol("quote".to_string()),
-            Value::Symbol("foo".to_string()),
+            Value::symbol("quote"),
+            Value::symbol("foo"),
         ]);
         assert_eq!(result, expected);
     }
@@ -472,7 +643,7 @@ mod tests {
         assert_eq!(results[1], Value::Integer(2));
         
         let expected_list = Value::from_vec(vec![
-            Value::Symbol("+".to_string()),
+            Value::symbol("+"),
             Value::Integer(3),
             Value::Integer(4),
         ]);
@@ -569,12 +740,12 @@ mod tests {
 
         // 测试符号的位置信息
         let result = Parser::parse_located("foo").unwrap();
-        assert_eq!(result.value(), &Value::Symbol("foo".to_string()));
+        assert_eq!(result.value(), &Value::symbol("foo"));
         assert!(result.position().is_some());
 
         // 测试字符串的位置信息
         let result = Parser::parse_located("\"hello\"").unwrap();
-        assert_eq!(result.value(), &Value::String("hello".to_string()));
+        assert_eq!(result.value(), &Value::string("hello"));
         assert!(result.position().is_some());
     }
 
@@ -582,7 +753,7 @@ mod tests {
     fn test_located_list_parsing() {
         let result = Parser::parse_located("(+ 1 2)").unwrap();
         let expected = Value::from_vec(vec![
-            Value::Symbol("+".to_string()),
+            Value::symbol("+"),
             Value::Integer(1),
             Value::Integer(2),
         ]);
@@ -597,8 +768,8 @@ mod tests {
     fn test_located_quote_parsing() {
         let result = Parser::parse_located("'foo").unwrap();
         let expected = Value::from_vec(vec![
-            Value::Symbol("quote".to_string()),
-            Value::Symbol("foo".to_string()),
+            Value::symbol("quote"),
+            Value::symbol("foo"),
         ]);
         assert_eq!(result.value(), &expected);
         assert!(result.position().is_some());
@@ -624,7 +795,7 @@ mod tests {
         
         // 检查第三个值（列表）
         let expected_list = Value::from_vec(vec![
-            Value::Symbol("+".to_string()),
+            Value::symbol("+"),
             Value::Integer(3),
             Value::Integer(4),
         ]);
@@ -655,4 +826,93 @@ mod tests {
         assert_eq!(pos3.line, 3);
         assert_eq!(pos3.column, 1);
     }
+
+    #[test]
+    fn test_parse_all_located_recovers() {
+        let source = "(define a 1)\n(a . b c)\n(define b 2)";
+        let results = Parser::parse_all_located(source);
+        
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().to_string(), "(define a 1)");
+        assert!(results[1].is_err());
+        let third = results[2].as_ref().unwrap();
+        assert_eq!(third.to_string(), "(define b 2)");
+        assert_eq!(third.position, Some(Position::new(3, 1)));
+        
+        // 多余的右括号只跳过自身
+        let results = Parser::parse_all_located("1 ) 2");
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_ref().unwrap().value, Value::Integer(2));
+        
+        // 未闭合的表达式吞掉剩余输入
+        let results = Parser::parse_all_located("'x (1 (2)");
+        assert_eq!(results.len(), 2);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+    }
+
+    #[test]
+    fn test_parse_hash_bang_objects() {
+        assert_eq!(Parser::parse("#!eof").unwrap(), Value::Eof);
+        assert_eq!(Parser::parse("'(#!default)").unwrap().to_string(), "(quote (#!default))");
+        assert!(matches!(Parser::parse("#!foo"), Err(SchemeError::SyntaxError(..))));
+    }
+
+    #[test]
+    fn test_parse_chars_and_vectors() {
+        assert_eq!(Parser::parse(r"#\x").unwrap(), Value::Char('x'));
+        let vector = Parser::parse(r"#(1 #\a (2 3) #())").unwrap();
+        assert_eq!(vector.to_string(), r"#(1 #\a (2 3) #())");
+        assert_eq!(Parser::parse_located("#(1 2)").unwrap().value.to_string(), "#(1 2)");
+        assert!(matches!(Parser::parse("#(1 . 2)"), Err(SchemeError::SyntaxError(..))));
+        assert!(matches!(Parser::parse("#(1"), Err(SchemeError::SyntaxError(..))));
+
+        // 出错恢复时向量整体被跳过
+        let results = Parser::parse_all_located("#(1 . 2) 2");
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1].as_ref().unwrap().value, Value::Integer(2));
+    }
+
+    #[test]
+    fn test_literal_interning_shares_structure() {
+        // 程序中大量重复的大型 quote 表
+        let table: Vec<String> = (0..500).map(|i| format!("(key-{i} . \"value {i}\")")).collect();
+        let program: String = (0..50)
+            .map(|n| format!("(define table-{n} '({}))\n", table.join(" ")))
+            .collect();
+
+        // 取出每个 define 中被 quote 的数据，返回其首个序对的 car 单元
+        let quoted_cells = |exprs: Vec<LocatedValue>| -> Vec<Rc<RefCell<Value>>> {
+            exprs.iter().map(|expr| {
+                let quoted = expr.value.to_vec().unwrap()[2].to_vec().unwrap()[1].clone();
+                match &quoted {
+                    Value::Cons(car, _, _) => car.clone(),
+                    other => panic!("expected a list, got {other}"),
+                }
+            }).collect()
+        };
+
+        let interned = Parser::new(&program).unwrap().parse_program_located().unwrap();
+        let cells = quoted_cells(interned);
+        assert_eq!(cells.len(), 50);
+        assert!(cells.iter().all(|cell| Rc::ptr_eq(cell, &cells[0])));
+
+        // (quote x) 写法与 'x 共享同一份数据，且同样被标记为字面量
+        let exprs = Parser::parse_multiple("'(1 2) (quote (1 2))").unwrap();
+        let data: Vec<Value> = exprs.iter().map(|expr| expr.to_vec().unwrap()[1].clone()).collect();
+        assert!(data[0].is_eq(&data[1]));
+        assert!(matches!(data[1], Value::Cons(_, _, true)));
+
+        // 不同解析器（如 REPL 的每一行）各自分配
+        let first = quoted_cells(Parser::parse_multiple_located(&program).unwrap());
+        let second = quoted_cells(Parser::parse_multiple_located(&program).unwrap());
+        assert!(!Rc::ptr_eq(&first[0], &second[0]));
+
+        // 写法不同（如 0.0 与 -0.0）的字面量不合并
+        let mut parser = Parser::new("'(0.0) '(-0.0)").unwrap();
+        let exprs = parser.parse_program().unwrap();
+        assert_eq!(exprs[1].to_string(), "(quote (-0.0))");
+    }
 }
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use crate::legacy::builtins::port::capture_output;
 use crate::legacy::eval::Evaluator;
 use crate::legacy::parser::Parser;
 use crate::legacy::types::{SchemeError, Value};
@@ -73,7 +74,9 @@ impl Repl {
                         
                         // 求值
                         let result = self.evaluate(&multiline_buffer);
-                        println!("{}", result);
+                        if !result.is_empty() {
+                            println!("{}", result);
+                        }
                         
                         // 清空缓冲区
                         multiline_buffer.clear();
@@ -124,25 +127,38 @@ impl Repl {
 
     /// 求值并返回结果
     fn evaluate(&mut self, input: &str) -> String {
-        match self.evaluator.eval_string(input, None) {
+        match self.evaluator.eval_string_located(input, None) {
             Ok(value) => {
                 // 如果是定义操作，更新上下文（简单检测）
                 if input.trim().starts_with("(define ") {
                     // 这里应该更准确地解析定义，但为简单起见使用字符串匹配
                     if let Value::Symbol(name) = &value {
-                        self.context.insert(name.clone(), value.clone());
+                        self.context.insert(name.to_string(), value.clone());
                     }
                 }
-                format!("{}", value)
-            }
-            Err(SchemeError::SyntaxError(msg, _)) => {
-                format!("Syntax Error: {}", msg)
+                // 未指定值（define、set! 等的结果）不输出
+                if value == Value::Unspecified {
+                    String::new()
+                } else {
+                    format!("{}", value)
+                }
             }
-            Err(SchemeError::RuntimeError(msg, _)) => {
-                format!("Runtime Error: {}", msg)
+            Err(SchemeError::Exit(code)) => {
+                println!("Goodbye!");
+                std::process::exit(code);
             }
             Err(e) => {
-                format!("Error: {}", e)
+                let message = match &e {
+                    SchemeError::SyntaxError(msg, _) => format!("Syntax Error: {}", msg),
+                    SchemeError::RuntimeError(msg, _) => format!("Runtime Error: {}", msg),
+                    SchemeError::IoError(msg) => format!("IO Error: {}", msg),
+                    e => format!("Error: {}", e),
+                };
+                // 有位置信息时附上出错的源码行
+                match e.position().and_then(|pos| pos.render_snippet(input)) {
+                    Some(snippet) => format!("{}\n{}", message, snippet),
+                    None => message,
+                }
             }
         }
     }
@@ -201,7 +217,7 @@ Navigation:
                 None
             }
             "reset" => {
-                self.evaluator = Evaluator::new();
+                self.evaluator.reset_user_bindings();
                 self.context.clear();
                 Some("Interpreter state reset.".to_string())
             }
@@ -221,16 +237,27 @@ Navigation:
     fn get_scheme_keywords(&self) -> Vec<&'static str> {
         vec![
             // Special forms
-            "quote", "if", "lambda", "let", "begin", "and", "or", "cond", "define", "set!",
+            "quote", "if", "when", "unless", "lambda", "let", "do", "begin", "and", "or", "cond", "case", "cond-expand", "define", "define-values", "set!", "assert", "define-record-type", "make-object", "parameterize", "guard", "the-environment", "delay", "delay-force",
+            "define-syntax", "let-syntax", "letrec-syntax", "syntax-rules", "syntax-error",
             // Built-in functions
             "+", "-", "*", "/", "=", "<", ">", "<=", ">=", "abs", "max", "min",
-            "cons", "car", "cdr", "list", "null?", "pair?", "number?", "string?", "symbol?",
+            "cons", "car", "cdr", "set-car!", "set-cdr!", "append!", "reverse!", "list", "length", "last-pair", "delete", "remove", "map", "for-each", "apply", "find", "list-index", "any", "every", "assq", "assv", "assoc", "member", "alist-update", "del-assq", "type-of", "procedure-arity", "null?", "pair?", "number?", "real?", "rational?", "exact-integer?", "positive?", "negative?", "odd?", "even?", "string?", "symbol?", "symbol=?", "eq?", "eqv?", "equal?",
+            "string-pad", "string-pad-right", "string-trim", "string-trim-left", "string-trim-right", "string-foldcase", "char-foldcase", "string-ci=?", "string-ci<?", "char-ci=?", "char->digit", "digit->char", "make-string", "string-copy", "string-ref", "string-set!", "string-fill!",
+            "open-input-string", "open-output-string", "get-output-string", "close-port", "open-input-file", "open-output-file", "call-with-input-file", "call-with-output-file", "peek-char", "read-char", "read-line", "char-ready?", "eof-object?", "read", "current-input-port", "with-input-from-string", "format", "display", "write-char", "newline", "write", "write-shared", "write-simple", "write-string", "pp",
+            "equal-hash", "hash", "make-parameter", "eval-string", "load", "read-tokens", "eval", "interaction-environment", "environment-bindings", "profile-report", "force", "make-promise", "promise?",
+            "make-hash-table", "hash-table?", "hash-table-set!", "hash-table-ref/default", "hash-table-contains?",
+            "hash-table-delete!", "hash-table-update!", "hash-table-update!/default", "hash-table-count", "alist->hash-table", "hash-table->alist",
+            "current-time", "current-jiffy", "jiffies-per-second", "random", "set-random-seed!",
+            "vector", "make-vector", "vector?", "vector-length", "vector-ref", "vector-set!",
+            "vector->list", "list->vector", "vector-copy", "vector-copy!", "vector-fill!", "vector-map", "vector-for-each", "list-sort", "vector-sort!",
+            "floor", "ceiling", "truncate", "round", "square", "exact-integer-sqrt", "number->string", "string->number", "floor/", "floor-quotient", "floor-remainder", "truncate/", "truncate-quotient", "truncate-remainder",
+            "values", "call-with-values", "raise", "raise-continuable", "with-exception-handler", "error", "error?", "error-object?", "error-object-message", "error-object-irritants", "exit", "emergency-exit", "features", "*version*",
             // Constants
             "#t", "#f", "true", "false",
         ]
     }
 
-    /// 获取当前环境中可用的符号
+    /// 获取当前环境中可用的符号（排序并去重，保证输出稳定）
     fn get_available_symbols(&self) -> Vec<String> {
         let mut symbols = Vec::new();
         
@@ -240,6 +267,8 @@ Navigation:
         // 添加用户定义的变量
         symbols.extend(self.context.keys().cloned());
         
+        symbols.sort();
+        symbols.dedup();
         symbols
     }
 
@@ -266,6 +295,11 @@ Navigation:
         self.evaluator.eval_string(input, context)
     }
     
+    /// 求值一行输入，返回结果与求值期间 display、write 等写到当前输出的文本
+    pub fn eval_line(&mut self, input: &str) -> (Result<Value, SchemeError>, String) {
+        capture_output(|| self.evaluator.eval_string_located(input, None))
+    }
+
     /// 获取全局环境
     pub fn global_env(&self) -> crate::legacy::env::Environment {
         self.evaluator.global_env()
@@ -302,7 +336,7 @@ mod tests {
         
         assert_eq!(repl.eval("42", None).unwrap(), Value::Integer(42));
         assert_eq!(repl.eval("(+ 1 2)", None).unwrap(), Value::Integer(3));
-        assert_eq!(repl.eval("'hello", None).unwrap(), Value::Symbol("hello".to_string()));
+        assert_eq!(repl.eval("'hello", None).unwrap(), Value::symbol("hello"));
     }
 
     #[test]
@@ -315,4 +349,51 @@ mod tests {
         assert_eq!(results[1], Value::Integer(2));
         assert_eq!(results[2], Value::Integer(7));
     }
+
+    #[test]
+    fn test_eval_line_captures_output() {
+        let mut repl = Repl::new().unwrap();
+
+        let (result, output) = repl.eval_line("(begin (display \"hi\") (write \"x\") (newline) (+ 1 2))");
+        assert_eq!(result.unwrap(), Value::Integer(3));
+        assert_eq!(output, "hi\"x\"\n");
+
+        // 出错时仍返回出错前的输出
+        let (result, output) = repl.eval_line("(begin (display 1) (car '()))");
+        assert!(result.is_err());
+        assert_eq!(output, "1");
+
+        // 捕获结束后恢复到标准输出，没有残留
+        let (_, output) = repl.eval_line("42");
+        assert_eq!(output, "");
+    }
+
+    #[test]
+    fn test_error_output_includes_snippet() {
+        let mut repl = Repl::new().unwrap();
+        
+        // 插入符号指向出错表达式的起始列
+        let output = repl.evaluate("   (car 5)");
+        assert!(output.contains("car expects a pair"), "unexpected output: {output}");
+        assert!(output.ends_with("  |\n1 |    (car 5)\n  |    ^"), "unexpected output: {output}");
+
+    }
+
+    #[test]
+    fn test_available_symbols_sorted_and_deduped() {
+        let mut repl = Repl::new().unwrap();
+        repl.context.insert("zeta".to_string(), Value::Integer(1));
+        repl.context.insert("alpha".to_string(), Value::Integer(2));
+        repl.context.insert("car".to_string(), Value::Integer(3)); // 与内置函数重名
+        
+        let symbols = repl.get_available_symbols();
+        
+        let mut sorted = symbols.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(symbols, sorted);
+        assert_eq!(symbols.iter().filter(|s| *s == "car").count(), 1);
+        assert!(symbols.contains(&"alpha".to_string()));
+        assert!(symbols.contains(&"zeta".to_string()));
+    }
 }
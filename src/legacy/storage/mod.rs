@@ -2,6 +2,13 @@ use crate::legacy::types::Value;
 use std::collections::HashMap;
 use std::rc::Rc;
 
+/// 当前时间距 Unix 纪元的时长
+pub fn since_unix_epoch() -> std::time::Duration {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+}
+
 /// S-Expression 的全局唯一 ID
 pub type SExpressionId = u64;
 
@@ -36,10 +43,7 @@ impl StoredSExpression {
         symbol_names: Vec<String>,
         dependencies: Vec<SExpressionId>,
     ) -> Self {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let now = since_unix_epoch().as_secs();
 
         StoredSExpression {
             id,
@@ -62,10 +66,7 @@ impl StoredSExpression {
         symbol_names: Option<Vec<String>>,
         dependencies: Option<Vec<SExpressionId>>,
     ) -> Self {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let now = since_unix_epoch().as_secs();
 
         StoredSExpression {
             id: self.id,
@@ -284,7 +285,7 @@ mod tests {
         let mut storage = MemoryStorage::new();
         
         // 创建测试 S-Expression
-        let code = Rc::new(Value::Symbol("test".to_string()));
+        let code = Rc::new(Value::symbol("test"));
         let expr = StoredSExpression::new(
             0, // 将被自动分配
             code,
@@ -319,7 +320,7 @@ mod tests {
         // 创建被依赖的表达式
         let dep_expr = StoredSExpression::new(
             0,
-            Rc::new(Value::Symbol("dependency".to_string())),
+            Rc::new(Value::symbol("dependency")),
             Some("Dependency".to_string()),
             None,
             vec!["dep".to_string()],
@@ -330,7 +331,7 @@ mod tests {
         // 创建依赖表达式
         let main_expr = StoredSExpression::new(
             0,
-            Rc::new(Value::Symbol("main".to_string())),
+            Rc::new(Value::symbol("main")),
             Some("Main expression".to_string()),
             None,
             vec!["main".to_string()],
@@ -1,5 +1,59 @@
-use std::fmt;
+use std::collections::HashMap;
+use std::fmt::{self, Write as _};
+use std::hash::{Hash, Hasher};
 use std::rc::Rc;
+use std::cell::RefCell;
+
+pub mod object;
+pub mod parameter;
+pub mod port;
+pub mod printer;
+pub mod promise;
+pub mod record;
+pub mod symbol;
+
+pub use object::Object;
+pub use parameter::Parameter;
+pub use port::{Port, InputPort};
+pub use promise::Promise;
+pub use record::{RecordType, RecordProcedure, RecordFields};
+pub use symbol::Symbol;
+
+/// 内置过程接受的参数个数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    /// 恰好 n 个
+    Exact(usize),
+    /// 闭区间 [min, max] 内
+    Range(usize, usize),
+    /// 至少 n 个（AtLeast(0) 即任意个数）
+    AtLeast(usize),
+}
+
+impl Arity {
+    /// 是否接受 n 个参数
+    pub fn accepts(&self, n: usize) -> bool {
+        match *self {
+            Arity::Exact(expected) => n == expected,
+            Arity::Range(min, max) => min <= n && n <= max,
+            Arity::AtLeast(min) => n >= min,
+        }
+    }
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Arity::Exact(n) => write!(f, "{n}"),
+            Arity::Range(min, max) => write!(f, "{min} to {max}"),
+            Arity::AtLeast(0) => write!(f, "variadic"),
+            Arity::AtLeast(min) => write!(f, "at least {min}"),
+        }
+    }
+}
+
+/// 哈希表（键按 equal? 比较）
+pub type HashTable = HashMap<Value, Value>;
 
 /// 位置信息结构
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -12,6 +66,18 @@ impl Position {
     pub fn new(line: usize, column: usize) -> Self {
         Position { line, column }
     }
+
+    /// 渲染该位置所在的源码行，并在对应列下方标出 ^（类似 rustc 的错误输出）
+    pub fn render_snippet(&self, source: &str) -> Option<String> {
+        let line = source.lines().nth(self.line.checked_sub(1)?)?;
+        let gutter = " ".repeat(self.line.to_string().len());
+        // 保留制表符以便在终端中对齐
+        let padding: String = line.chars()
+            .take(self.column.saturating_sub(1))
+            .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+            .collect();
+        Some(format!("{gutter} |\n{} | {line}\n{gutter} | {padding}^", self.line))
+    }
 }
 
 impl fmt::Display for Position {
@@ -101,17 +167,62 @@ pub enum Value {
     Integer(i64),
     /// 浮点数
     Float(f64),
-    /// 字符串
-    String(String),
-    /// 符号
-    Symbol(String),
-    /// Cons 对 (列表的基本构造块)
-    Cons(Rc<Value>, Rc<Value>),
+    /// 字符串（不可变：字符串字面量以及大多数字符串过程的结果）；
+    /// 克隆只复制 Rc 句柄，因此 eq? 能区分内容相同的两个字符串对象
+    String(Rc<str>),
+    /// 可变字符串（由 make-string、string-copy 创建，可被 string-set!、string-fill! 原地修改）；
+    /// 克隆只复制 Rc 句柄，修改对所有共享者可见。除修改操作外，字符串过程同等接受两种字符串
+    MutableString(Rc<RefCell<String>>),
+    /// 符号（驻留于符号表）
+    Symbol(Symbol),
+    /// 字符
+    Char(char),
+    /// 文件结束对象
+    Eof,
+    /// 默认对象（#!default，表示省略的可选参数）
+    Default,
+    /// 未指定值（define、set! 等只为副作用而执行的形式的结果），REPL 不输出
+    Unspecified,
+    /// 一等环境（由 interaction-environment 或 the-environment 得到）
+    Environment(crate::legacy::env::EnvironmentId),
+    /// 端口
+    Port(Rc<RefCell<Port>>),
+    /// 记录实例（字段可变）
+    Record {
+        record_type: Rc<RecordType>,
+        fields: RecordFields,
+    },
+    /// define-record-type 生成的构造器、谓词、访问器或修改器
+    RecordProcedure(Rc<RecordProcedure>),
+    /// make-object 创建的消息传递对象，以 (obj 'method arg ...) 调用
+    Object(Rc<Object>),
+    /// 参数对象
+    Parameter(Rc<Parameter>),
+    /// 承诺（delay / delay-force / make-promise，求值后记忆结果）
+    Promise(Rc<RefCell<Promise>>),
+    /// 哈希表（可变）
+    HashTable(Rc<RefCell<HashTable>>),
+    /// 多值（由 values 产生，单个值时直接返回该值本身）
+    MultipleValues(Vec<Value>),
+    /// Cons 对 (列表的基本构造块)；car 和 cdr 各自为可变单元，可由 set-car!/set-cdr! 修改，
+    /// 克隆只复制单元的 Rc 句柄，因此修改对所有共享该序对的值可见。
+    /// 第三个字段为 true 时是源码中的 quote 字面量（'x 或 (quote x)，写法相同的字面量
+    /// 可能共享结构），修改操作对其报错；该标记随值克隆，比较与哈希时忽略
+    Cons(Rc<RefCell<Value>>, Rc<RefCell<Value>>, bool),
+    /// 向量（元素可变）；第二个字段为 true 时是源码中的字面量常量（如 '#(1 2 3)），
+    /// vector-set! 等修改操作对其报错。该标记随值克隆，比较与哈希时忽略
+    Vector(Rc<RefCell<Vec<Value>>>, bool),
     /// 内置函数
     BuiltinFunction {
         name: String,
         func: fn(&[Value]) -> Result<Value>,
-        arity: Option<usize>, // None 表示可变参数
+        arity: Arity,
+    },
+    /// 需要回调求值器的内置函数（如接受过程参数的高阶函数）
+    EvaluatorFunction {
+        name: String,
+        func: fn(&[Value], &crate::legacy::eval::CoreEvaluator, Option<&crate::legacy::eval::EvaluationContext>) -> Result<Value>,
+        arity: Arity,
     },
     /// 用户定义的函数 (lambda)
     Lambda {
@@ -134,41 +245,260 @@ impl Value {
 
     /// 检查是否为列表（包括空列表）
     pub fn is_list(&self) -> bool {
-        match self {
-            Value::Nil => true,
-            Value::Cons(_, cdr) => cdr.is_list(),
-            _ => false,
-        }
+        self.is_proper_list()
     }
 
-    /// 将列表转换为 Vec（如果可能）
-    pub fn to_vec(&self) -> Option<Vec<Value>> {
-        let mut result = Vec::new();
-        let mut current = self;
-        
+    /// 检查是否为正常列表（以空表结尾的 cons 链，包括空表本身）；循环列表不是正常列表
+    pub fn is_proper_list(&self) -> bool {
+        matches!(self.walk_list(|_| {}), Some(Value::Nil))
+    }
+
+    /// 沿 cdr 链依次访问每个元素，返回链的结尾（空表或非序对的尾部）。
+    /// cdr 链成环时返回 None：慢指针每两步前进一步（龟兔赛跑），被快指针追上即说明有环
+    pub fn walk_list(&self, mut visit: impl FnMut(&Value)) -> Option<Value> {
+        let mut current = self.clone();
+        let mut slow = self.clone();
+        let mut steps = 0usize;
         loop {
-            match current {
-                Value::Nil => return Some(result),
-                Value::Cons(car, cdr) => {
-                    result.push((**car).clone());
-                    current = cdr;
+            current = match &current {
+                Value::Cons(car, cdr, _) => {
+                    visit(&car.borrow());
+                    let next = cdr.borrow().clone();
+                    next
                 },
-                _ => return None, // 不是有效的列表
+                end => return Some(end.clone()),
+            };
+            steps += 1;
+            if steps.is_multiple_of(2) {
+                slow = slow.cdr()?;
+                if matches!(current, Value::Cons(_, _, _)) && current.is_eq(&slow) {
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// 获取 cons 链的最后一个 pair；非 pair 或 cdr 链成环时返回 None（环的检测同 walk_list）
+    pub fn last_pair(&self) -> Option<Value> {
+        if !matches!(self, Value::Cons(_, _, _)) {
+            return None;
+        }
+        let mut current = self.clone();
+        let mut slow = self.clone();
+        let mut steps = 0usize;
+        loop {
+            let next = current.cdr()?;
+            if !matches!(next, Value::Cons(_, _, _)) {
+                return Some(current);
+            }
+            current = next;
+            steps += 1;
+            if steps.is_multiple_of(2) {
+                slow = slow.cdr()?;
+                if current.is_eq(&slow) {
+                    return None;
+                }
             }
         }
     }
 
+    /// 将列表转换为 Vec（如果可能）；非正常列表与循环列表返回 None
+    pub fn to_vec(&self) -> Option<Vec<Value>> {
+        let mut result = Vec::new();
+        match self.walk_list(|item| result.push(item.clone())) {
+            Some(Value::Nil) => Some(result),
+            _ => None,
+        }
+    }
+
+    /// 创建新的 cons 对
+    pub fn cons(car: Value, cdr: Value) -> Value {
+        Value::Cons(Rc::new(RefCell::new(car)), Rc::new(RefCell::new(cdr)), false)
+    }
+
+    /// 创建（驻留的）符号
+    pub fn symbol(name: &str) -> Value {
+        Value::Symbol(Symbol::intern(name))
+    }
+
+    /// 创建新的不可变字符串对象
+    pub fn string(contents: impl Into<Rc<str>>) -> Value {
+        Value::String(contents.into())
+    }
+
     /// 从 Vec 创建列表
     pub fn from_vec(values: Vec<Value>) -> Value {
-        values.into_iter().rev().fold(Value::Nil, |acc, val| {
-            Value::Cons(Rc::new(val), Rc::new(acc))
-        })
+        values.into_iter().rev().fold(Value::Nil, |acc, val| Value::cons(val, acc))
     }
 
     /// 获取列表长度
     pub fn length(&self) -> Option<usize> {
         self.to_vec().map(|v| v.len())
     }
+
+    /// 数字的规范键（见 [`NumericKey`]）；不是数字时返回 None
+    pub fn numeric_key(&self) -> Option<NumericKey> {
+        match self {
+            Value::Integer(n) => Some(NumericKey::Exact(*n)),
+            Value::Float(f) if f.is_nan() => Some(NumericKey::Inexact(f64::NAN.to_bits())),
+            Value::Float(f) => Some(NumericKey::Inexact(f.to_bits())),
+            _ => None,
+        }
+    }
+
+    /// eqv? 比较：同类型的数字（按规范键）、字符、布尔值、符号、空表按值比较，
+    /// 其余对象仅在为同一对象时相等
+    pub fn is_eqv(&self, other: &Value) -> bool {
+        match (self.numeric_key(), other.numeric_key()) {
+            (Some(a), Some(b)) => a == b,
+            (None, None) => self.is_eq(other),
+            _ => false,
+        }
+    }
+
+    /// equal? 比较：递归比较序对和向量的元素，字符串（可变或不可变）按内容比较，
+    /// 其余值按 eqv? 比较（因此 `(equal? 2 2.0)` 为假）
+    ///
+    /// 使用显式工作栈而非递归，长列表不会耗尽调用栈；记录已比较过的序对/向量组合，
+    /// 再次遇到时视为相等，因此循环结构的比较总会终止
+    pub fn is_equal(&self, other: &Value) -> bool {
+        let mut visited = std::collections::HashSet::new();
+        let mut pending = vec![(self.clone(), other.clone())];
+        while let Some((a, b)) = pending.pop() {
+            match (&a, &b) {
+                (Value::Cons(a_car, a_cdr, _), Value::Cons(b_car, b_cdr, _)) => {
+                    if visited.insert((Rc::as_ptr(a_car), Rc::as_ptr(b_car))) {
+                        pending.push((a_cdr.borrow().clone(), b_cdr.borrow().clone()));
+                        pending.push((a_car.borrow().clone(), b_car.borrow().clone()));
+                    }
+                },
+                (Value::Vector(a_items, _), Value::Vector(b_items, _)) => {
+                    if visited.insert((Rc::as_ptr(a_items).cast(), Rc::as_ptr(b_items).cast())) {
+                        let (a_items, b_items) = (a_items.borrow(), b_items.borrow());
+                        if a_items.len() != b_items.len() {
+                            return false;
+                        }
+                        pending.extend(a_items.iter().cloned().zip(b_items.iter().cloned()).rev());
+                    }
+                },
+                _ => match (a.string_contents(), b.string_contents()) {
+                    (Some(a), Some(b)) => if a != b { return false },
+                    _ => if !a.is_eqv(&b) { return false },
+                },
+            }
+        }
+        true
+    }
+
+    /// eq? 比较：符号、布尔值、字符、空表和整数按值比较，其余对象仅在为同一对象时相等
+    ///
+    /// 符号已驻留，整数不装箱，因此所有相等的整数（不限于小整数）都 eq?；浮点数从不 eq?（用 eqv?）。
+    /// 字符串、序对、向量等对象以及 lambda 闭包按身份比较
+    pub fn is_eq(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Nil, Value::Nil) | (Value::Eof, Value::Eof) | (Value::Default, Value::Default)
+            | (Value::Unspecified, Value::Unspecified) => true,
+            (Value::Bool(a), Value::Bool(b)) => a == b,
+            (Value::Integer(a), Value::Integer(b)) => a == b,
+            (Value::Char(a), Value::Char(b)) => a == b,
+            (Value::Symbol(a), Value::Symbol(b)) => a == b,
+            (Value::Cons(a, _, _), Value::Cons(b, _, _)) => Rc::ptr_eq(a, b),
+            (Value::BuiltinFunction { name: a, .. }, Value::BuiltinFunction { name: b, .. }) => a == b,
+            (Value::EvaluatorFunction { name: a, .. }, Value::EvaluatorFunction { name: b, .. }) => a == b,
+            (Value::Vector(a, _), Value::Vector(b, _)) => Rc::ptr_eq(a, b),
+            (Value::String(a), Value::String(b)) => Rc::ptr_eq(a, b),
+            (Value::MutableString(a), Value::MutableString(b)) => Rc::ptr_eq(a, b),
+            (Value::Lambda { body: a, env_id: env_a, .. }, Value::Lambda { body: b, env_id: env_b, .. }) => {
+                Rc::ptr_eq(a, b) && env_a == env_b
+            },
+            (Value::Port(a), Value::Port(b)) => Rc::ptr_eq(a, b),
+            (Value::Record { fields: a, .. }, Value::Record { fields: b, .. }) => Rc::ptr_eq(a, b),
+            (Value::RecordProcedure(a), Value::RecordProcedure(b)) => Rc::ptr_eq(a, b),
+            (Value::Object(a), Value::Object(b)) => Rc::ptr_eq(a, b),
+            (Value::Parameter(a), Value::Parameter(b)) => Rc::ptr_eq(a, b),
+            (Value::Promise(a), Value::Promise(b)) => Rc::ptr_eq(a, b),
+            (Value::HashTable(a), Value::HashTable(b)) => Rc::ptr_eq(a, b),
+            (Value::Environment(a), Value::Environment(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// 字符串（可变或不可变）的内容；不是字符串时返回 None
+    pub fn string_contents(&self) -> Option<std::borrow::Cow<'_, str>> {
+        match self {
+            Value::String(s) => Some(std::borrow::Cow::Borrowed(s)),
+            Value::MutableString(s) => Some(std::borrow::Cow::Owned(s.borrow().clone())),
+            _ => None,
+        }
+    }
+
+    /// display 形式的字符串表示（字符串和字符不加引号与转义，其余同 write 形式）
+    pub fn to_display_string(&self) -> String {
+        printer::print(self, printer::Style::Display, printer::Labels::Cycles)
+    }
+
+    /// 创建错误对象（error 过程与被异常处理器捕获的内置错误所产生的条件对象）
+    pub fn error_object(message: Value, irritants: Value) -> Value {
+        Value::Record {
+            record_type: RecordType::error_object(),
+            fields: Rc::new(RefCell::new(vec![
+                ("message".to_string(), message),
+                ("irritants".to_string(), irritants),
+            ])),
+        }
+    }
+
+    /// 错误对象的消息与附加对象列表；不是错误对象时返回 None
+    pub fn error_object_parts(&self) -> Option<(Value, Value)> {
+        match self {
+            Value::Record { record_type, fields } if Rc::ptr_eq(record_type, &RecordType::error_object()) => {
+                let fields = fields.borrow();
+                Some((fields[0].1.clone(), fields[1].1.clone()))
+            },
+            _ => None,
+        }
+    }
+
+    /// write 形式的字符串表示。对由数字、字符串、符号、字符、布尔值、空表、序对和向量
+    /// 构成的无环数据，结果经 Parser 读回后与原值 equal?；过程、端口等不可读的值除外
+    pub fn write_string(&self) -> String {
+        printer::print(self, printer::Style::Write, printer::Labels::Cycles)
+    }
+}
+
+/// 以带引号和转义的形式输出字符串，与词法分析器的转义规则一致
+fn write_string_literal(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\t' => f.write_str("\\t")?,
+            '\r' => f.write_str("\\r")?,
+            _ => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+/// 符号名按原样输出后能否被词法分析器读回为同一符号；不能时需用 |...| 包围
+fn symbol_needs_bars(name: &str) -> bool {
+    let mut chars = name.chars();
+    let Some(first) = chars.next() else {
+        return true;
+    };
+    let second = chars.next();
+    let starts_ok = match first {
+        '-' => !second.is_some_and(|c| c.is_ascii_digit()),
+        '.' => second == Some('.'),
+        // # 开头的名字可能被读作布尔值或 #! 对象
+        '#' => false,
+        c => c.is_alphabetic() || "+*/<>=!?_".contains(c),
+    };
+    let chars_ok = name.chars().all(|c| c.is_alphanumeric() || "!$%&*+-./:<=>?@^_~#".contains(c));
+    let is_number = matches!(name, "+inf.0" | "-inf.0" | "+nan.0" | "-nan.0");
+    !starts_ok || !chars_ok || is_number
 }
 
 impl fmt::Display for Value {
@@ -178,42 +508,124 @@ impl fmt::Display for Value {
             Value::Bool(true) => write!(f, "#t"),
             Value::Bool(false) => write!(f, "#f"),
             Value::Integer(n) => write!(f, "{n}"),
-            Value::Float(n) => write!(f, "{n}"),
-            Value::String(s) => write!(f, "\"{s}\""),
-            Value::Symbol(s) => write!(f, "{s}"),
-            Value::Cons(_, _) => {
-                // 打印列表形式
-                if let Some(vec) = self.to_vec() {
-                    write!(f, "(")?;
-                    for (i, val) in vec.iter().enumerate() {
-                        if i > 0 { write!(f, " ")?; }
-                        write!(f, "{val}")?;
+            Value::Float(n) if n.is_nan() => write!(f, "+nan.0"),
+            Value::Float(n) if n.is_infinite() => write!(f, "{}inf.0", if *n > 0.0 { '+' } else { '-' }),
+            // Debug 形式总带小数点或指数，读回后仍是浮点数
+            Value::Float(n) => write!(f, "{n:?}"),
+            Value::String(s) => write_string_literal(f, s),
+            Value::MutableString(s) => write_string_literal(f, &s.borrow()),
+            Value::Symbol(s) if symbol_needs_bars(s) => {
+                f.write_char('|')?;
+                for c in s.chars() {
+                    if c == '|' || c == '\\' {
+                        f.write_char('\\')?;
                     }
-                    write!(f, ")")
-                } else {
-                    // 非正常列表（dotted pair）
-                    write!(f, "({} . {})", self.car().unwrap(), self.cdr().unwrap())
+                    f.write_char(c)?;
                 }
+                f.write_char('|')
+            },
+            Value::Symbol(s) => write!(f, "{s}"),
+            Value::Char(c) => match c {
+                ' ' => write!(f, "#\\space"),
+                '\n' => write!(f, "#\\newline"),
+                '\t' => write!(f, "#\\tab"),
+                '\r' => write!(f, "#\\return"),
+                '\0' => write!(f, "#\\nul"),
+                '\x7f' => write!(f, "#\\delete"),
+                '\x1b' => write!(f, "#\\escape"),
+                '\x07' => write!(f, "#\\alarm"),
+                '\x08' => write!(f, "#\\backspace"),
+                c if c.is_control() => write!(f, "#\\x{:x}", *c as u32),
+                _ => write!(f, "#\\{c}"),
+            },
+            // 复合数据经由打印器输出，循环结构使用数据标签
+            Value::Vector(_, _) | Value::Cons(_, _, _) | Value::Record { .. } => {
+                f.write_str(&printer::print(self, printer::Style::Write, printer::Labels::Cycles))
+            },
+            Value::Eof => write!(f, "#<eof>"),
+            Value::Default => write!(f, "#!default"),
+            Value::Unspecified => write!(f, "#<unspecified>"),
+            Value::Environment(id) => write!(f, "#<environment:{id}>"),
+            Value::Port(port) => match &*port.borrow() {
+                Port::Input(_) => write!(f, "#<input-port>"),
+                Port::Output(_) | Port::File(_) => write!(f, "#<output-port>"),
+                Port::Closed => write!(f, "#<closed-port>"),
+            },
+            Value::RecordProcedure(procedure) => write!(f, "#<procedure:{}>", procedure.name()),
+            Value::Object(_) => write!(f, "#<object>"),
+            Value::Parameter(_) => write!(f, "#<parameter>"),
+            Value::Promise(_) => write!(f, "#<promise>"),
+            Value::HashTable(table) => write!(f, "#<hash-table:{}>", table.borrow().len()),
+            Value::MultipleValues(values) => {
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 { write!(f, " ")?; }
+                    write!(f, "{value}")?;
+                }
+                Ok(())
             },
             Value::BuiltinFunction { name, .. } => write!(f, "#<builtin:{name}>"),
+            Value::EvaluatorFunction { name, .. } => write!(f, "#<builtin:{name}>"),
             Value::Lambda { .. } => write!(f, "#<procedure>"),
         }
     }
 }
 
 impl Value {
+    /// 是否可以作为过程调用
+    pub fn is_procedure(&self) -> bool {
+        self.type_name() == "procedure"
+    }
+
+    /// 可调用值接受的参数个数；不可调用的值返回 None
+    pub fn arity(&self) -> Option<Arity> {
+        match self {
+            Value::BuiltinFunction { arity, .. } | Value::EvaluatorFunction { arity, .. } => Some(*arity),
+            Value::Lambda { params, .. } => Some(Arity::Exact(params.len())),
+            Value::RecordProcedure(procedure) => Some(procedure.arity()),
+            Value::Parameter(_) => Some(Arity::Exact(0)),
+            Value::Object(_) => Some(Arity::AtLeast(1)),
+            _ => None,
+        }
+    }
+
+    /// 运行时类型名（type-of 的结果）
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Nil => "null",
+            Value::Bool(_) => "boolean",
+            Value::Integer(_) => "integer",
+            Value::Float(_) => "float",
+            Value::String(_) | Value::MutableString(_) => "string",
+            Value::Symbol(_) => "symbol",
+            Value::Char(_) => "char",
+            Value::Cons(_, _, _) => "pair",
+            Value::Vector(_, _) => "vector",
+            Value::BuiltinFunction { .. } | Value::EvaluatorFunction { .. } | Value::Lambda { .. }
+            | Value::RecordProcedure(_) | Value::Object(_) | Value::Parameter(_) => "procedure",
+            Value::Eof => "eof-object",
+            Value::Default => "default-object",
+            Value::Unspecified => "unspecified",
+            Value::Environment(_) => "environment",
+            Value::Port(_) => "port",
+            Value::Record { .. } => "record",
+            Value::Promise(_) => "promise",
+            Value::HashTable(_) => "hash-table",
+            Value::MultipleValues(_) => "values",
+        }
+    }
+
     /// 获取 cons 对的 car
-    pub fn car(&self) -> Option<&Value> {
+    pub fn car(&self) -> Option<Value> {
         match self {
-            Value::Cons(car, _) => Some(car),
+            Value::Cons(car, _, _) => Some(car.borrow().clone()),
             _ => None,
         }
     }
 
     /// 获取 cons 对的 cdr
-    pub fn cdr(&self) -> Option<&Value> {
+    pub fn cdr(&self) -> Option<Value> {
         match self {
-            Value::Cons(_, cdr) => Some(cdr),
+            Value::Cons(_, cdr, _) => Some(cdr.borrow().clone()),
             _ => None,
         }
     }
@@ -234,11 +646,24 @@ pub enum SchemeError {
     ArityError(String, Option<Position>),
     /// 除零错误
     DivisionByZero(Option<Position>),
+    /// 断言失败
+    AssertionError(String, Option<Position>),
+    /// 文件系统等 I/O 错误
+    IoError(String),
+    /// 程序请求退出（由 exit 产生，传播到顶层后以该状态码结束进程）
+    Exit(i32),
+    /// 由 raise / raise-continuable 抛出、尚未被处理的条件对象
+    Raised {
+        payload: Value,
+        continuable: bool,
+    },
     /// 带调用栈的运行时错误
     RuntimeErrorWithCallStack {
         message: String,
         position: Option<Position>,
         call_stack: Option<String>,
+        /// 附加调用栈之前的原始错误，错误对象由它生成
+        cause: Option<Box<SchemeError>>,
     },
 }
 
@@ -252,6 +677,11 @@ impl PartialEq for SchemeError {
             (SchemeError::UndefinedVariable(a, ap), SchemeError::UndefinedVariable(b, bp)) => a == b && ap == bp,
             (SchemeError::ArityError(a, ap), SchemeError::ArityError(b, bp)) => a == b && ap == bp,
             (SchemeError::DivisionByZero(ap), SchemeError::DivisionByZero(bp)) => ap == bp,
+            (SchemeError::AssertionError(a, ap), SchemeError::AssertionError(b, bp)) => a == b && ap == bp,
+            (SchemeError::IoError(a), SchemeError::IoError(b)) => a == b,
+            (SchemeError::Exit(a), SchemeError::Exit(b)) => a == b,
+            (SchemeError::Raised { payload: a, continuable: ac }, SchemeError::Raised { payload: b, continuable: bc }) =>
+                a == b && ac == bc,
             (SchemeError::RuntimeErrorWithCallStack { message: a, position: ap, .. }, 
              SchemeError::RuntimeErrorWithCallStack { message: b, position: bp, .. }) => a == b && ap == bp,
             _ => false,
@@ -304,7 +734,27 @@ impl fmt::Display for SchemeError {
                     write!(f, "Division by zero")
                 }
             },
-            SchemeError::RuntimeErrorWithCallStack { message, position, call_stack } => {
+            SchemeError::AssertionError(msg, pos) => {
+                if let Some(pos) = pos {
+                    write!(f, "Assertion Error at {}: {}", pos, msg)
+                } else {
+                    write!(f, "Assertion Error: {}", msg)
+                }
+            },
+            SchemeError::IoError(msg) => write!(f, "IO Error: {}", msg),
+            SchemeError::Exit(code) => write!(f, "Exit with status {}", code),
+            SchemeError::Raised { payload, .. } => match payload.error_object_parts() {
+                // 未被处理的错误对象按 error 的惯例输出消息和附加对象
+                Some((message, irritants)) => {
+                    write!(f, "Error: {}", message.to_display_string())?;
+                    for irritant in irritants.to_vec().unwrap_or_default() {
+                        write!(f, " {irritant}")?;
+                    }
+                    Ok(())
+                },
+                None => write!(f, "Uncaught exception: {}", payload),
+            },
+            SchemeError::RuntimeErrorWithCallStack { message, position, call_stack, .. } => {
                 if let Some(pos) = position {
                     write!(f, "Runtime Error at {}: {}", pos, message)?;
                 } else {
@@ -323,7 +773,55 @@ impl fmt::Display for SchemeError {
 
 impl std::error::Error for SchemeError {}
 
+impl From<std::io::Error> for SchemeError {
+    fn from(error: std::io::Error) -> Self {
+        SchemeError::IoError(error.to_string())
+    }
+}
+
 impl SchemeError {
+    /// 错误的源码位置（如果有）
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            SchemeError::SyntaxError(_, pos)
+            | SchemeError::RuntimeError(_, pos)
+            | SchemeError::TypeError(_, pos)
+            | SchemeError::UndefinedVariable(_, pos)
+            | SchemeError::ArityError(_, pos)
+            | SchemeError::DivisionByZero(pos)
+            | SchemeError::AssertionError(_, pos)
+            | SchemeError::RuntimeErrorWithCallStack { position: pos, .. } => *pos,
+            SchemeError::IoError(_) | SchemeError::Exit(_) | SchemeError::Raised { .. } => None,
+        }
+    }
+
+    /// 不含位置与调用栈的错误消息（错误对象的 error-object-message）
+    pub fn message(&self) -> String {
+        match self {
+            SchemeError::SyntaxError(msg, _)
+            | SchemeError::RuntimeError(msg, _)
+            | SchemeError::TypeError(msg, _)
+            | SchemeError::ArityError(msg, _)
+            | SchemeError::AssertionError(msg, _)
+            | SchemeError::IoError(msg)
+            | SchemeError::RuntimeErrorWithCallStack { message: msg, .. } => msg.clone(),
+            SchemeError::UndefinedVariable(var, _) => format!("Undefined Variable: {var}"),
+            SchemeError::DivisionByZero(_) => "Division by zero".to_string(),
+            SchemeError::Exit(code) => format!("Exit with status {code}"),
+            SchemeError::Raised { payload, .. } => payload.to_string(),
+        }
+    }
+
+    /// 内置错误对应的错误对象；raise 抛出的条件对象原样返回。
+    /// 附加了调用栈的错误按原始错误生成，消息不含 "Type Error: " 等前缀
+    pub fn to_condition(&self) -> Value {
+        match self {
+            SchemeError::Raised { payload, .. } => payload.clone(),
+            SchemeError::RuntimeErrorWithCallStack { cause: Some(cause), .. } => cause.to_condition(),
+            other => Value::error_object(Value::string(other.message()), Value::Nil),
+        }
+    }
+
     /// 创建一个带调用栈的运行时错误
     pub fn runtime_error_with_callstack(
         message: String, 
@@ -334,63 +832,458 @@ impl SchemeError {
             message,
             position,
             call_stack,
+            cause: None,
         }
     }
     
     /// 将现有错误转换为带调用栈的错误
     pub fn with_callstack(self, call_stack: String) -> Self {
-        match self {
-            SchemeError::RuntimeError(msg, pos) => 
-                SchemeError::RuntimeErrorWithCallStack {
-                    message: msg,
-                    position: pos,
-                    call_stack: Some(call_stack),
-                },
-            SchemeError::DivisionByZero(pos) => 
-                SchemeError::RuntimeErrorWithCallStack {
-                    message: "Division by zero".to_string(),
-                    position: pos,
-                    call_stack: Some(call_stack),
-                },
-            SchemeError::TypeError(msg, pos) => 
-                SchemeError::RuntimeErrorWithCallStack {
-                    message: format!("Type Error: {}", msg),
-                    position: pos,
-                    call_stack: Some(call_stack),
-                },
-            SchemeError::UndefinedVariable(var, pos) => 
-                SchemeError::RuntimeErrorWithCallStack {
-                    message: format!("Undefined Variable: {}", var),
-                    position: pos,
-                    call_stack: Some(call_stack),
-                },
-            SchemeError::ArityError(msg, pos) => 
-                SchemeError::RuntimeErrorWithCallStack {
-                    message: format!("Arity Error: {}", msg),
-                    position: pos,
-                    call_stack: Some(call_stack),
-                },
-            other => other, // 保持其他错误类型不变
+        let message = match &self {
+            SchemeError::RuntimeError(msg, _) => msg.clone(),
+            SchemeError::DivisionByZero(_) => "Division by zero".to_string(),
+            SchemeError::TypeError(msg, _) => format!("Type Error: {}", msg),
+            SchemeError::UndefinedVariable(var, _) => format!("Undefined Variable: {}", var),
+            SchemeError::ArityError(msg, _) => format!("Arity Error: {}", msg),
+            _ => return self, // 保持其他错误类型不变
+        };
+        SchemeError::RuntimeErrorWithCallStack {
+            message,
+            position: self.position(),
+            call_stack: Some(call_stack),
+            cause: Some(Box::new(self)),
         }
     }
 }
 
 pub type Result<T> = std::result::Result<T, SchemeError>;
 
+/// 与 eqv? 一致的数字键：精确整数与非精确浮点数互不相等（1 与 1.0 不同），
+/// 浮点数按位比较（0.0 与 -0.0 不同），所有 NaN 视为同一个键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NumericKey {
+    Exact(i64),
+    Inexact(u64),
+}
+
 impl PartialEq for Value {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Value::Nil, Value::Nil) => true,
             (Value::Bool(a), Value::Bool(b)) => a == b,
             (Value::Integer(a), Value::Integer(b)) => a == b,
-            (Value::Float(a), Value::Float(b)) => a == b,
+            // 数字按 eqv? 的规范键比较，NaN 与自身相等，满足 Eq 的自反性
+            (Value::Float(_), Value::Float(_)) => self.numeric_key() == other.numeric_key(),
             (Value::String(a), Value::String(b)) => a == b,
+            (Value::MutableString(a), Value::MutableString(b)) => Rc::ptr_eq(a, b) || *a.borrow() == *b.borrow(),
+            (Value::String(a), Value::MutableString(b)) | (Value::MutableString(b), Value::String(a)) => **a == *b.borrow(),
             (Value::Symbol(a), Value::Symbol(b)) => a == b,
-            (Value::Cons(a1, a2), Value::Cons(b1, b2)) => a1 == b1 && a2 == b2,
+            (Value::Char(a), Value::Char(b)) => a == b,
+            (Value::Eof, Value::Eof) => true,
+            (Value::Default, Value::Default) => true,
+            (Value::Unspecified, Value::Unspecified) => true,
+            (Value::Environment(a), Value::Environment(b)) => a == b,
+            (Value::Port(a), Value::Port(b)) => Rc::ptr_eq(a, b),
+            (Value::Record { fields: a, .. }, Value::Record { fields: b, .. }) => Rc::ptr_eq(a, b),
+            (Value::RecordProcedure(a), Value::RecordProcedure(b)) => Rc::ptr_eq(a, b),
+            (Value::Object(a), Value::Object(b)) => Rc::ptr_eq(a, b),
+            (Value::Parameter(a), Value::Parameter(b)) => Rc::ptr_eq(a, b),
+            (Value::Promise(a), Value::Promise(b)) => Rc::ptr_eq(a, b),
+            (Value::HashTable(a), Value::HashTable(b)) => Rc::ptr_eq(a, b),
+            (Value::Cons(a1, a2, _), Value::Cons(b1, b2, _)) => *a1.borrow() == *b1.borrow() && *a2.borrow() == *b2.borrow(),
+            (Value::Vector(a, _), Value::Vector(b, _)) => Rc::ptr_eq(a, b) || *a.borrow() == *b.borrow(),
             (Value::BuiltinFunction { name: n1, .. }, Value::BuiltinFunction { name: n2, .. }) => n1 == n2,
-            // Lambda functions are compared by identity (always false for different instances)
-            (Value::Lambda { .. }, Value::Lambda { .. }) => false,
+            (Value::EvaluatorFunction { name: n1, .. }, Value::EvaluatorFunction { name: n2, .. }) => n1 == n2,
+            (Value::MultipleValues(a), Value::MultipleValues(b)) => a == b,
+            // 闭包按身份比较（同一函数体与同一环境），保证自反性
+            (Value::Lambda { body: a, env_id: env_a, .. }, Value::Lambda { body: b, env_id: env_b, .. }) => {
+                Rc::ptr_eq(a, b) && env_a == env_b
+            },
             _ => false,
         }
     }
 }
+
+// Value 作为 HashMap 键时的相等性沿用 PartialEq（即 equal? 语义）
+impl Eq for Value {}
+
+/// 逐个拆开列表的脊来释放序对，避免长列表在 Rc 的递归析构中栈溢出；
+/// 只有独占的 cdr 才被拆开，与其他值共享的尾部保持不变
+impl Drop for Value {
+    fn drop(&mut self) {
+        let Value::Cons(_, cdr, _) = self else {
+            return;
+        };
+        let mut next = take_unique(cdr);
+        while let Some(mut value) = next {
+            next = match &mut value {
+                Value::Cons(_, cdr, _) => take_unique(cdr),
+                _ => None,
+            };
+        }
+    }
+}
+
+/// 单元格只被这一个 Rc 持有时取出其中的值，留下空表
+fn take_unique(cell: &mut Rc<RefCell<Value>>) -> Option<Value> {
+    Rc::get_mut(cell).map(|cell| std::mem::replace(cell.get_mut(), Value::Nil))
+}
+
+/// 与 PartialEq（equal? 语义）一致的哈希
+///
+/// 可哈希的规范子集为：空表、布尔值、整数、浮点数、字符串、符号、字符，
+/// 以及由它们构成的 cons 树（见 [`Value::is_hashable`]）。
+/// 数字按 [`NumericKey`] 哈希：`1` 与 `1.0`、`0.0` 与 `-0.0` 互不相同，所有 NaN 哈希相同。
+/// 其他值（过程、端口、记录等）按身份哈希，不应作为哈希表的键。
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // 沿 cdr 链迭代，避免长列表递归过深
+        let mut current = self.clone();
+        loop {
+            // 可变字符串与内容相同的不可变字符串相等，因此按字符串哈希
+            match &current {
+                Value::MutableString(_) => std::mem::discriminant(&Value::string(String::new())).hash(state),
+                other => std::mem::discriminant(other).hash(state),
+            }
+            match &current {
+                Value::Cons(car, cdr, _) => {
+                    car.borrow().hash(state);
+                    let next = cdr.borrow().clone();
+                    current = next;
+                },
+                Value::Nil | Value::Eof | Value::Default | Value::Unspecified | Value::Lambda { .. } => break,
+                Value::Bool(b) => break b.hash(state),
+                Value::Integer(_) | Value::Float(_) => break current.numeric_key().hash(state),
+                Value::String(s) => break s.hash(state),
+                Value::MutableString(s) => break s.borrow().hash(state),
+                Value::Symbol(s) => break s.as_str().hash(state),
+                Value::Char(c) => break c.hash(state),
+                Value::Environment(id) => break id.hash(state),
+                Value::Vector(items, _) => break items.borrow().hash(state),
+                Value::BuiltinFunction { name, .. } | Value::EvaluatorFunction { name, .. } => break name.hash(state),
+                Value::Port(port) => break Rc::as_ptr(port).hash(state),
+                Value::Record { fields, .. } => break Rc::as_ptr(fields).hash(state),
+                Value::RecordProcedure(procedure) => break Rc::as_ptr(procedure).hash(state),
+                Value::Object(object) => break Rc::as_ptr(object).hash(state),
+                Value::Parameter(parameter) => break Rc::as_ptr(parameter).hash(state),
+                Value::Promise(promise) => break Rc::as_ptr(promise).hash(state),
+                Value::HashTable(table) => break Rc::as_ptr(table).hash(state),
+                Value::MultipleValues(values) => break values.hash(state),
+            }
+        }
+    }
+}
+
+impl Value {
+    /// 由序对构成的结构中是否有环，即沿 car 或 cdr 能回到正在访问的序对；
+    /// 共享而不成环的子结构不算。使用显式工作栈，长列表不会耗尽调用栈
+    pub fn has_pair_cycle(&self) -> bool {
+        // Some(pair) 表示进入该序对，None 表示离开最近进入的序对
+        let mut pending = vec![Some(self.clone())];
+        let mut path: Vec<*const RefCell<Value>> = Vec::new();
+        let mut on_path = std::collections::HashSet::new();
+        let mut finished = std::collections::HashSet::new();
+        while let Some(step) = pending.pop() {
+            let Some(value) = step else {
+                if let Some(pair) = path.pop() {
+                    on_path.remove(&pair);
+                    finished.insert(pair);
+                }
+                continue;
+            };
+            if let Value::Cons(car, cdr, _) = &value {
+                let pair = Rc::as_ptr(car);
+                if on_path.contains(&pair) {
+                    return true;
+                }
+                if finished.contains(&pair) {
+                    continue;
+                }
+                path.push(pair);
+                on_path.insert(pair);
+                pending.push(None);
+                pending.push(Some(cdr.borrow().clone()));
+                pending.push(Some(car.borrow().clone()));
+            }
+        }
+        false
+    }
+
+    /// 是否属于可作为 equal? 哈希表键的规范子集（调用前应排除有环的结构）
+    pub fn is_hashable(&self) -> bool {
+        let mut current = self.clone();
+        loop {
+            match &current {
+                Value::Cons(car, cdr, _) => {
+                    if !car.borrow().is_hashable() {
+                        return false;
+                    }
+                    let next = cdr.borrow().clone();
+                    current = next;
+                },
+                Value::Nil | Value::Bool(_) | Value::Integer(_) | Value::Float(_)
+                | Value::String(_) | Value::Symbol(_) | Value::Char(_) => return true,
+                _ => return false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_dotted_tail() {
+        // (1 . 2)
+        let pair = Value::cons(Value::Integer(1), Value::Integer(2));
+        assert_eq!(pair.to_string(), "(1 . 2)");
+
+        // (1 2 . 3)
+        let improper = Value::cons(Value::Integer(1), Value::cons(Value::Integer(2), Value::Integer(3)));
+        assert_eq!(improper.to_string(), "(1 2 . 3)");
+
+        // 正常列表和嵌套的点对
+        let nested = Value::from_vec(vec![Value::Integer(1), pair]);
+        assert_eq!(nested.to_string(), "(1 (1 . 2))");
+    }
+
+    fn hash_of(value: &Value) -> u64 {
+        use std::collections::hash_map::DefaultHasher;
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_equal_lists_hash_identically() {
+        let a = Value::from_vec(vec![Value::Integer(1), Value::string("x"), Value::from_vec(vec![Value::Char('c')])]);
+        let b = Value::from_vec(vec![Value::Integer(1), Value::string("x"), Value::from_vec(vec![Value::Char('c')])]);
+        assert_eq!(a, b);
+        assert_eq!(hash_of(&a), hash_of(&b));
+
+        // 与 eqv? 一致：浮点数的 0.0 与 -0.0 不同，NaN 与自身相等
+        assert_ne!(Value::Float(0.0), Value::Float(-0.0));
+        assert_eq!(Value::Float(f64::NAN), Value::Float(f64::NAN));
+        assert_eq!(hash_of(&Value::Float(f64::NAN)), hash_of(&Value::Float(-f64::NAN)));
+
+        // 可以作为 HashMap 的键
+        let mut map = std::collections::HashMap::new();
+        map.insert(a, 1);
+        assert_eq!(map.get(&b), Some(&1));
+    }
+
+    #[test]
+    fn test_long_list_drop_and_display() {
+        // 一百万个序对的列表：析构、打印和转换都不会栈溢出
+        let list = Value::from_vec((0..1_000_000).map(Value::Integer).collect());
+        assert_eq!(list.to_vec().map(|items| items.len()), Some(1_000_000));
+        assert!(list.to_string().ends_with(" 999998 999999)"));
+        drop(list);
+
+        // 与其他值共享的尾部在析构后保持完整
+        let list = Value::from_vec((0..1_000).map(Value::Integer).collect());
+        let tail = list.last_pair().unwrap();
+        drop(list);
+        assert_eq!(tail.to_string(), "(999)");
+    }
+
+    #[test]
+    fn test_numeric_keys_distinguish_exactness() {
+        assert_eq!(Value::Integer(1).numeric_key(), Value::Integer(1).numeric_key());
+        assert_ne!(Value::Integer(1).numeric_key(), Value::Float(1.0).numeric_key());
+        assert!(Value::symbol("a").numeric_key().is_none());
+
+        // 1 与 1.0 作为键是两个独立的条目，与 eqv? 一致
+        let mut table = HashTable::new();
+        table.insert(Value::Integer(1), Value::symbol("exact"));
+        table.insert(Value::Float(1.0), Value::symbol("inexact"));
+        table.insert(Value::Integer(1), Value::symbol("exact-again"));
+        table.insert(Value::Float(f64::NAN), Value::symbol("nan"));
+        assert_eq!(table.len(), 3);
+        assert_eq!(table.get(&Value::Integer(1)), Some(&Value::symbol("exact-again")));
+        assert_eq!(table.get(&Value::Float(1.0)), Some(&Value::symbol("inexact")));
+        assert_eq!(table.get(&Value::Float(f64::NAN)), Some(&Value::symbol("nan")));
+        assert!(!Value::Integer(1).is_eqv(&Value::Float(1.0)));
+        assert!(!Value::Float(0.0).is_eqv(&Value::Float(-0.0)));
+    }
+
+    #[test]
+    fn test_is_equal_nested_and_circular() {
+        let string = |s: &str| Value::string(s.to_string());
+        let vector = |items: Vec<Value>| Value::Vector(Rc::new(RefCell::new(items)), false);
+        let nested = || vector(vec![Value::Integer(1), Value::from_vec(vec![string("a"), vector(vec![])])]);
+
+        // 向量中包含含有字符串的列表
+        assert!(nested().is_equal(&nested()));
+        let different = vector(vec![Value::Integer(1), Value::from_vec(vec![string("b"), vector(vec![])])]);
+        assert!(!nested().is_equal(&different));
+        assert!(!nested().is_equal(&vector(vec![Value::Integer(1)])));
+
+        // 字符串按内容比较，数字不跨精确性
+        let mutable = Value::MutableString(Rc::new(RefCell::new("a".to_string())));
+        assert!(string("a").is_equal(&mutable));
+        assert!(!Value::Integer(2).is_equal(&Value::Float(2.0)));
+        assert!(Value::Float(f64::NAN).is_equal(&Value::Float(f64::NAN)));
+
+        // 两个独立构造的循环列表 #0=(1 2 . #0#)
+        let circular = || {
+            let list = Value::from_vec(vec![Value::Integer(1), Value::Integer(2)]);
+            if let Some(Value::Cons(_, cdr, _)) = &list.last_pair() {
+                *cdr.borrow_mut() = list.clone();
+            }
+            list
+        };
+        assert!(circular().is_equal(&circular()));
+        let other = Value::from_vec(vec![Value::Integer(1), Value::Integer(3)]);
+        if let Some(Value::Cons(_, cdr, _)) = &other.last_pair() {
+            *cdr.borrow_mut() = other.clone();
+        }
+        assert!(!circular().is_equal(&other));
+    }
+
+    #[test]
+    fn test_is_hashable() {
+        assert!(Value::from_vec(vec![Value::Integer(1), Value::symbol("a")]).is_hashable());
+        assert!(Value::Nil.is_hashable());
+        let procedure = Value::BuiltinFunction { name: "f".to_string(), func: |_| Ok(Value::Nil), arity: Arity::AtLeast(0) };
+        assert!(!procedure.is_hashable());
+        assert!(!Value::from_vec(vec![Value::Integer(1), procedure]).is_hashable());
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        // 前缀长度与环长度各不相同的循环列表都能被检测出
+        for prefix in 0..4 {
+            for cycle in 1..5 {
+                let list = Value::from_vec((0..prefix + cycle).map(Value::Integer).collect());
+                let mut loop_start = list.clone();
+                for _ in 0..prefix {
+                    loop_start = loop_start.cdr().unwrap();
+                }
+                if let Value::Cons(_, cdr, _) = &list.last_pair().unwrap() {
+                    *cdr.borrow_mut() = loop_start;
+                }
+                assert!(!list.is_proper_list(), "prefix {prefix}, cycle {cycle}");
+                assert_eq!(list.to_vec(), None);
+                assert!(list.has_pair_cycle());
+            }
+        }
+
+        // 经 car 成环的结构有环，共享而不成环的结构没有
+        let self_car = Value::from_vec(vec![Value::Integer(1)]);
+        if let Value::Cons(car, _, _) = &self_car {
+            *car.borrow_mut() = self_car.clone();
+        }
+        assert!(self_car.has_pair_cycle());
+        assert!(self_car.is_proper_list());
+        let shared = Value::from_vec(vec![Value::Integer(1)]);
+        assert!(!Value::from_vec(vec![shared.clone(), shared.clone(), shared]).has_pair_cycle());
+        assert!(!Value::from_vec((0..5).map(Value::Integer).collect()).has_pair_cycle());
+    }
+
+    #[test]
+    fn test_proper_list_and_last_pair() {
+        let improper = Value::cons(Value::Integer(1), Value::cons(Value::Integer(2), Value::Integer(3)));
+        assert!(!improper.is_proper_list());
+        assert!(Value::Nil.is_proper_list());
+        assert!(Value::from_vec(vec![Value::Integer(1)]).is_proper_list());
+        assert!(!Value::Integer(1).is_proper_list());
+
+        assert_eq!(improper.last_pair().unwrap().to_string(), "(2 . 3)");
+        let list = Value::from_vec(vec![Value::Integer(1), Value::Integer(2)]);
+        assert_eq!(list.last_pair().unwrap().to_string(), "(2)");
+        assert!(Value::Nil.last_pair().is_none());
+    }
+
+    #[test]
+    fn test_render_snippet() {
+        let source = "(define x 1)\n(car x)\n";
+        let snippet = Position::new(2, 6).render_snippet(source).unwrap();
+        assert_eq!(snippet, "  |\n2 | (car x)\n  |      ^");
+
+        // 插入符号位于第 6 列下方
+        let lines: Vec<&str> = snippet.lines().collect();
+        assert_eq!(lines[2].find('^').unwrap() - 4, lines[1].find('x').unwrap() - 4);
+
+        assert_eq!(Position::new(5, 1).render_snippet(source), None);
+    }
+
+    #[test]
+    fn test_write_string_escapes() {
+        assert_eq!(Value::Float(1.0).write_string(), "1.0");
+        assert_eq!(Value::Float(1e300).write_string(), "1e300");
+        assert_eq!(Value::string("a\"b\\c\n").write_string(), r#""a\"b\\c\n""#);
+        assert_eq!(Value::symbol("a b").write_string(), "|a b|");
+        assert_eq!(Value::symbol("").write_string(), "||");
+        assert_eq!(Value::symbol("1+").write_string(), "|1+|");
+        assert_eq!(Value::symbol("x|y").write_string(), r"|x\|y|");
+        assert_eq!(Value::symbol("+inf.0").write_string(), "|+inf.0|");
+        assert_eq!(Value::symbol("...").write_string(), "...");
+        assert_eq!(Value::symbol("-").write_string(), "-");
+        // display 形式不加竖线
+        assert_eq!(Value::symbol("a b").to_display_string(), "a b");
+    }
+
+    /// 确定性的伪随机数生成器（splitmix64），保证测试可重现
+    struct SplitMix64(u64);
+
+    impl SplitMix64 {
+        fn next(&mut self) -> u64 {
+            self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = self.0;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+
+        fn pick(&mut self, choices: &[char]) -> char {
+            choices[self.below(choices.len() as u64) as usize]
+        }
+    }
+
+    fn random_text(rng: &mut SplitMix64, alphabet: &[char]) -> String {
+        (0..rng.below(6)).map(|_| rng.pick(alphabet)).collect()
+    }
+
+    fn random_value(rng: &mut SplitMix64, depth: usize) -> Value {
+        const TEXT: &[char] = &['a', 'Z', '0', '9', ' ', '"', '\\', '|', '\n', '\t', '\r', '(', '#', '.', '-', '+', ';', 'λ'];
+        let kinds = if depth == 0 { 7 } else { 10 };
+        match rng.below(kinds) {
+            0 => Value::Integer(rng.next() as i64 >> rng.below(64)),
+            1 => {
+                let float = f64::from_bits(rng.next());
+                if float.is_nan() { Value::Float(f64::INFINITY) } else { Value::Float(float) }
+            },
+            2 => Value::string(random_text(rng, TEXT)),
+            3 => Value::symbol(&random_text(rng, TEXT)),
+            4 => Value::Char(rng.pick(&['a', 'Z', '7', ' ', '\n', '\t', '\r', '(', ')', '#', '\\', '|', '"', 'λ'])),
+            5 => Value::Bool(rng.below(2) == 0),
+            6 => Value::Nil,
+            7 => {
+                let items = (0..rng.below(4)).map(|_| random_value(rng, depth - 1)).collect();
+                Value::Vector(Rc::new(RefCell::new(items)), false)
+            },
+            8 => {
+                let car = random_value(rng, depth - 1);
+                Value::cons(car, random_value(rng, depth - 1))
+            },
+            _ => Value::from_vec((0..rng.below(4)).map(|_| random_value(rng, depth - 1)).collect()),
+        }
+    }
+
+    #[test]
+    fn test_write_string_round_trip() {
+        let mut rng = SplitMix64(2161);
+        for _ in 0..2000 {
+            let value = random_value(&mut rng, 3);
+            let written = value.write_string();
+            let read = crate::legacy::parser::Parser::parse(&written)
+                .unwrap_or_else(|e| panic!("failed to read back {written}: {e}"));
+            assert!(read.is_equal(&value), "{written} read back as {}", read.write_string());
+        }
+    }
+}
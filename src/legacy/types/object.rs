@@ -0,0 +1,45 @@
+use crate::legacy::types::{Value, SchemeError, Result, Symbol};
+
+/// make-object 创建的消息传递对象：方法名到方法闭包的关联列表。
+/// 以 (obj 'method arg ...) 调用时，按方法名找到闭包并以其余参数调用它
+#[derive(Debug)]
+pub struct Object {
+    pub methods: Vec<(Symbol, Value)>,
+}
+
+impl Object {
+    /// 解析一次消息调用：返回应调用的方法闭包及其参数
+    pub fn dispatch(&self, mut args: Vec<Value>) -> Result<(Value, Vec<Value>)> {
+        if args.is_empty() {
+            return Err(SchemeError::ArityError("object requires a method name".to_string(), None));
+        }
+
+        let message = args.remove(0);
+        let Value::Symbol(name) = &message else {
+            return Err(SchemeError::TypeError(format!("object expects a method name symbol, got {message}"), None));
+        };
+        match self.methods.iter().find(|(method, _)| method == name) {
+            Some((_, method)) => Ok((method.clone(), args)),
+            None => Err(SchemeError::RuntimeError(format!("object: unknown method {name}"), None)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dispatch_errors() {
+        let object = Object { methods: vec![(Symbol::intern("get"), Value::Integer(0))] };
+
+        // 找到方法时返回其余参数
+        let (method, args) = object.dispatch(vec![Value::symbol("get"), Value::Integer(1)]).unwrap();
+        assert_eq!(method, Value::Integer(0));
+        assert_eq!(args, vec![Value::Integer(1)]);
+
+        assert!(matches!(object.dispatch(vec![]), Err(SchemeError::ArityError(..))));
+        assert!(matches!(object.dispatch(vec![Value::Integer(1)]), Err(SchemeError::TypeError(..))));
+        assert!(matches!(object.dispatch(vec![Value::symbol("set")]), Err(SchemeError::RuntimeError(..))));
+    }
+}
@@ -0,0 +1,10 @@
+use crate::legacy::types::Value;
+
+/// 参数对象（由 make-parameter 创建，可被 parameterize 动态重绑定）
+#[derive(Debug)]
+pub struct Parameter {
+    /// 全局（默认）值，已经过转换过程处理
+    pub value: Value,
+    /// 可选的转换过程，作用于初始值和 parameterize 安装的值
+    pub converter: Option<Value>,
+}
@@ -0,0 +1,88 @@
+use std::fmt;
+
+/// 输入端口：基于缓冲字符源，并带有一个字符的回退缓冲
+pub struct InputPort {
+    /// 字符源
+    source: Box<dyn Iterator<Item = char>>,
+    /// 回退缓冲（已从字符源读出但尚未消费的字符，逆序存放）
+    pushback: Vec<char>,
+}
+
+impl InputPort {
+    /// 从任意字符迭代器创建输入端口
+    pub fn new(source: Box<dyn Iterator<Item = char>>) -> Self {
+        InputPort {
+            source,
+            pushback: Vec::new(),
+        }
+    }
+
+    /// 从字符串创建输入端口
+    pub fn from_string(input: &str) -> Self {
+        let chars: Vec<char> = input.chars().collect();
+        Self::new(Box::new(chars.into_iter()))
+    }
+
+    /// 从标准输入创建输入端口（按行惰性读取）
+    pub fn stdin() -> Self {
+        let lines = std::io::stdin().lines().map_while(|line| line.ok());
+        Self::new(Box::new(lines.flat_map(|line| {
+            line.chars().chain(std::iter::once('\n')).collect::<Vec<char>>()
+        })))
+    }
+
+    /// 查看下一个字符但不消费；None 表示已到达末尾
+    pub fn peek_char(&mut self) -> Option<char> {
+        if self.pushback.is_empty() {
+            self.pushback.extend(self.source.next());
+        }
+        self.pushback.last().copied()
+    }
+
+    /// 读取并消费下一个字符；None 表示已到达末尾
+    pub fn read_char(&mut self) -> Option<char> {
+        self.pushback.pop().or_else(|| self.source.next())
+    }
+
+    /// 将已读出的字符退回端口，之后按原顺序再次读出
+    pub fn unread(&mut self, chars: &[char]) {
+        self.pushback.extend(chars.iter().rev());
+    }
+}
+
+impl fmt::Debug for InputPort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InputPort")
+            .field("pushback", &self.pushback)
+            .finish_non_exhaustive()
+    }
+}
+
+/// 端口
+#[derive(Debug)]
+pub enum Port {
+    /// 输入端口
+    Input(InputPort),
+    /// 字符串输出端口：累积写入的文本
+    Output(String),
+    /// 文件输出端口
+    File(std::io::BufWriter<std::fs::File>),
+    /// 已关闭的端口，不能再读写
+    Closed,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peek_then_read() {
+        let mut port = InputPort::from_string("ab");
+        assert_eq!(port.peek_char(), Some('a'));
+        assert_eq!(port.peek_char(), Some('a'));
+        assert_eq!(port.read_char(), Some('a'));
+        assert_eq!(port.read_char(), Some('b'));
+        assert_eq!(port.peek_char(), None);
+        assert_eq!(port.read_char(), None);
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use crate::legacy::types::Value;
+
+/// 输出形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// write 形式：字符串和字符带引号
+    Write,
+    /// display 形式：字符串和字符原样输出
+    Display,
+}
+
+/// 数据标签策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Labels {
+    /// 不使用标签（write-simple），循环结构会导致无限输出
+    None,
+    /// 仅为循环结构使用标签（write 的默认行为）
+    Cycles,
+    /// 为所有共享结构使用标签（write-shared）
+    Shared,
+}
+
+/// 复合数据（序对、向量、记录）的身份
+type NodeId = *const ();
+
+fn node_id(value: &Value) -> Option<NodeId> {
+    match value {
+        Value::Cons(car, _, _) => Some(Rc::as_ptr(car).cast()),
+        Value::Vector(items, _) => Some(Rc::as_ptr(items).cast()),
+        Value::Record { fields, .. } => Some(Rc::as_ptr(fields).cast()),
+        _ => None,
+    }
+}
+
+/// 将值输出为文本，按 labels 策略为循环或共享结构生成 `#n=` / `#n#` 数据标签
+pub fn print(value: &Value, style: Style, labels: Labels) -> String {
+    let labeled = match labels {
+        Labels::None => HashSet::new(),
+        Labels::Cycles | Labels::Shared => {
+            let mut scanner = Scanner {
+                shared: labels == Labels::Shared,
+                visits: HashMap::new(),
+                labeled: HashSet::new(),
+            };
+            scanner.scan(value);
+            scanner.labeled
+        },
+    };
+
+    let mut printer = Printer {
+        style,
+        labeled,
+        assigned: HashMap::new(),
+        out: String::new(),
+    };
+    printer.print(value);
+    printer.out
+}
+
+/// 遍历状态
+enum Visit {
+    /// 正在遍历其子结构（再次遇到说明存在循环）
+    InProgress,
+    /// 已遍历完毕（再次遇到说明结构被共享）
+    Done,
+}
+
+/// 预扫描：找出需要标签的节点
+struct Scanner {
+    shared: bool,
+    visits: HashMap<NodeId, Visit>,
+    labeled: HashSet<NodeId>,
+}
+
+impl Scanner {
+    fn scan(&mut self, value: &Value) {
+        // 沿 cdr 链迭代，链上的序对在整条链处理完之前都处于遍历中
+        let mut spine = Vec::new();
+        let mut current = value.clone();
+        while let Some(id) = node_id(&current) {
+            match self.visits.get(&id) {
+                Some(Visit::InProgress) => {
+                    self.labeled.insert(id);
+                    break;
+                },
+                Some(Visit::Done) => {
+                    if self.shared {
+                        self.labeled.insert(id);
+                    }
+                    break;
+                },
+                None => {},
+            }
+            self.visits.insert(id, Visit::InProgress);
+            spine.push(id);
+
+            match &current {
+                Value::Cons(car, cdr, _) => {
+                    self.scan(&car.borrow());
+                    let next = cdr.borrow().clone();
+                    current = next;
+                },
+                Value::Vector(items, _) => {
+                    for item in items.borrow().iter() {
+                        self.scan(item);
+                    }
+                    break;
+                },
+                Value::Record { fields, .. } => {
+                    for (_, field) in fields.borrow().iter() {
+                        self.scan(field);
+                    }
+                    break;
+                },
+                _ => break,
+            }
+        }
+        for id in spine {
+            self.visits.insert(id, Visit::Done);
+        }
+    }
+}
+
+struct Printer {
+    style: Style,
+    labeled: HashSet<NodeId>,
+    /// 已输出定义（#n=）的节点及其标签编号
+    assigned: HashMap<NodeId, usize>,
+    out: String,
+}
+
+impl Printer {
+    fn is_labeled(&self, value: &Value) -> bool {
+        node_id(value).is_some_and(|id| self.labeled.contains(&id))
+    }
+
+    fn print(&mut self, value: &Value) {
+        if let Some(id) = node_id(value).filter(|id| self.labeled.contains(id)) {
+            if let Some(label) = self.assigned.get(&id) {
+                self.out.push_str(&format!("#{label}#"));
+                return;
+            }
+            let label = self.assigned.len();
+            self.assigned.insert(id, label);
+            self.out.push_str(&format!("#{label}="));
+        }
+
+        match value {
+            Value::Cons(car, cdr, _) => {
+                // 沿 cdr 链打印元素；末尾不是空表，或下一个序对需要标签时输出点尾
+                self.out.push('(');
+                self.print(&car.borrow());
+                let mut tail = cdr.borrow().clone();
+                loop {
+                    match &tail {
+                        Value::Nil => break,
+                        Value::Cons(car, cdr, _) if !self.is_labeled(&tail) => {
+                            self.out.push(' ');
+                            self.print(&car.borrow());
+                            let next = cdr.borrow().clone();
+                            tail = next;
+                        },
+                        other => {
+                            self.out.push_str(" . ");
+                            self.print(other);
+                            break;
+                        },
+                    }
+                }
+                self.out.push(')');
+            },
+            Value::Vector(items, _) => {
+                self.out.push_str("#(");
+                for (i, item) in items.borrow().iter().enumerate() {
+                    if i > 0 {
+                        self.out.push(' ');
+                    }
+                    self.print(item);
+                }
+                self.out.push(')');
+            },
+            Value::Record { record_type, fields } => {
+                self.out.push_str(&format!("#<{}", record_type.name));
+                for (field, field_value) in fields.borrow().iter() {
+                    self.out.push_str(&format!(" {field}: "));
+                    self.print(field_value);
+                }
+                self.out.push('>');
+            },
+            Value::String(s) if self.style == Style::Display => self.out.push_str(s),
+            Value::MutableString(s) if self.style == Style::Display => self.out.push_str(&s.borrow()),
+            Value::Char(c) if self.style == Style::Display => self.out.push(*c),
+            Value::Symbol(s) if self.style == Style::Display => self.out.push_str(s),
+            other => self.out.push_str(&other.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_and_circular_labels() {
+        // 共享但无循环：write 不加标签，write-shared 加标签
+        let shared = Value::from_vec(vec![Value::Integer(1)]);
+        let outer = Value::from_vec(vec![shared.clone(), shared]);
+        assert_eq!(print(&outer, Style::Write, Labels::Cycles), "((1) (1))");
+        assert_eq!(print(&outer, Style::Write, Labels::Shared), "(#0=(1) #0#)");
+        assert_eq!(print(&outer, Style::Write, Labels::None), "((1) (1))");
+
+        // 包含自身的向量
+        let vector = Value::Vector(Rc::new(std::cell::RefCell::new(vec![Value::Integer(1)])), false);
+        if let Value::Vector(items, _) = &vector {
+            items.borrow_mut().push(vector.clone());
+        }
+        assert_eq!(print(&vector, Style::Write, Labels::Cycles), "#0=#(1 #0#)");
+    }
+}
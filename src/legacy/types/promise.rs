@@ -0,0 +1,37 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::legacy::env::EnvironmentId;
+use crate::legacy::types::Value;
+
+/// 承诺对象的状态（由 delay、delay-force 和 make-promise 创建）
+#[derive(Debug, Clone)]
+pub enum Promise {
+    /// 已求值，保存结果
+    Forced(Value),
+    /// (delay expr)：尚未求值的表达式及其环境
+    Delayed {
+        expr: Value,
+        env_id: EnvironmentId,
+    },
+    /// (delay-force expr)：表达式求值结果为另一个承诺，强制时迭代跟随
+    DelayForce {
+        expr: Value,
+        env_id: EnvironmentId,
+    },
+    /// 与另一个承诺共享状态（R7RS 的 promise-update!）：delay-force 得到内层承诺后转发到它，
+    /// 强制任一方都会为两者记忆结果
+    Shared(Rc<RefCell<Promise>>),
+}
+
+/// 沿 Shared 转发找到真正保存状态的承诺
+pub fn resolve_shared(promise: &Rc<RefCell<Promise>>) -> Rc<RefCell<Promise>> {
+    let mut current = Rc::clone(promise);
+    loop {
+        let next = match &*current.borrow() {
+            Promise::Shared(next) => Rc::clone(next),
+            _ => break,
+        };
+        current = next;
+    }
+    current
+}
@@ -0,0 +1,136 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::legacy::types::{Value, SchemeError, Result, Arity};
+
+/// 记录实例的字段（字段名与值，可变）
+pub type RecordFields = Rc<RefCell<Vec<(String, Value)>>>;
+
+/// 记录类型描述（由 define-record-type 创建）。记录类型按身份区分：
+/// 两次 define-record-type 即使名字相同也得到不同的类型
+#[derive(Debug, PartialEq)]
+pub struct RecordType {
+    pub name: String,
+    pub field_names: Vec<String>,
+}
+
+thread_local! {
+    /// 错误对象的记录类型，error 过程与被捕获的内置错误共用
+    static ERROR_OBJECT_TYPE: Rc<RecordType> = Rc::new(RecordType {
+        name: "error-object".to_string(),
+        field_names: vec!["message".to_string(), "irritants".to_string()],
+    });
+}
+
+impl RecordType {
+    /// 错误对象的记录类型
+    pub fn error_object() -> Rc<RecordType> {
+        ERROR_OBJECT_TYPE.with(Rc::clone)
+    }
+}
+
+/// define-record-type 生成的过程
+#[derive(Debug)]
+pub enum RecordProcedure {
+    /// 构造器，field_indices 为构造器参数对应的字段下标
+    Constructor {
+        name: String,
+        record_type: Rc<RecordType>,
+        field_indices: Vec<usize>,
+    },
+    /// 类型谓词
+    Predicate {
+        name: String,
+        record_type: Rc<RecordType>,
+    },
+    /// 字段访问器
+    Accessor {
+        name: String,
+        record_type: Rc<RecordType>,
+        index: usize,
+    },
+    /// 字段修改器
+    Modifier {
+        name: String,
+        record_type: Rc<RecordType>,
+        index: usize,
+    },
+}
+
+impl RecordProcedure {
+    /// 过程名
+    pub fn name(&self) -> &str {
+        match self {
+            RecordProcedure::Constructor { name, .. }
+            | RecordProcedure::Predicate { name, .. }
+            | RecordProcedure::Accessor { name, .. }
+            | RecordProcedure::Modifier { name, .. } => name,
+        }
+    }
+
+    /// 过程接受的参数个数
+    pub fn arity(&self) -> Arity {
+        match self {
+            RecordProcedure::Constructor { field_indices, .. } => Arity::Exact(field_indices.len()),
+            RecordProcedure::Predicate { .. } | RecordProcedure::Accessor { .. } => Arity::Exact(1),
+            RecordProcedure::Modifier { .. } => Arity::Exact(2),
+        }
+    }
+
+    /// 应用过程
+    pub fn apply(&self, args: &[Value]) -> Result<Value> {
+        match self {
+            RecordProcedure::Constructor { name, record_type, field_indices } => {
+                check_arity(name, field_indices.len(), args)?;
+                let mut fields: Vec<(String, Value)> = record_type.field_names.iter()
+                    .map(|field| (field.clone(), Value::Bool(false)))
+                    .collect();
+                for (index, value) in field_indices.iter().zip(args) {
+                    fields[*index].1 = value.clone();
+                }
+                Ok(Value::Record {
+                    record_type: Rc::clone(record_type),
+                    fields: Rc::new(RefCell::new(fields)),
+                })
+            },
+            RecordProcedure::Predicate { name, record_type } => {
+                check_arity(name, 1, args)?;
+                Ok(Value::Bool(matches!(
+                    &args[0],
+                    Value::Record { record_type: actual, .. } if Rc::ptr_eq(actual, record_type)
+                )))
+            },
+            RecordProcedure::Accessor { name, record_type, index } => {
+                check_arity(name, 1, args)?;
+                let fields = record_fields(name, record_type, &args[0])?;
+                let value = fields.borrow()[*index].1.clone();
+                Ok(value)
+            },
+            RecordProcedure::Modifier { name, record_type, index } => {
+                check_arity(name, 2, args)?;
+                let fields = record_fields(name, record_type, &args[0])?;
+                fields.borrow_mut()[*index].1 = args[1].clone();
+                Ok(Value::Unspecified)
+            },
+        }
+    }
+}
+
+/// 检查参数个数
+fn check_arity(name: &str, expected: usize, args: &[Value]) -> Result<()> {
+    if args.len() != expected {
+        return Err(SchemeError::ArityError(
+            format!("{name} requires exactly {expected} arguments, got {}", args.len()), None
+        ));
+    }
+    Ok(())
+}
+
+/// 检查参数为指定类型的记录并取出其字段
+fn record_fields<'a>(name: &str, record_type: &Rc<RecordType>, value: &'a Value) -> Result<&'a RecordFields> {
+    match value {
+        Value::Record { record_type: actual, fields } if Rc::ptr_eq(actual, record_type) => Ok(fields),
+        other => Err(SchemeError::TypeError(
+            format!("{name} expects a {} record, got {other}", record_type.name), None
+        )),
+    }
+}
@@ -0,0 +1,135 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Deref;
+
+/// 驻留符号：符号表中的编号，比较和哈希只涉及整数
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Symbol(u32);
+
+/// 符号表：符号名与编号的双向映射，符号名在进程生命周期内驻留
+#[derive(Default)]
+struct SymbolTable {
+    ids: HashMap<&'static str, Symbol>,
+    names: Vec<&'static str>,
+}
+
+thread_local! {
+    static SYMBOL_TABLE: RefCell<SymbolTable> = RefCell::new(SymbolTable::default());
+}
+
+impl Symbol {
+    /// 驻留符号名，同名符号总是得到同一个编号
+    pub fn intern(name: &str) -> Symbol {
+        SYMBOL_TABLE.with(|table| {
+            let mut table = table.borrow_mut();
+            if let Some(symbol) = table.ids.get(name) {
+                return *symbol;
+            }
+            let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+            let symbol = Symbol(table.names.len() as u32);
+            table.names.push(name);
+            table.ids.insert(name, symbol);
+            symbol
+        })
+    }
+
+    /// 查找已驻留的符号，不存在时不驻留
+    pub fn lookup(name: &str) -> Option<Symbol> {
+        SYMBOL_TABLE.with(|table| table.borrow().ids.get(name).copied())
+    }
+
+    /// 符号名
+    pub fn as_str(&self) -> &'static str {
+        SYMBOL_TABLE.with(|table| table.borrow().names[self.0 as usize])
+    }
+
+    /// 符号在符号表中的编号
+    pub fn id(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Deref for Symbol {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq<str> for Symbol {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Symbol {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern_identity() {
+        let a = Symbol::intern("interned-symbol");
+        let b = Symbol::intern(&String::from("interned-symbol"));
+        assert_eq!(a, b);
+        assert_eq!(a.id(), b.id());
+        assert_eq!(a.as_str(), "interned-symbol");
+        assert_ne!(a, Symbol::intern("other-symbol"));
+
+        assert_eq!(Symbol::lookup("interned-symbol"), Some(a));
+        assert_eq!(Symbol::lookup("never-interned-symbol"), None);
+    }
+
+    /// 统计写入哈希器的字节数
+    #[derive(Default)]
+    struct CountingHasher {
+        bytes: usize,
+    }
+
+    impl std::hash::Hasher for CountingHasher {
+        fn finish(&self) -> u64 {
+            0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.bytes += bytes.len();
+        }
+    }
+
+    #[test]
+    fn test_hash_does_not_touch_name() {
+        use std::hash::Hash;
+
+        // 无论符号名多长，哈希只写入编号
+        let long_name = "a-very-long-variable-name-".repeat(100);
+        let long = Symbol::intern(&long_name);
+        let short = Symbol::intern("x");
+        for _ in 0..1000 {
+            let mut long_hasher = CountingHasher::default();
+            long.hash(&mut long_hasher);
+            let mut short_hasher = CountingHasher::default();
+            short.hash(&mut short_hasher);
+            assert_eq!(long_hasher.bytes, std::mem::size_of::<u32>());
+            assert_eq!(short_hasher.bytes, long_hasher.bytes);
+        }
+    }
+}
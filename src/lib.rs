@@ -1,36 +1,83 @@
 //! Arbores - A Scheme-compatible Lisp interpreter written in Rust
+//!
+//! The `legacy` feature (enabled by default) provides the legacy interpreter,
+//! the `Arbores` knowledge base and the REPL. Without it only the new
+//! `interpreter` module is compiled, and `eval`/`parse` are backed by it.
 
 // Legacy implementation - will be replaced by new modular design
+#[cfg(feature = "legacy")]
 pub mod legacy;
 
 // New modular implementation (under development)
 pub mod interpreter;
 
+#[cfg(feature = "legacy")]
 pub mod arbores;
 
 // Re-export commonly used types and functions from legacy
+#[cfg(feature = "legacy")]
 pub use legacy::{Value, SchemeError, Result, run_repl};
+#[cfg(feature = "legacy")]
 pub use legacy::eval::{Evaluator, EvaluationContext};
+#[cfg(feature = "legacy")]
 pub use legacy::parser::Parser;
+#[cfg(feature = "legacy")]
 pub use legacy::repl::Repl;
+#[cfg(feature = "legacy")]
 pub use legacy::types::Position;
+#[cfg(feature = "legacy")]
 pub use arbores::Arbores;
 
 // Re-export eval function with different name to avoid conflict
+#[cfg(feature = "legacy")]
 pub use legacy::eval as eval_string;
 
 // For backward compatibility, expose eval submodule through a nested module
+#[cfg(feature = "legacy")]
 pub mod eval {
     pub use crate::legacy::eval::*;
 }
 
 /// Convenience function to evaluate a Scheme expression from a string
+#[cfg(feature = "legacy")]
 pub fn eval(input: &str) -> Result<Value> {
     let evaluator = legacy::eval::Evaluator::new();
     evaluator.eval_string(input, None)
 }
 
 /// Convenience function to parse a Scheme expression from a string
+#[cfg(feature = "legacy")]
 pub fn parse(input: &str) -> Result<Value> {
     legacy::parser::Parser::parse(input)
 }
+
+/// Error returned by the convenience functions when the legacy interpreter is disabled
+#[cfg(not(feature = "legacy"))]
+#[derive(Debug)]
+pub enum Error {
+    /// The input could not be parsed
+    Parse(interpreter::ParseError),
+    /// An expression failed to evaluate
+    Evaluate(interpreter::EvaluateError),
+}
+
+/// Convenience function to evaluate Scheme source with the new interpreter,
+/// returning the value of the last expression (`()` for empty input)
+#[cfg(not(feature = "legacy"))]
+pub fn eval(input: &str) -> std::result::Result<std::rc::Rc<interpreter::RuntimeValue>, Error> {
+    let env = gc::Gc::new(interpreter::Environment::new());
+    let mut result = std::rc::Rc::new(interpreter::RuntimeValue {
+        core: interpreter::evaluator::RuntimeObjectCore::Nil,
+        source: None,
+    });
+    for expr in parse(input).map_err(Error::Parse)? {
+        result = interpreter::evaluator::evaluate(std::rc::Rc::new(expr), env.clone()).map_err(Error::Evaluate)?;
+    }
+    Ok(result)
+}
+
+/// Convenience function to parse Scheme source into expressions with the new interpreter
+#[cfg(not(feature = "legacy"))]
+pub fn parse(input: &str) -> std::result::Result<Vec<interpreter::SExpr>, interpreter::ParseError> {
+    interpreter::parse_from_string(input).result
+}
@@ -1,89 +1,78 @@
-use std::rc::Rc;
-use gc::Gc;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process;
 
-use arbores::interpreter::{
-    evaluator::{evaluate, Environment, RuntimeObject, RuntimeObjectCore},
-    tokenize,
-    parse,
-};
+use clap::Parser as CliParser;
 
-fn main() {
-    println!("Arbores 求值器测试");
-    println!("==================");
-    
-    // 测试简单的四则运算
-    test_arithmetic();
+use arbores::{Evaluator, SchemeError, Value, run_repl};
+
+/// Arbores Scheme 解释器
+#[derive(CliParser)]
+#[command(name = "arbores", version, about = "A Scheme-compatible Lisp interpreter")]
+struct Cli {
+    /// 求值表达式并打印最后一个结果
+    #[arg(short, long, value_name = "EXPR", conflicts_with = "script")]
+    eval: Option<String>,
+
+    /// 要运行的脚本文件；不提供参数时从管道读取程序或启动 REPL
+    script: Option<PathBuf>,
 }
 
-fn test_arithmetic() {
-    println!("\n测试四则运算:");
-    
-    let test_cases = vec![
-        ("(+ 1 2)", "3"),
-        ("(- 5 2)", "3"),
-        ("(* 3 4)", "12"),
-        ("(/ 10 2)", "5"),
-        ("(+ 1 2 3 4)", "10"),
-        ("(- 10 3 2)", "5"),
-        ("(* 2 3 4)", "24"),
-        ("(/ 100 2 5)", "10"),
-        ("(+ 42)", "42"),
-        ("(- 5)", "-5"),
-        ("(* 7)", "7"),
-        ("(/ 2)", "0.5"),
-        ("(+)", "0"),
-        ("(*)", "1"),
-        ("(+ 1 2.5)", "3.5"),
-        ("(* 3 2.5)", "7.5"),
-    ];
-    
-    // 创建全局环境
-    let env = Gc::new(Environment::new());
-    
-    for (input, expected) in test_cases {
-        match evaluate_expression(input, &env) {
-            Ok(result) => {
-                let result_str = format_runtime_object(&result);
-                let status = if result_str == expected { "✓" } else { "✗" };
-                println!("{} {} => {} (期望: {})", status, input, result_str, expected);
-            },
+fn main() {
+    let cli = Cli::parse();
+
+    let code = if let Some(expr) = cli.eval {
+        run_program(&expr, true)
+    } else if let Some(path) = cli.script {
+        match std::fs::read_to_string(&path) {
+            Ok(source) => run_program(&source, false),
             Err(e) => {
-                println!("✗ {} => 错误: {:?}", input, e);
+                eprintln!("Error: cannot read {}: {}", path.display(), e);
+                1
             }
         }
-    }
-}
+    } else if !atty::is(atty::Stream::Stdin) {
+        // 标准输入不是终端：读入整个程序求值并打印最后一个结果
+        let mut source = String::new();
+        match io::stdin().read_to_string(&mut source) {
+            Ok(_) => run_program(&source, true),
+            Err(e) => {
+                eprintln!("Error: cannot read stdin: {}", e);
+                1
+            }
+        }
+    } else {
+        match run_repl() {
+            Ok(()) => 0,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                1
+            }
+        }
+    };
 
-fn evaluate_expression(expr_str: &str, env: &Gc<Environment>) -> Result<Rc<RuntimeObject>, Box<dyn std::error::Error>> {
-    // 词法分析
-    let tokens = tokenize(expr_str.chars());
-    
-    // 语法分析
-    let parse_output = parse(tokens);
-    let exprs = parse_output.result?;
-    
-    if exprs.is_empty() {
-        return Err("没有解析到表达式".into());
-    }
-    
-    // 求值
-    let result = evaluate(Rc::new(exprs.remove(0)), env.clone())?;
-    Ok(result)
+    let _ = io::stdout().flush();
+    process::exit(code);
 }
 
-fn format_runtime_object(obj: &RuntimeObject) -> String {
-    match &obj.core {
-        RuntimeObjectCore::Integer(n) => n.to_string(),
-        RuntimeObjectCore::Float(n) => {
-            if n.fract() == 0.0 {
-                (*n as i64).to_string()
-            } else {
-                n.to_string()
+/// 用旧版求值器运行整个程序，返回进程退出码
+fn run_program(source: &str, print_result: bool) -> i32 {
+    let evaluator = Evaluator::new();
+
+    match evaluator.eval_all(source) {
+        Ok(values) => {
+            // define、display 等副作用形式返回未指定值，不打印
+            if let Some(last) = values.last().filter(|value| !matches!(value, Value::Unspecified)) {
+                if print_result {
+                    println!("{}", last);
+                }
             }
-        },
-        RuntimeObjectCore::String(s) => s.to_string(),
-        RuntimeObjectCore::Boolean(b) => if *b { "#t".to_string() } else { "#f".to_string() },
-        RuntimeObjectCore::Nil => "()".to_string(),
-        _ => format!("{:?}", obj.core),
+            0
+        }
+        Err(SchemeError::Exit(code)) => code,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
     }
 }
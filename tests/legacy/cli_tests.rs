@@ -1,14 +1,28 @@
 #[cfg(test)]
 mod cli_tests {
-    use std::process::Command;
     use std::io::Write;
+    use std::process::{Command, Output, Stdio};
+
+    /// 运行编译好的 arbores 可执行文件
+    fn arbores(args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_arbores"))
+            .args(args)
+            .stdin(Stdio::null())
+            .output()
+            .expect("Failed to execute arbores")
+    }
+
+    /// 写一个临时脚本文件
+    fn write_script(name: &str, source: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("arbores_cli_{}_{}.scm", name, std::process::id()));
+        let mut file = std::fs::File::create(&path).unwrap();
+        file.write_all(source.as_bytes()).unwrap();
+        path
+    }
 
     #[test]
     fn test_eval_parameter() {
-        let output = Command::new("cargo")
-            .args(&["run", "--", "-e", "(+ 1 2 3)"])
-            .output()
-            .expect("Failed to execute command");
+        let output = arbores(&["-e", "(+ 1 2 3)"]);
 
         assert!(output.status.success());
         let stdout = String::from_utf8(output.stdout).unwrap();
@@ -16,57 +30,126 @@ mod cli_tests {
     }
 
     #[test]
-    fn test_stdin_input() {
-        let mut child = Command::new("cargo")
-            .args(&["run"])
-            .stdin(std::process::Stdio::piped())
-            .stdout(std::process::Stdio::piped())
-            .spawn()
-            .expect("Failed to spawn command");
+    fn test_eval_parameter_empty_list() {
+        // 空表是普通的结果，照常打印
+        let output = arbores(&["-e", "(cdr '(1))"]);
 
-        if let Some(stdin) = child.stdin.as_mut() {
-            stdin.write_all(b"(* 4 5)\n").unwrap();
-        }
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout.trim(), "()");
+    }
+
+    #[test]
+    fn test_eval_parameter_display() {
+        let output = arbores(&["--eval", "(display (+ 1 2))"]);
 
-        let output = child.wait_with_output().unwrap();
         assert!(output.status.success());
         let stdout = String::from_utf8(output.stdout).unwrap();
-        assert_eq!(stdout.trim(), "20");
+        assert_eq!(stdout, "3");
     }
 
     #[test]
-    fn test_multiple_expressions_stdin() {
-        let mut child = Command::new("cargo")
-            .args(&["run"])
-            .stdin(std::process::Stdio::piped())
-            .stdout(std::process::Stdio::piped())
+    fn test_script_file() {
+        let path = write_script("script", "(define (square x) (* x x))\n(display (square 7))\n(newline)\n");
+        let output = arbores(&[path.to_str().unwrap()]);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout, "49\n");
+    }
+
+    #[test]
+    fn test_exit_status() {
+        let output = arbores(&["-e", "(exit 3)"]);
+        assert_eq!(output.status.code(), Some(3));
+
+        let path = write_script("exit", "(display \"before\")\n(exit #f)\n(display \"after\")\n");
+        let output = arbores(&[path.to_str().unwrap()]);
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(output.status.code(), Some(1));
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "before");
+    }
+
+    #[test]
+    fn test_file_ports() {
+        // 用 call-with-output-file 写入临时文件，再用 call-with-input-file 读回
+        let data = std::env::temp_dir().join(format!("arbores_cli_data_{}.txt", std::process::id()));
+        let data = data.to_str().unwrap();
+        let path = write_script("file_ports", &format!(
+            "(call-with-output-file {data:?} (lambda (port) (write '(1 \"two\" #\\3) port) (newline port) (display \"done\" port)))\n\
+             (define items (call-with-input-file {data:?} (lambda (port) (list (read port) (read-line port) (read-line port) (read-line port)))))\n\
+             (write items)\n"
+        ));
+        let output = arbores(&[path.to_str().unwrap()]);
+        let written = std::fs::read_to_string(data);
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(data);
+
+        assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+        assert_eq!(written.unwrap(), "(1 \"two\" #\\3)\ndone");
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "((1 \"two\" #\\3) \"\" \"done\" #<eof>)");
+    }
+
+    #[test]
+    fn test_error_status() {
+        let output = arbores(&["-e", "(car 1)"]);
+
+        assert_eq!(output.status.code(), Some(1));
+        assert!(!String::from_utf8(output.stderr).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_missing_script_file() {
+        let output = arbores(&["/nonexistent/arbores-script.scm"]);
+        assert_eq!(output.status.code(), Some(1));
+    }
+
+    /// 通过管道把程序写入标准输入运行
+    fn arbores_with_stdin(input: &[u8]) -> Output {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_arbores"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
             .spawn()
-            .expect("Failed to spawn command");
+            .expect("Failed to spawn arbores");
 
         if let Some(stdin) = child.stdin.as_mut() {
-            stdin.write_all(b"(+ 1 2)\n(* 3 4)\n").unwrap();
+            stdin.write_all(input).unwrap();
         }
 
-        let output = child.wait_with_output().unwrap();
+        child.wait_with_output().unwrap()
+    }
+
+    #[test]
+    fn test_stdin_input() {
+        let output = arbores_with_stdin(b"(* 4 5)\n");
         assert!(output.status.success());
         let stdout = String::from_utf8(output.stdout).unwrap();
-        assert_eq!(stdout.trim(), "3\n12");
+        assert_eq!(stdout.trim(), "20");
     }
 
     #[test]
-    fn test_empty_stdin() {
-        let mut child = Command::new("cargo")
-            .args(&["run"])
-            .stdin(std::process::Stdio::piped())
-            .stdout(std::process::Stdio::piped())
-            .spawn()
-            .expect("Failed to spawn command");
+    fn test_multiple_expressions_stdin() {
+        // 管道输入作为一个程序求值，只打印最后一个结果
+        let output = arbores_with_stdin(b"(+ 1 2)\n(* 3 4)\n");
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout.trim(), "12");
+    }
 
-        if let Some(stdin) = child.stdin.as_mut() {
-            stdin.write_all(b"").unwrap();
-        }
+    #[test]
+    fn test_program_stdin() {
+        let program = b"(define (fact n) (if (= n 0) 1 (* n (fact (- n 1)))))\n(display \"fact: \")\n(fact 10)\n";
+        let output = arbores_with_stdin(program);
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(stdout, "fact: 3628800\n");
+    }
 
-        let output = child.wait_with_output().unwrap();
+    #[test]
+    fn test_empty_stdin() {
+        let output = arbores_with_stdin(b"");
         assert!(output.status.success());
         let stdout = String::from_utf8(output.stdout).unwrap();
         assert_eq!(stdout.trim(), "");
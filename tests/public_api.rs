@@ -0,0 +1,36 @@
+//! 公共 API 在两种特性配置下都能编译和使用。
+//!
+//! 该测试不依赖 CI 配置，分别运行以下两条命令即可覆盖两种配置：
+//!
+//! ```text
+//! cargo test --test public_api
+//! cargo test --test public_api --no-default-features
+//! ```
+
+#[cfg(feature = "legacy")]
+#[test]
+fn test_legacy_api() {
+    use arbores::{Arbores, Evaluator, Value};
+
+    assert_eq!(arbores::eval("(+ 1 2)").unwrap(), Value::Integer(3));
+    assert_eq!(arbores::parse("(a b)").unwrap().to_string(), "(a b)");
+    assert_eq!(Evaluator::new().eval_string("(* 2 3)", None).unwrap(), Value::Integer(6));
+    let _ = Arbores::new();
+}
+
+#[cfg(not(feature = "legacy"))]
+#[test]
+fn test_interpreter_only_api() {
+    assert_eq!(arbores::eval("(+ 1 2) (* 2 3)").unwrap().to_string(), "6");
+    assert_eq!(arbores::eval("").unwrap().to_string(), "()");
+    assert!(matches!(arbores::eval("(1 2)"), Err(arbores::Error::Evaluate(_))));
+    assert!(matches!(arbores::eval("(+ 1"), Err(arbores::Error::Parse(_))));
+    assert_eq!(arbores::parse("(a b) c").unwrap().len(), 2);
+}
+
+#[test]
+fn test_interpreter_api() {
+    // 新解释器在两种配置下都可用
+    let output = arbores::interpreter::parse_from_string("(+ 1 2)");
+    assert!(output.result.is_ok());
+}